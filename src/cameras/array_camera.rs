@@ -0,0 +1,162 @@
+use math::{Matrix4, Vector4};
+use core::{HasObject3D, Object3D};
+use cameras::{Camera, PerspectiveCamera};
+
+// one of the cameras of an ArrayCamera, the viewport is x, y, width and height in pixels
+// measured from the top left of the render target like a view offset
+pub struct SubCamera {
+    pub camera: PerspectiveCamera,
+    pub viewport: Vector4,
+}
+
+// a view drawn by several sub cameras, each into its own viewport of the render target, for
+// multi screen walls or one viewport per eye
+pub struct ArrayCamera {
+    // covers everything the sub cameras see, culling and sorting use it
+    pub camera: PerspectiveCamera,
+    pub cameras: Vec<SubCamera>,
+}
+
+impl Camera for ArrayCamera {
+    fn projection_matrix(&self) -> Matrix4 {
+        self.camera.projection_matrix()
+    }
+
+    fn set_projection_matrix(&mut self, val: &Matrix4) {
+        self.camera.set_projection_matrix(val);
+    }
+
+    fn scene_object_mut(&mut self) -> &mut Object3D {
+        self.camera.scene_object_mut()
+    }
+}
+
+impl HasObject3D for ArrayCamera {
+    fn scene_object(&self) -> &Object3D {
+        self.camera.scene_object()
+    }
+}
+
+impl ArrayCamera {
+    pub fn new(camera: PerspectiveCamera) -> ArrayCamera {
+        ArrayCamera {
+            camera: camera,
+            cameras: Vec::new(),
+        }
+    }
+
+    // splits a width by height render target into columns by rows tiles, each tile gets the
+    // matching slice of camera's frustum, the last row and column take any leftover pixels.
+    // camera takes the aspect of the whole target. an empty target or grid gives no tiles
+    pub fn from_grid(camera: PerspectiveCamera,
+                     width: usize,
+                     height: usize,
+                     columns: usize,
+                     rows: usize)
+                     -> ArrayCamera {
+        let mut array = ArrayCamera::new(camera);
+        if width == 0 || height == 0 || columns == 0 || rows == 0 {
+            return array;
+        }
+
+        array.camera.aspect = width as f32 / height as f32;
+        array.camera.update_projection_matrix();
+
+        let tile_width = width / columns;
+        let tile_height = height / rows;
+
+        for row in 0..rows {
+            for column in 0..columns {
+                let x = column * tile_width;
+                let y = row * tile_height;
+                let w = if column == columns - 1 { width - x } else { tile_width };
+                let h = if row == rows - 1 { height - y } else { tile_height };
+
+                let mut tile = {
+                    let c = &array.camera;
+                    PerspectiveCamera::new(c.fov, c.aspect, Some(c.near), Some(c.far))
+                };
+                tile.zoom = array.camera.zoom;
+                tile.focus = array.camera.focus;
+                tile.film_gauge = array.camera.film_gauge;
+                tile.film_offset = array.camera.film_offset;
+                tile.set_view_offset(width, height, x as f32, y as f32, w, h);
+
+                let viewport = Vector4::new(x as f32, y as f32, w as f32, h as f32);
+                array.push(tile, &viewport);
+            }
+        }
+
+        array
+    }
+
+    pub fn push(&mut self, camera: PerspectiveCamera, viewport: &Vector4) {
+        self.cameras.push(SubCamera {
+            camera: camera,
+            viewport: *viewport,
+        });
+    }
+
+    // places the sub cameras relative to the array camera, its world matrix has to be up to date
+    pub fn update(&mut self) {
+        let matrix_world = self.camera.scene_object().matrix_world;
+        for sub in &mut self.cameras {
+            sub.camera.scene_object_mut().update_matrix_world(Some(&matrix_world), true);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cameras::{ArrayCamera, Camera, PerspectiveCamera};
+    use math::{Matrix4, Vector3, Vector4};
+
+    fn matrix_close(a: &Matrix4, b: &Matrix4) -> bool {
+        a.elements.iter().zip(b.elements.iter()).all(|(x, y)| (x - y).abs() < 1e-5)
+    }
+
+    #[test]
+    fn from_grid() {
+        let camera = PerspectiveCamera::new(90.0, 2.0, Some(1.0), Some(100.0));
+        let mut array = ArrayCamera::from_grid(camera, 201, 100, 2, 2);
+        assert_eq!(array.cameras.len(), 4);
+
+        let viewports: Vec<Vector4> = array.cameras.iter().map(|sub| sub.viewport).collect();
+        assert_eq!(viewports,
+                   vec![Vector4::new(0.0, 0.0, 100.0, 50.0),
+                        Vector4::new(100.0, 0.0, 101.0, 50.0),
+                        Vector4::new(0.0, 50.0, 100.0, 50.0),
+                        Vector4::new(100.0, 50.0, 101.0, 50.0)]);
+
+        // the tiles add up to the frustum of the whole view, which the array camera covers
+        let right = 2.0 * 201.0 / 100.0 / 2.0;
+        let split = -right + 2.0 * right * 100.0 / 201.0;
+        assert_eq!(array.camera.aspect, 2.01);
+        assert!(matrix_close(&array.projection_matrix(),
+                             &Matrix4::from_frustum(-right, right, -1.0, 1.0, 1.0, 100.0)));
+        assert!(matrix_close(&array.cameras[0].camera.projection_matrix(),
+                             &Matrix4::from_frustum(-right, split, 0.0, 1.0, 1.0, 100.0)));
+        assert!(matrix_close(&array.cameras[3].camera.projection_matrix(),
+                             &Matrix4::from_frustum(split, right, -1.0, 0.0, 1.0, 100.0)));
+
+        array.scene_object_mut().position = Vector3::new(1.0, 2.0, 3.0);
+        array.look_at(&Vector3::new(1.0, 2.0, 10.0));
+        array.scene_object_mut().update_matrix_world(None, false);
+        array.update();
+        for sub in &array.cameras {
+            assert!(sub.camera.world_position().distance_to(&Vector3::new(1.0, 2.0, 3.0)) < 1e-5);
+            assert!(sub.camera.world_direction().distance_to(&Vector3::Z) < 1e-5);
+        }
+    }
+
+    #[test]
+    fn from_empty_grid() {
+        for &(width, height, columns, rows) in &[(200, 100, 0, 2), (200, 100, 2, 0),
+                                                 (0, 100, 2, 2), (200, 0, 2, 2)] {
+            let camera = PerspectiveCamera::new(90.0, 2.0, Some(1.0), Some(100.0));
+            let array = ArrayCamera::from_grid(camera, width, height, columns, rows);
+            assert!(array.cameras.is_empty());
+            assert_eq!(array.camera.aspect, 2.0);
+        }
+    }
+}
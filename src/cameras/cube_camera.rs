@@ -0,0 +1,142 @@
+use math::Vector3;
+use core::{HasObject3D, Object3D};
+use cameras::{Camera, PerspectiveCamera};
+
+// six 90 degree cameras looking down the axes, for rendering the faces of a cube map. faces are
+// in the +x, -x, +y, -y, +z, -z order and use the fov of -90 and the up vectors of the three.js
+// r160 CubeCamera with the WebGL coordinate system, so the view and projection of each face
+// match three.js. the negative fov flips x and y in the projection, which the up vectors
+// undo. drawing the faces into a cube render target is left to the caller, the crate has no
+// renderer or render targets yet
+pub struct CubeCamera {
+    scene_object: Object3D,
+    pub cameras: [PerspectiveCamera; 6],
+}
+
+impl HasObject3D for CubeCamera {
+    fn scene_object(&self) -> &Object3D {
+        &self.scene_object
+    }
+}
+
+impl CubeCamera {
+    // the direction and up vector of every face
+    pub const FACES: [(Vector3, Vector3); 6] = [(Vector3::X, Vector3::Y),
+                                                (Vector3::NEG_X, Vector3::Y),
+                                                (Vector3::Y, Vector3::NEG_Z),
+                                                (Vector3::NEG_Y, Vector3::Z),
+                                                (Vector3::Z, Vector3::Y),
+                                                (Vector3::NEG_Z, Vector3::Y)];
+
+    pub const FOV: f32 = -90.0;
+
+    pub fn new(near: Option<f32>, far: Option<f32>) -> CubeCamera {
+        let face = |i: usize| {
+            let (direction, up) = CubeCamera::FACES[i];
+            let mut camera = PerspectiveCamera::new(CubeCamera::FOV, 1.0, near, far);
+            camera.scene_object_mut().up = up;
+            camera.look_at(&direction);
+            camera
+        };
+
+        let mut cube = CubeCamera {
+            scene_object: Object3D::next(),
+            cameras: [face(0), face(1), face(2), face(3), face(4), face(5)],
+        };
+
+        cube.update();
+        cube
+    }
+
+    pub fn scene_object_mut(&mut self) -> &mut Object3D {
+        &mut self.scene_object
+    }
+
+    // places the face cameras relative to the cube camera, its world matrix has to be up to date
+    pub fn update(&mut self) {
+        let matrix_world = self.scene_object.matrix_world;
+        for camera in &mut self.cameras {
+            camera.scene_object_mut().update_matrix_world(Some(&matrix_world), true);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cameras::{Camera, CubeCamera};
+    use core::HasObject3D;
+    use math::{Matrix4, Vector3};
+
+    fn matrix_close(a: &Matrix4, b: &Matrix4) -> bool {
+        a.elements.iter().zip(b.elements.iter()).all(|(x, y)| (x - y).abs() < 1e-5)
+    }
+
+    fn close(a: &Vector3, b: &Vector3) -> bool {
+        a.distance_to(b) < 1e-5
+    }
+
+    // the world corner each face draws in its top right, following the cube map layout
+    fn top_right_corners() -> [Vector3; 6] {
+        [Vector3::new(1.0, -1.0, -1.0),
+         Vector3::new(-1.0, -1.0, 1.0),
+         Vector3::new(1.0, 1.0, 1.0),
+         Vector3::new(1.0, -1.0, -1.0),
+         Vector3::new(1.0, -1.0, 1.0),
+         Vector3::new(-1.0, -1.0, -1.0)]
+    }
+
+    #[test]
+    fn faces() {
+        let mut cube = CubeCamera::new(Some(0.1), Some(10.0));
+        cube.scene_object_mut().position = Vector3::new(5.0, 0.0, 0.0);
+        cube.scene_object_mut().update_matrix_world(None, false);
+        cube.update();
+
+        let center = Vector3::new(5.0, 0.0, 0.0);
+        let corners = top_right_corners();
+        for (i, camera) in cube.cameras.iter().enumerate() {
+            let (direction, _) = CubeCamera::FACES[i];
+            assert!(close(&camera.world_position(), &center));
+            assert!(close(&camera.world_direction(), &direction));
+
+            let view = camera.scene_object().matrix_world.inverse();
+            let corner = corners[i].add(&center)
+                .apply_matrix4(&view)
+                .apply_projection(&camera.projection_matrix());
+            assert!((corner.x - 1.0).abs() < 1e-5);
+            assert!((corner.y - 1.0).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn three_reference() {
+        // matrixWorldInverse and projectionMatrix elements of the faces of a three.js r160
+        // new CubeCamera(0.1, 10) sitting at the origin, worked out from Matrix4.lookAt and
+        // Matrix4.makePerspective
+        let views = [[0.0, 0.0, -1.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0,
+                      0.0, 1.0],
+                     [0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 0.0, 0.0, -1.0, 0.0, 0.0, 0.0, 0.0, 0.0,
+                      0.0, 1.0],
+                     [-1.0, 0.0, 0.0, 0.0, 0.0, 0.0, -1.0, 0.0, 0.0, -1.0, 0.0, 0.0, 0.0, 0.0,
+                      0.0, 1.0],
+                     [-1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0,
+                      0.0, 1.0],
+                     [-1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, -1.0, 0.0, 0.0, 0.0,
+                      0.0, 1.0],
+                     [1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0,
+                      0.0, 1.0]];
+        let c = -10.1 / 9.9;
+        let d = -2.0 / 9.9;
+        let projection = Matrix4 {
+            elements: [-1.0, 0.0, 0.0, 0.0, 0.0, -1.0, 0.0, 0.0, 0.0, 0.0, c, -1.0, 0.0, 0.0, d,
+                       0.0],
+        };
+
+        let cube = CubeCamera::new(Some(0.1), Some(10.0));
+        for (i, camera) in cube.cameras.iter().enumerate() {
+            let view = camera.scene_object().matrix_world.inverse();
+            assert!(matrix_close(&view, &Matrix4 { elements: views[i] }));
+            assert!(matrix_close(&camera.projection_matrix(), &projection));
+        }
+    }
+}
@@ -1,22 +1,34 @@
+mod array_camera;
+mod cube_camera;
+mod perspective_camera;
+mod stereo_camera;
+
 use math::{Matrix4, Vector3};
 use core::{HasObject3D, Object3D};
 
+pub use self::array_camera::{ArrayCamera, SubCamera};
+pub use self::cube_camera::CubeCamera;
+pub use self::perspective_camera::PerspectiveCamera;
+pub use self::stereo_camera::StereoCamera;
+
 pub trait Camera: HasObject3D {
     fn projection_matrix(&self) -> Matrix4;
     fn set_projection_matrix(&mut self, val: &Matrix4);
+    fn scene_object_mut(&mut self) -> &mut Object3D;
 
+    fn world_position(&self) -> Vector3 {
+        self.scene_object().world_position()
+    }
+
+    // cameras look down their local -z
     fn world_direction(&self) -> Vector3 {
         let q = self.scene_object().world_quaternion();
-        Vector3::new(0.0, 0.0, -1.0).apply_quaternion(&q)
+        Vector3::NEG_Z.apply_quaternion(&q)
     }
 
-    // fn look_at(&mut self, vector: &Vector3) {
-    fn look_at(&mut self) {
-        unimplemented!();
-        // should be doing self... learning best way to do traits;
-        // let so = self.scene_object();
-        // let m = Matrix4::IDENTITY.look_at(so.position, vector, so.up);
-        // self.scene_object.quaternion = Quaternion::from_rotation_matrix(&m);
+    // see Object3D::look_at
+    fn look_at(&mut self, target: &Vector3) {
+        self.scene_object_mut().look_at_negative_z(target);
     }
 }
 
@@ -24,7 +36,7 @@ pub struct OrthographicCamera {
     scene_object: Object3D,
     projection_matrix: Matrix4,
     pub zoom: f32,
-    pub view: Option<OrthographicView>,
+    pub view: Option<CameraView>,
     pub left: f32,
     pub right: f32,
     pub top: f32,
@@ -41,6 +53,10 @@ impl Camera for OrthographicCamera {
     fn set_projection_matrix(&mut self, val: &Matrix4) {
         self.projection_matrix = *val;
     }
+
+    fn scene_object_mut(&mut self) -> &mut Object3D {
+        &mut self.scene_object
+    }
 }
 
 impl HasObject3D for OrthographicCamera {
@@ -49,7 +65,8 @@ impl HasObject3D for OrthographicCamera {
     }
 }
 
-pub struct OrthographicView {
+// a window into a larger view, for rendering one tile of a multi screen setup
+pub struct CameraView {
     pub full_width: usize,
     pub full_height: usize,
     pub offset_x: f32,
@@ -91,7 +108,7 @@ impl OrthographicCamera {
                            width: usize,
                            height: usize) {
 
-        self.view = Some(OrthographicView {
+        self.view = Some(CameraView {
             full_width: full_width,
             full_height: full_height,
             offset_x: x,
@@ -134,4 +151,54 @@ impl OrthographicCamera {
         let m = Matrix4::from_orthographic(left, right, top, bottom, self.near, self.far);
         self.set_projection_matrix(&m);
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+    use cameras::{Camera, OrthographicCamera, PerspectiveCamera};
+    use math::{Matrix4, Vector3};
+
+    fn close(a: &Vector3, b: &Vector3) -> bool {
+        a.distance_to(b) < 1e-5
+    }
+
+    #[test]
+    fn look_at() {
+        let mut camera = PerspectiveCamera::new(50.0, 1.0, None, None);
+        assert_eq!(camera.world_direction(), Vector3::NEG_Z);
+
+        camera.scene_object_mut().position = Vector3::new(0.0, 0.0, 10.0);
+        camera.look_at(&Vector3::new(10.0, 0.0, 10.0));
+        assert!(close(&camera.world_direction(), &Vector3::X));
+        assert!(close(&camera.world_position(), &Vector3::new(0.0, 0.0, 10.0)));
+
+        camera.look_at(&Vector3::new(3.0, 3.0, 14.0));
+        assert!(close(&camera.world_direction(), &Vector3::new(3.0, 3.0, 4.0).normalized()));
+    }
+
+    #[test]
+    fn look_at_under_parent() {
+        // the parent turns local +z to world +x and moves everything 5 along x
+        let parent = Matrix4::from_translation(&Vector3::new(5.0, 0.0, 0.0))
+            .multiply(&Matrix4::from_rotation_y(PI / 2.0));
+        let mut camera = OrthographicCamera::new(-1.0, 1.0, 1.0, -1.0, None, None);
+        camera.scene_object_mut().position = Vector3::new(0.0, 0.0, 2.0);
+        camera.scene_object_mut().update_matrix_world(Some(&parent), false);
+        assert!(close(&camera.world_position(), &Vector3::new(7.0, 0.0, 0.0)));
+        assert!(close(&camera.world_direction(), &Vector3::NEG_X));
+
+        camera.look_at(&Vector3::new(7.0, 0.0, -5.0));
+        assert!(close(&camera.world_direction(), &Vector3::NEG_Z));
+        assert!(close(&camera.world_position(), &Vector3::new(7.0, 0.0, 0.0)));
+
+        camera.look_at(&Vector3::new(10.0, 4.0, 0.0));
+        assert!(close(&camera.world_direction(), &Vector3::new(0.6, 0.8, 0.0)));
+
+        // the parent keeps applying after the camera moves on its own
+        camera.scene_object_mut().position = Vector3::ZERO;
+        camera.look_at(&Vector3::new(5.0, 0.0, 5.0));
+        assert!(close(&camera.world_position(), &Vector3::new(5.0, 0.0, 0.0)));
+        assert!(close(&camera.world_direction(), &Vector3::Z));
+    }
+}
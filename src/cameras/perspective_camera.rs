@@ -0,0 +1,211 @@
+use math::Matrix4;
+use core::{HasObject3D, Object3D};
+use cameras::{Camera, CameraView};
+
+pub struct PerspectiveCamera {
+    scene_object: Object3D,
+    projection_matrix: Matrix4,
+    // vertical field of view in degrees
+    pub fov: f32,
+    pub zoom: f32,
+    pub near: f32,
+    pub far: f32,
+    // distance to the plane in focus, for stereo and depth of field
+    pub focus: f32,
+    pub aspect: f32,
+    pub view: Option<CameraView>,
+    // height of the film in millimeters when it's landscape, 35 is a full frame camera
+    pub film_gauge: f32,
+    // horizontal shift of the film in millimeters
+    pub film_offset: f32,
+}
+
+impl Camera for PerspectiveCamera {
+    fn projection_matrix(&self) -> Matrix4 {
+        self.projection_matrix
+    }
+
+    fn set_projection_matrix(&mut self, val: &Matrix4) {
+        self.projection_matrix = *val;
+    }
+
+    fn scene_object_mut(&mut self) -> &mut Object3D {
+        &mut self.scene_object
+    }
+}
+
+impl HasObject3D for PerspectiveCamera {
+    fn scene_object(&self) -> &Object3D {
+        &self.scene_object
+    }
+}
+
+impl PerspectiveCamera {
+    pub fn new(fov: f32, aspect: f32, near: Option<f32>, far: Option<f32>) -> PerspectiveCamera {
+        let mut perspective = PerspectiveCamera {
+            scene_object: Object3D::next(),
+            projection_matrix: Matrix4::IDENTITY,
+            fov: fov,
+            zoom: 1.0,
+            near: near.unwrap_or(0.1),
+            far: far.unwrap_or(2000.0),
+            focus: 10.0,
+            aspect: aspect,
+            view: None,
+            film_gauge: 35.0,
+            film_offset: 0.0,
+        };
+
+        perspective.update_projection_matrix();
+        perspective
+    }
+
+    // sets the fov from the focal length, in millimeters, of a lens on the film
+    pub fn set_focal_length(&mut self, focal_length: f32) {
+        let v_extent_slope = 0.5 * self.film_height() / focal_length;
+        self.fov = (2.0 * v_extent_slope.atan()).to_degrees();
+        self.update_projection_matrix();
+    }
+
+    pub fn focal_length(&self) -> f32 {
+        let v_extent_slope = (self.fov.to_radians() * 0.5).tan();
+        0.5 * self.film_height() / v_extent_slope
+    }
+
+    // the fov once zoom is applied
+    pub fn effective_fov(&self) -> f32 {
+        (2.0 * ((self.fov.to_radians() * 0.5).tan() / self.zoom).atan()).to_degrees()
+    }
+
+    pub fn film_width(&self) -> f32 {
+        self.film_gauge * self.aspect.min(1.0)
+    }
+
+    pub fn film_height(&self) -> f32 {
+        self.film_gauge / self.aspect.max(1.0)
+    }
+
+    // renders the part of a full_width by full_height view that starts at x, y and is width by
+    // height, the aspect becomes that of the full view
+    pub fn set_view_offset(&mut self,
+                           full_width: usize,
+                           full_height: usize,
+                           x: f32,
+                           y: f32,
+                           width: usize,
+                           height: usize) {
+        self.aspect = full_width as f32 / full_height as f32;
+        self.view = Some(CameraView {
+            full_width: full_width,
+            full_height: full_height,
+            offset_x: x,
+            offset_y: y,
+            width: width,
+            height: height,
+        });
+
+        self.update_projection_matrix();
+    }
+
+    pub fn clear_view_offset(&mut self) {
+        self.view = None;
+        self.update_projection_matrix();
+    }
+
+    pub fn update_projection_matrix(&mut self) {
+        let near = self.near;
+        let mut top = near * (self.fov.to_radians() * 0.5).tan() / self.zoom;
+        let mut height = 2.0 * top;
+        let mut width = self.aspect * height;
+        let mut left = -0.5 * width;
+
+        if let Some(ref view) = self.view {
+            let full_width = view.full_width as f32;
+            let full_height = view.full_height as f32;
+
+            left += view.offset_x * width / full_width;
+            top -= view.offset_y * height / full_height;
+            width *= view.width as f32 / full_width;
+            height *= view.height as f32 / full_height;
+        }
+
+        if self.film_offset != 0.0 {
+            left += near * self.film_offset / self.film_width();
+        }
+
+        let m = Matrix4::from_frustum(left, left + width, top - height, top, near, self.far);
+        self.set_projection_matrix(&m);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cameras::{Camera, PerspectiveCamera};
+    use math::{Matrix4, Vector3};
+
+    fn matrix_close(a: &Matrix4, b: &Matrix4) -> bool {
+        a.elements.iter().zip(b.elements.iter()).all(|(x, y)| (x - y).abs() < 1e-5)
+    }
+
+    #[test]
+    fn projection_matrix() {
+        let camera = PerspectiveCamera::new(90.0, 2.0, Some(1.0), Some(100.0));
+        assert!(matrix_close(&camera.projection_matrix(),
+                             &Matrix4::from_frustum(-2.0, 2.0, -1.0, 1.0, 1.0, 100.0)));
+        assert!(matrix_close(&camera.projection_matrix(),
+                             &Matrix4::from_perspective(90.0, 2.0, 1.0, 100.0)));
+
+        // the top right corner of the near plane lands on the corner of clip space
+        let corner = Vector3::new(2.0, 1.0, -1.0).apply_projection(&camera.projection_matrix());
+        assert!(corner.distance_to(&Vector3::new(1.0, 1.0, -1.0)) < 1e-5);
+
+        let mut zoomed = PerspectiveCamera::new(90.0, 2.0, Some(1.0), Some(100.0));
+        zoomed.zoom = 2.0;
+        zoomed.update_projection_matrix();
+        assert!(matrix_close(&zoomed.projection_matrix(),
+                             &Matrix4::from_frustum(-1.0, 1.0, -0.5, 0.5, 1.0, 100.0)));
+        assert!((zoomed.effective_fov() - 2.0 * 0.5f32.atan().to_degrees()).abs() < 1e-4);
+    }
+
+    #[test]
+    fn focal_length() {
+        let mut camera = PerspectiveCamera::new(50.0, 1.5, None, None);
+        assert_eq!(camera.film_width(), 35.0);
+        assert!((camera.film_height() - 35.0 / 1.5).abs() < 1e-5);
+
+        camera.set_focal_length(50.0);
+        assert!((camera.focal_length() - 50.0).abs() < 1e-3);
+        let expected = (2.0 * (0.5 * 35.0 / 1.5 / 50.0f32).atan()).to_degrees();
+        assert!((camera.fov - expected).abs() < 1e-4);
+
+        // a longer lens narrows the view
+        camera.set_focal_length(100.0);
+        assert!(camera.fov < expected);
+    }
+
+    #[test]
+    fn set_view_offset() {
+        // the right half of a 2 wide view, split into two tiles
+        let mut camera = PerspectiveCamera::new(90.0, 1.0, Some(1.0), Some(100.0));
+        camera.set_view_offset(200, 100, 100.0, 0.0, 100, 100);
+        assert_eq!(camera.aspect, 2.0);
+        assert!(matrix_close(&camera.projection_matrix(),
+                             &Matrix4::from_frustum(0.0, 2.0, -1.0, 1.0, 1.0, 100.0)));
+
+        // the bottom left quarter
+        camera.set_view_offset(200, 100, 0.0, 50.0, 100, 50);
+        assert!(matrix_close(&camera.projection_matrix(),
+                             &Matrix4::from_frustum(-2.0, 0.0, -1.0, 0.0, 1.0, 100.0)));
+
+        camera.clear_view_offset();
+        assert!(camera.view.is_none());
+        assert!(matrix_close(&camera.projection_matrix(),
+                             &Matrix4::from_frustum(-2.0, 2.0, -1.0, 1.0, 1.0, 100.0)));
+
+        // shifting the film moves the frustum sideways without turning the camera
+        camera.film_offset = 35.0;
+        camera.update_projection_matrix();
+        assert!(matrix_close(&camera.projection_matrix(),
+                             &Matrix4::from_frustum(-1.0, 3.0, -1.0, 1.0, 1.0, 100.0)));
+    }
+}
@@ -0,0 +1,117 @@
+use core::HasObject3D;
+use cameras::{Camera, PerspectiveCamera};
+
+// a pair of eye cameras that follow a perspective camera, each eye gets an off axis frustum
+// so both converge on the camera's focus distance, see
+// http://paulbourke.net/stereographics/stereorender/
+pub struct StereoCamera {
+    // multiplies the aspect of the followed camera, 0.5 for side by side halves
+    pub aspect: f32,
+    // distance between the eyes in world units
+    pub eye_separation: f32,
+    pub left: PerspectiveCamera,
+    pub right: PerspectiveCamera,
+}
+
+impl StereoCamera {
+    pub fn new() -> StereoCamera {
+        let mut left = PerspectiveCamera::new(50.0, 1.0, None, None);
+        let mut right = PerspectiveCamera::new(50.0, 1.0, None, None);
+        left.scene_object_mut().layers.enable(1);
+        right.scene_object_mut().layers.enable(2);
+
+        StereoCamera {
+            aspect: 1.0,
+            eye_separation: 0.064,
+            left: left,
+            right: right,
+        }
+    }
+
+    // copies the projection and world matrix of camera into both eyes, camera's world matrix
+    // has to be up to date
+    pub fn update(&mut self, camera: &PerspectiveCamera) {
+        let aspect = camera.aspect * self.aspect;
+        let near = camera.near;
+        let eye_separation_half = self.eye_separation / 2.0;
+        let eye_separation_on_projection = eye_separation_half * near / camera.focus;
+        let ymax = near * (camera.fov.to_radians() * 0.5).tan() / camera.zoom;
+
+        // only the horizontal terms change, anything else like view offsets carries over
+        let mut projection_matrix = camera.projection_matrix();
+
+        let xmin = -ymax * aspect + eye_separation_on_projection;
+        let xmax = ymax * aspect + eye_separation_on_projection;
+        projection_matrix.elements[0] = 2.0 * near / (xmax - xmin);
+        projection_matrix.elements[8] = (xmax + xmin) / (xmax - xmin);
+        self.left.set_projection_matrix(&projection_matrix);
+
+        let xmin = -ymax * aspect - eye_separation_on_projection;
+        let xmax = ymax * aspect - eye_separation_on_projection;
+        projection_matrix.elements[0] = 2.0 * near / (xmax - xmin);
+        projection_matrix.elements[8] = (xmax + xmin) / (xmax - xmin);
+        self.right.set_projection_matrix(&projection_matrix);
+
+        let matrix_world = camera.scene_object().matrix_world;
+        {
+            let left = self.left.scene_object_mut();
+            left.position.x = -eye_separation_half;
+            left.update_matrix_world(Some(&matrix_world), true);
+        }
+        {
+            let right = self.right.scene_object_mut();
+            right.position.x = eye_separation_half;
+            right.update_matrix_world(Some(&matrix_world), true);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+    use cameras::{Camera, PerspectiveCamera, StereoCamera};
+    use core::HasObject3D;
+    use math::{Matrix4, Vector3};
+
+    fn matrix_close(a: &Matrix4, b: &Matrix4) -> bool {
+        a.elements.iter().zip(b.elements.iter()).all(|(x, y)| (x - y).abs() < 1e-5)
+    }
+
+    #[test]
+    fn update() {
+        let mut camera = PerspectiveCamera::new(90.0, 1.0, Some(1.0), Some(100.0));
+        camera.scene_object_mut().position = Vector3::new(0.0, 2.0, 0.0);
+        camera.scene_object_mut().set_rotation_from_axis_angle(&Vector3::Y, PI / 2.0);
+        camera.scene_object_mut().update_matrix_world(None, false);
+
+        let mut stereo = StereoCamera::new();
+        stereo.update(&camera);
+
+        // the frustum of each eye leans toward the other by half the separation at the focus
+        let shift = 0.032 / 10.0;
+        assert!(matrix_close(&stereo.left.projection_matrix(),
+                             &Matrix4::from_frustum(-1.0 + shift, 1.0 + shift, -1.0, 1.0, 1.0,
+                                                    100.0)));
+        assert!(matrix_close(&stereo.right.projection_matrix(),
+                             &Matrix4::from_frustum(-1.0 - shift, 1.0 - shift, -1.0, 1.0, 1.0,
+                                                    100.0)));
+
+        // the camera looks down world -x, so its right is world -z
+        assert!(stereo.left.world_position().distance_to(&Vector3::new(0.0, 2.0, 0.032)) < 1e-5);
+        assert!(stereo.right.world_position().distance_to(&Vector3::new(0.0, 2.0, -0.032)) <
+                1e-5);
+        assert!(stereo.left.world_direction().distance_to(&Vector3::NEG_X) < 1e-5);
+
+        // a point on the focus plane straight ahead lands in the same spot for both eyes
+        let focus = Vector3::new(-10.0, 2.0, 0.0);
+        for eye in &[&stereo.left, &stereo.right] {
+            let view = eye.scene_object().matrix_world.inverse();
+            let p = focus.apply_matrix4(&view).apply_projection(&eye.projection_matrix());
+            assert!(p.x.abs() < 1e-5);
+            assert!(p.y.abs() < 1e-5);
+        }
+
+        assert!(stereo.left.scene_object().layers.is_enabled(1));
+        assert!(stereo.right.scene_object().layers.is_enabled(2));
+    }
+}
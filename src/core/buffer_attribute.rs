@@ -0,0 +1,250 @@
+use math::{Vector2, Vector3};
+
+#[derive(Debug,PartialEq,Clone)]
+pub enum BufferArray {
+    F32(Vec<f32>),
+    I8(Vec<i8>),
+    U8(Vec<u8>),
+    I16(Vec<i16>),
+    U16(Vec<u16>),
+    I32(Vec<i32>),
+    U32(Vec<u32>),
+}
+
+impl BufferArray {
+    pub fn len(&self) -> usize {
+        match *self {
+            BufferArray::F32(ref a) => a.len(),
+            BufferArray::I8(ref a) => a.len(),
+            BufferArray::U8(ref a) => a.len(),
+            BufferArray::I16(ref a) => a.len(),
+            BufferArray::U16(ref a) => a.len(),
+            BufferArray::I32(ref a) => a.len(),
+            BufferArray::U32(ref a) => a.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // raw value, no normalization applied
+    pub fn get(&self, index: usize) -> f32 {
+        match *self {
+            BufferArray::F32(ref a) => a[index],
+            BufferArray::I8(ref a) => a[index] as f32,
+            BufferArray::U8(ref a) => a[index] as f32,
+            BufferArray::I16(ref a) => a[index] as f32,
+            BufferArray::U16(ref a) => a[index] as f32,
+            BufferArray::I32(ref a) => a[index] as f32,
+            BufferArray::U32(ref a) => a[index] as f32,
+        }
+    }
+
+    pub fn set(&mut self, index: usize, value: f32) {
+        match *self {
+            BufferArray::F32(ref mut a) => a[index] = value,
+            BufferArray::I8(ref mut a) => a[index] = value.round() as i8,
+            BufferArray::U8(ref mut a) => a[index] = value.round() as u8,
+            BufferArray::I16(ref mut a) => a[index] = value.round() as i16,
+            BufferArray::U16(ref mut a) => a[index] = value.round() as u16,
+            BufferArray::I32(ref mut a) => a[index] = value.round() as i32,
+            BufferArray::U32(ref mut a) => a[index] = value.round() as u32,
+        }
+    }
+
+    // appends a copy of the len values starting at start
+    pub fn extend_from_range(&mut self, start: usize, len: usize) {
+        match *self {
+            BufferArray::F32(ref mut a) => extend_from_range(a, start, len),
+            BufferArray::I8(ref mut a) => extend_from_range(a, start, len),
+            BufferArray::U8(ref mut a) => extend_from_range(a, start, len),
+            BufferArray::I16(ref mut a) => extend_from_range(a, start, len),
+            BufferArray::U16(ref mut a) => extend_from_range(a, start, len),
+            BufferArray::I32(ref mut a) => extend_from_range(a, start, len),
+            BufferArray::U32(ref mut a) => extend_from_range(a, start, len),
+        }
+    }
+
+    // maps a stored integer to [0, 1] (unsigned) or [-1, 1] (signed)
+    pub fn denormalize(&self, value: f32) -> f32 {
+        match *self {
+            BufferArray::F32(_) => value,
+            BufferArray::I8(_) => (value / 127.0).max(-1.0),
+            BufferArray::U8(_) => value / 255.0,
+            BufferArray::I16(_) => (value / 32767.0).max(-1.0),
+            BufferArray::U16(_) => value / 65535.0,
+            BufferArray::I32(_) => (value / 2147483647.0).max(-1.0),
+            BufferArray::U32(_) => value / 4294967295.0,
+        }
+    }
+
+    pub fn normalize(&self, value: f32) -> f32 {
+        match *self {
+            BufferArray::F32(_) => value,
+            BufferArray::I8(_) => value * 127.0,
+            BufferArray::U8(_) => value * 255.0,
+            BufferArray::I16(_) => value * 32767.0,
+            BufferArray::U16(_) => value * 65535.0,
+            BufferArray::I32(_) => value * 2147483647.0,
+            BufferArray::U32(_) => value * 4294967295.0,
+        }
+    }
+}
+
+// common accessor over plain and interleaved attributes, values are read and written
+// already (de)normalized
+pub trait VertexAttribute {
+    fn count(&self) -> usize;
+    fn item_size(&self) -> usize;
+    fn component(&self, index: usize, component: usize) -> f32;
+    fn set_component(&mut self, index: usize, component: usize, value: f32);
+
+    fn x(&self, index: usize) -> f32 {
+        self.component(index, 0)
+    }
+
+    fn y(&self, index: usize) -> f32 {
+        self.component(index, 1)
+    }
+
+    fn z(&self, index: usize) -> f32 {
+        self.component(index, 2)
+    }
+
+    fn w(&self, index: usize) -> f32 {
+        self.component(index, 3)
+    }
+
+    fn vector2(&self, index: usize) -> Vector2 {
+        Vector2::new(self.x(index), self.y(index))
+    }
+
+    fn vector3(&self, index: usize) -> Vector3 {
+        Vector3::new(self.x(index), self.y(index), self.z(index))
+    }
+
+    fn set_vector2(&mut self, index: usize, v: &Vector2) {
+        self.set_component(index, 0, v.x);
+        self.set_component(index, 1, v.y);
+    }
+
+    fn set_vector3(&mut self, index: usize, v: &Vector3) {
+        self.set_component(index, 0, v.x);
+        self.set_component(index, 1, v.y);
+        self.set_component(index, 2, v.z);
+    }
+}
+
+fn extend_from_range<T: Copy>(array: &mut Vec<T>, start: usize, len: usize) {
+    let copy = array[start..start + len].to_vec();
+    array.extend(copy);
+}
+
+#[derive(Debug,PartialEq,Clone)]
+pub struct BufferAttribute {
+    pub array: BufferArray,
+    pub item_size: usize,
+    pub normalized: bool,
+}
+
+impl BufferAttribute {
+    pub fn new(array: BufferArray, item_size: usize, normalized: bool) -> BufferAttribute {
+        if item_size == 0 {
+            panic!("BufferAttribute item size must be greater than zero.");
+        }
+
+        BufferAttribute {
+            array: array,
+            item_size: item_size,
+            normalized: normalized,
+        }
+    }
+
+    pub fn from_f32(array: Vec<f32>, item_size: usize) -> BufferAttribute {
+        BufferAttribute::new(BufferArray::F32(array), item_size, false)
+    }
+
+    pub fn from_vector2s(vectors: &[Vector2]) -> BufferAttribute {
+        let array = vectors.iter().flat_map(|v| v.to_vec()).collect();
+        BufferAttribute::from_f32(array, 2)
+    }
+
+    pub fn from_vector3s(vectors: &[Vector3]) -> BufferAttribute {
+        let array = vectors.iter().flat_map(|v| v.to_vec()).collect();
+        BufferAttribute::from_f32(array, 3)
+    }
+}
+
+impl VertexAttribute for BufferAttribute {
+    fn count(&self) -> usize {
+        self.array.len() / self.item_size
+    }
+
+    fn item_size(&self) -> usize {
+        self.item_size
+    }
+
+    fn component(&self, index: usize, component: usize) -> f32 {
+        let value = self.array.get(index * self.item_size + component);
+
+        if self.normalized {
+            self.array.denormalize(value)
+        } else {
+            value
+        }
+    }
+
+    fn set_component(&mut self, index: usize, component: usize, value: f32) {
+        let stored = if self.normalized {
+            self.array.normalize(value)
+        } else {
+            value
+        };
+
+        self.array.set(index * self.item_size + component, stored);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::{BufferArray, BufferAttribute, VertexAttribute};
+    use math::Vector3;
+
+    #[test]
+    fn count() {
+        let a = BufferAttribute::from_f32(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], 3);
+        assert_eq!(a.count(), 2);
+        assert_eq!(a.vector3(1), Vector3::new(4.0, 5.0, 6.0));
+
+        let b = BufferAttribute::from_f32(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], 2);
+        assert_eq!(b.count(), 3);
+        assert_eq!(b.y(2), 6.0);
+    }
+
+    #[test]
+    fn normalized() {
+        let mut a = BufferAttribute::new(BufferArray::U8(vec![0, 255, 51]), 3, true);
+        assert_eq!(a.x(0), 0.0);
+        assert_eq!(a.y(0), 1.0);
+        assert_eq!(a.z(0), 0.2);
+
+        a.set_component(0, 0, 0.5);
+        assert_eq!(a.array, BufferArray::U8(vec![128, 255, 51]));
+
+        let b = BufferAttribute::new(BufferArray::I8(vec![-128, 127]), 2, true);
+        assert_eq!(b.x(0), -1.0);
+        assert_eq!(b.y(0), 1.0);
+
+        let c = BufferAttribute::new(BufferArray::U8(vec![0, 255]), 2, false);
+        assert_eq!(c.y(0), 255.0);
+    }
+
+    #[test]
+    fn set_vector3() {
+        let mut a = BufferAttribute::from_f32(vec![0.0; 6], 3);
+        a.set_vector3(1, &Vector3::new(1.0, 2.0, 3.0));
+        assert_eq!(a.array,
+                   BufferArray::F32(vec![0.0, 0.0, 0.0, 1.0, 2.0, 3.0]));
+    }
+}
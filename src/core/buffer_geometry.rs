@@ -0,0 +1,863 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::f32::consts::PI;
+use std::rc::Rc;
+
+use core::{BufferAttribute, InterleavedBuffer, InterleavedBufferAttribute, MeshBvh,
+           VertexAttribute};
+use math::{Box3, Sphere, Triangle, Vector3};
+
+#[derive(Debug,PartialEq,Eq,Hash,Clone)]
+pub enum AttributeName {
+    Position,
+    Normal,
+    Uv,
+    Color,
+    Tangent,
+    Custom(String),
+}
+
+#[derive(Debug,PartialEq,Clone)]
+pub enum GeometryAttribute {
+    Buffer(BufferAttribute),
+    Interleaved(InterleavedBufferAttribute),
+}
+
+impl VertexAttribute for GeometryAttribute {
+    fn count(&self) -> usize {
+        match *self {
+            GeometryAttribute::Buffer(ref a) => a.count(),
+            GeometryAttribute::Interleaved(ref a) => a.count(),
+        }
+    }
+
+    fn item_size(&self) -> usize {
+        match *self {
+            GeometryAttribute::Buffer(ref a) => a.item_size,
+            GeometryAttribute::Interleaved(ref a) => a.item_size,
+        }
+    }
+
+    fn component(&self, index: usize, component: usize) -> f32 {
+        match *self {
+            GeometryAttribute::Buffer(ref a) => a.component(index, component),
+            GeometryAttribute::Interleaved(ref a) => a.component(index, component),
+        }
+    }
+
+    fn set_component(&mut self, index: usize, component: usize, value: f32) {
+        match *self {
+            GeometryAttribute::Buffer(ref mut a) => a.set_component(index, component, value),
+            GeometryAttribute::Interleaved(ref mut a) => {
+                a.set_component(index, component, value)
+            }
+        }
+    }
+}
+
+impl From<BufferAttribute> for GeometryAttribute {
+    fn from(attribute: BufferAttribute) -> GeometryAttribute {
+        GeometryAttribute::Buffer(attribute)
+    }
+}
+
+impl From<InterleavedBufferAttribute> for GeometryAttribute {
+    fn from(attribute: InterleavedBufferAttribute) -> GeometryAttribute {
+        GeometryAttribute::Interleaved(attribute)
+    }
+}
+
+#[derive(Debug,PartialEq,Clone)]
+pub enum Index {
+    U16(Vec<u16>),
+    U32(Vec<u32>),
+}
+
+impl Index {
+    // picks the smallest index type able to address every vertex
+    pub fn from_vec(indices: &[usize]) -> Index {
+        let max = indices.iter().cloned().max().unwrap_or(0);
+
+        if max < 65535 {
+            Index::U16(indices.iter().map(|&i| i as u16).collect())
+        } else {
+            Index::U32(indices.iter().map(|&i| i as u32).collect())
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match *self {
+            Index::U16(ref a) => a.len(),
+            Index::U32(ref a) => a.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, i: usize) -> usize {
+        match *self {
+            Index::U16(ref a) => a[i] as usize,
+            Index::U32(ref a) => a[i] as usize,
+        }
+    }
+}
+
+#[derive(Debug,PartialEq,Copy,Clone)]
+pub struct DrawRange {
+    pub start: usize,
+    // None draws everything after start
+    pub count: Option<usize>,
+}
+
+impl DrawRange {
+    pub const ALL: DrawRange = DrawRange {
+        start: 0,
+        count: None,
+    };
+}
+
+// how much each face contributes to the smoothed normal of its corners
+#[derive(Debug,PartialEq,Copy,Clone)]
+pub enum NormalWeighting {
+    Area,
+    Angle,
+}
+
+#[derive(Debug,PartialEq,Copy,Clone)]
+pub struct GeometryGroup {
+    pub start: usize,
+    pub count: usize,
+    pub material_index: usize,
+}
+
+#[derive(Debug,PartialEq)]
+pub struct BufferGeometry {
+    pub attributes: HashMap<AttributeName, GeometryAttribute>,
+    pub index: Option<Index>,
+    pub groups: Vec<GeometryGroup>,
+    pub draw_range: DrawRange,
+    pub bounding_box: Option<Box3>,
+    pub bounding_sphere: Option<Sphere>,
+    // speeds up raycasting when set, see update_bounds_tree
+    pub bounds_tree: Option<MeshBvh>,
+}
+
+// interleaved buffers are copied rather than shared, so writing to a clone never moves the
+// original, attributes that shared a buffer in the original share its copy
+impl Clone for BufferGeometry {
+    fn clone(&self) -> BufferGeometry {
+        // copies[i] is the copy of originals[i]
+        let mut originals: Vec<Rc<RefCell<InterleavedBuffer>>> = vec![];
+        let mut copies: Vec<Rc<RefCell<InterleavedBuffer>>> = vec![];
+        let mut attributes = HashMap::new();
+
+        for (name, attribute) in &self.attributes {
+            let attribute = match *attribute {
+                GeometryAttribute::Buffer(ref a) => GeometryAttribute::Buffer(a.clone()),
+                GeometryAttribute::Interleaved(ref a) => {
+                    let data = match originals.iter().position(|o| Rc::ptr_eq(o, &a.data)) {
+                        Some(i) => copies[i].clone(),
+                        None => {
+                            let copy = Rc::new(RefCell::new(a.data.borrow().clone()));
+                            originals.push(a.data.clone());
+                            copies.push(copy.clone());
+                            copy
+                        }
+                    };
+
+                    GeometryAttribute::Interleaved(InterleavedBufferAttribute {
+                        data: data,
+                        item_size: a.item_size,
+                        offset: a.offset,
+                        normalized: a.normalized,
+                    })
+                }
+            };
+            attributes.insert(name.clone(), attribute);
+        }
+
+        BufferGeometry {
+            attributes: attributes,
+            index: self.index.clone(),
+            groups: self.groups.clone(),
+            draw_range: self.draw_range,
+            bounding_box: self.bounding_box,
+            bounding_sphere: self.bounding_sphere,
+            bounds_tree: self.bounds_tree.clone(),
+        }
+    }
+}
+
+impl BufferGeometry {
+    pub fn new() -> BufferGeometry {
+        BufferGeometry {
+            attributes: HashMap::new(),
+            index: None,
+            groups: vec![],
+            draw_range: DrawRange::ALL,
+            bounding_box: None,
+            bounding_sphere: None,
+            bounds_tree: None,
+        }
+    }
+
+    // positions only, for drawing points or a line strip through them like a tessellated curve
+    pub fn from_points(points: &[Vector3]) -> BufferGeometry {
+        let mut geometry = BufferGeometry::new();
+        geometry.set_attribute(AttributeName::Position, BufferAttribute::from_vector3s(points));
+        geometry
+    }
+
+    pub fn attribute(&self, name: &AttributeName) -> Option<&GeometryAttribute> {
+        self.attributes.get(name)
+    }
+
+    pub fn attribute_mut(&mut self, name: &AttributeName) -> Option<&mut GeometryAttribute> {
+        self.attributes.get_mut(name)
+    }
+
+    pub fn set_attribute<T: Into<GeometryAttribute>>(&mut self, name: AttributeName, attribute: T) {
+        self.attributes.insert(name, attribute.into());
+    }
+
+    pub fn remove_attribute(&mut self, name: &AttributeName) -> Option<GeometryAttribute> {
+        self.attributes.remove(name)
+    }
+
+    pub fn has_attribute(&self, name: &AttributeName) -> bool {
+        self.attributes.contains_key(name)
+    }
+
+    pub fn set_index(&mut self, index: Option<Index>) {
+        self.index = index;
+    }
+
+    pub fn add_group(&mut self, start: usize, count: usize, material_index: usize) {
+        self.groups.push(GeometryGroup {
+            start: start,
+            count: count,
+            material_index: material_index,
+        });
+    }
+
+    pub fn clear_groups(&mut self) {
+        self.groups.clear();
+    }
+
+    pub fn set_draw_range(&mut self, start: usize, count: Option<usize>) {
+        self.draw_range = DrawRange {
+            start: start,
+            count: count,
+        };
+    }
+
+    pub fn vertex_count(&self) -> usize {
+        match self.attribute(&AttributeName::Position) {
+            Some(position) => position.count(),
+            None => 0,
+        }
+    }
+
+    pub fn compute_bounding_box(&self) -> Box3 {
+        match self.attribute(&AttributeName::Position) {
+            None => Box3::EMPTY,
+            Some(position) => Box3::from_attribute(position),
+        }
+    }
+
+    // the smallest sphere around the vertices, see Sphere::from_points
+    pub fn compute_bounding_sphere(&self) -> Sphere {
+        match self.attribute(&AttributeName::Position) {
+            None => Sphere::DEFAULT,
+            Some(position) => {
+                let points: Vec<Vector3> =
+                    (0..position.count()).map(|i| position.vector3(i)).collect();
+                let points: Vec<&Vector3> = points.iter().collect();
+                Sphere::from_points(&points)
+            }
+        }
+    }
+
+    // caches the bounds so culling doesn't have to walk the vertices every frame
+    pub fn update_bounds(&mut self) {
+        self.bounding_box = Some(self.compute_bounding_box());
+        self.bounding_sphere = Some(self.compute_bounding_sphere());
+    }
+
+    // worth it for big meshes that are raycast more than once, has to be updated whenever the
+    // positions change
+    pub fn update_bounds_tree(&mut self) {
+        self.bounds_tree = Some(MeshBvh::new(self));
+    }
+
+    // vertex indices of every triangle, the index buffer is used when there is one
+    pub fn triangle_indices(&self) -> Vec<[usize; 3]> {
+        match self.index {
+            Some(ref index) => {
+                (0..index.len() / 3)
+                    .map(|f| [index.get(f * 3), index.get(f * 3 + 1), index.get(f * 3 + 2)])
+                    .collect()
+            }
+            None => (0..self.vertex_count() / 3).map(|f| [f * 3, f * 3 + 1, f * 3 + 2]).collect(),
+        }
+    }
+
+    pub fn compute_face_normals(&self) -> Vec<Vector3> {
+        match self.attribute(&AttributeName::Position) {
+            None => vec![],
+            Some(position) => {
+                self.triangle_indices()
+                    .iter()
+                    .map(|face| triangle_from_attribute(position, face).normal())
+                    .collect()
+            }
+        }
+    }
+
+    // smooth normals for indexed geometry, non-indexed geometry doesn't share vertices between
+    // faces so every vertex simply gets the normal of its face
+    pub fn compute_vertex_normals(&mut self, weighting: NormalWeighting) {
+        let normals = match self.attribute(&AttributeName::Position) {
+            None => return,
+            Some(position) => {
+                let mut normals = vec![Vector3::ZERO; position.count()];
+
+                for face in self.triangle_indices() {
+                    let triangle = triangle_from_attribute(position, &face);
+                    let area = triangle.area();
+
+                    // degenerate faces have no direction to contribute
+                    if area == 0.0 {
+                        continue;
+                    }
+
+                    let normal = triangle.normal();
+                    let weights = match weighting {
+                        NormalWeighting::Area => [area, area, area],
+                        NormalWeighting::Angle => corner_angles(&triangle),
+                    };
+
+                    for k in 0..3 {
+                        normals[face[k]] = normals[face[k]].add_scaled_vector(&normal, weights[k]);
+                    }
+                }
+
+                normals
+            }
+        };
+
+        let mut attribute = match self.attributes.remove(&AttributeName::Normal) {
+            // reuse the existing (possibly interleaved) storage when it fits
+            Some(existing) if existing.count() == normals.len() &&
+                              existing.item_size() == 3 => existing,
+            _ => BufferAttribute::from_f32(vec![0.0; normals.len() * 3], 3).into(),
+        };
+
+        for (i, normal) in normals.iter().enumerate() {
+            let n = if normal.length_squared() > 0.0 {
+                normal.normalized()
+            } else {
+                *normal
+            };
+            attribute.set_vector3(i, &n);
+        }
+
+        self.attributes.insert(AttributeName::Normal, attribute);
+    }
+
+    // MikkTSpace tangents, the ones normal map bakers expect. vertices with the same position,
+    // normal and uv share a tangent space. each face's uv derived tangent is projected onto the
+    // plane of every corner's normal and weighted by the corner angle in that plane. faces with
+    // mirrored uvs are kept apart from the rest, indexed vertices used by both kinds of faces are
+    // split in two. w holds the handedness, bitangent = w * cross(normal, tangent).
+    // returns false and leaves the geometry alone when position, normal or uv is missing or
+    // shorter than position
+    pub fn compute_tangents(&mut self) -> bool {
+        let faces = self.triangle_indices();
+        let (tangents, sources, corners) = {
+            let (position, normal, uv) = match (self.attribute(&AttributeName::Position),
+                                                self.attribute(&AttributeName::Normal),
+                                                self.attribute(&AttributeName::Uv)) {
+                (Some(p), Some(n), Some(u)) => (p, n, u),
+                _ => return false,
+            };
+
+            let count = position.count();
+            if normal.count() < count || uv.count() < count {
+                return false;
+            }
+
+            // vertices with the same position, normal and uv weld to the same number
+            let mut keys = HashMap::new();
+            let welded: Vec<usize> = (0..count)
+                .map(|i| {
+                    let p = position.vector3(i);
+                    let n = normal.vector3(i);
+                    let t = uv.vector2(i);
+                    let key = [p.x.to_bits(), p.y.to_bits(), p.z.to_bits(), n.x.to_bits(),
+                               n.y.to_bits(), n.z.to_bits(), t.x.to_bits(), t.y.to_bits()];
+                    let next = keys.len();
+                    *keys.entry(key).or_insert(next)
+                })
+                .collect();
+
+            // summed tangents by welded vertex and orientation, true unless the uvs are mirrored
+            let mut groups: HashMap<(usize, bool), Vector3> = HashMap::new();
+            // None for faces without area in space or in uv
+            let mut orientations = vec![];
+
+            for face in &faces {
+                let triangle = triangle_from_attribute(position, face);
+                let uv0 = uv.vector2(face[0]);
+                let duv1 = uv.vector2(face[1]).subtract(&uv0);
+                let duv2 = uv.vector2(face[2]).subtract(&uv0);
+
+                let r = duv1.x * duv2.y - duv2.x * duv1.y;
+                if triangle.area() == 0.0 || r == 0.0 {
+                    orientations.push(None);
+                    continue;
+                }
+
+                let orientation = r > 0.0;
+                orientations.push(Some(orientation));
+
+                let edge1 = triangle.b.subtract(&triangle.a);
+                let edge2 = triangle.c.subtract(&triangle.a);
+                let sdir = edge1.multiply_scalar(duv2.y)
+                    .subtract(&edge2.multiply_scalar(duv1.y))
+                    .divide_scalar(r);
+                let corners = [triangle.a, triangle.b, triangle.c];
+
+                for k in 0..3 {
+                    let n = normal.vector3(face[k]);
+                    let project = |v: &Vector3| v.subtract(&n.multiply_scalar(n.dot(v)));
+
+                    let tangent = project(&sdir);
+                    let before = project(&corners[(k + 2) % 3].subtract(&corners[k]));
+                    let after = project(&corners[(k + 1) % 3].subtract(&corners[k]));
+                    if tangent.length_squared() == 0.0 || before.length_squared() == 0.0 ||
+                       after.length_squared() == 0.0 {
+                        continue;
+                    }
+
+                    let sum = groups.entry((welded[face[k]], orientation)).or_insert(Vector3::ZERO);
+                    *sum = sum.add_scaled_vector(&tangent.normalized(), before.angle_to(&after));
+                }
+            }
+
+            // a vertex takes the orientation of the first face using it, faces of the other
+            // orientation get a copy of it
+            let mut vertex_orientations: Vec<Option<bool>> = vec![None; count];
+            let mut copies: HashMap<usize, usize> = HashMap::new();
+            let mut sources = vec![];
+            let mut corners = vec![];
+
+            for (face, orientation) in faces.iter().zip(orientations) {
+                for &v in face {
+                    let o = match orientation {
+                        Some(o) => o,
+                        None => {
+                            corners.push(v);
+                            continue;
+                        }
+                    };
+
+                    match vertex_orientations[v] {
+                        None => {
+                            vertex_orientations[v] = Some(o);
+                            corners.push(v);
+                        }
+                        Some(existing) if existing == o => corners.push(v),
+                        Some(_) => {
+                            let copy = *copies.entry(v).or_insert_with(|| {
+                                sources.push(v);
+                                count + sources.len() - 1
+                            });
+                            corners.push(copy);
+                        }
+                    }
+                }
+            }
+
+            let vertices = (0..count).map(|v| (v, vertex_orientations[v]))
+                .chain(sources.iter().map(|&v| (v, vertex_orientations[v].map(|o| !o))));
+            let tangents: Vec<(Vector3, f32)> = vertices.map(|(v, orientation)| {
+                    match orientation {
+                        Some(o) => {
+                            let sum = groups.get(&(welded[v], o)).cloned().unwrap_or(Vector3::ZERO);
+                            let tangent = if sum.length_squared() > 0.0 {
+                                sum.normalized()
+                            } else {
+                                sum
+                            };
+                            (tangent, if o { 1.0 } else { -1.0 })
+                        }
+                        None => (Vector3::ZERO, 1.0),
+                    }
+                })
+                .collect();
+
+            (tangents, sources, corners)
+        };
+
+        if !sources.is_empty() {
+            self.duplicate_vertices(&sources);
+            self.index = Some(Index::from_vec(&corners));
+            if self.bounds_tree.is_some() {
+                self.update_bounds_tree();
+            }
+        }
+
+        let mut attribute = BufferAttribute::from_f32(vec![0.0; tangents.len() * 4], 4);
+        for (i, &(tangent, w)) in tangents.iter().enumerate() {
+            attribute.set_vector3(i, &tangent);
+            attribute.set_component(i, 3, w);
+        }
+
+        self.set_attribute(AttributeName::Tangent, attribute);
+        true
+    }
+
+    // appends a copy of every source vertex to all attributes, in order
+    fn duplicate_vertices(&mut self, sources: &[usize]) {
+        let mut buffers: Vec<Rc<RefCell<InterleavedBuffer>>> = vec![];
+
+        for attribute in self.attributes.values_mut() {
+            match *attribute {
+                GeometryAttribute::Buffer(ref mut a) => {
+                    for &v in sources {
+                        a.array.extend_from_range(v * a.item_size, a.item_size);
+                    }
+                }
+                GeometryAttribute::Interleaved(ref a) => {
+                    // attributes sharing a buffer must only copy its rows once
+                    if buffers.iter().any(|b| Rc::ptr_eq(b, &a.data)) {
+                        continue;
+                    }
+                    buffers.push(a.data.clone());
+
+                    let mut buffer = a.data.borrow_mut();
+                    let stride = buffer.stride;
+                    for &v in sources {
+                        buffer.array.extend_from_range(v * stride, stride);
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn translate(&mut self, offset: &Vector3) {
+        if let Some(position) = self.attributes.get_mut(&AttributeName::Position) {
+            for i in 0..position.count() {
+                let v = position.vector3(i).add(offset);
+                position.set_vector3(i, &v);
+            }
+        }
+
+        if self.bounding_box.is_some() || self.bounding_sphere.is_some() {
+            self.update_bounds();
+        }
+
+        if let Some(mut tree) = self.bounds_tree.take() {
+            tree.refit(self);
+            self.bounds_tree = Some(tree);
+        }
+    }
+}
+
+fn triangle_from_attribute<T: VertexAttribute>(position: &T, face: &[usize; 3]) -> Triangle {
+    Triangle::new(&position.vector3(face[0]),
+                  &position.vector3(face[1]),
+                  &position.vector3(face[2]))
+}
+
+fn corner_angles(triangle: &Triangle) -> [f32; 3] {
+    let ab = triangle.b.subtract(&triangle.a);
+    let ac = triangle.c.subtract(&triangle.a);
+    let bc = triangle.c.subtract(&triangle.b);
+
+    let a = ab.angle_to(&ac);
+    let b = ab.negate().angle_to(&bc);
+    [a, b, PI - a - b]
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+    use core::{AttributeName, BufferArray, BufferAttribute, BufferGeometry, GeometryAttribute,
+               Index, InterleavedBuffer, InterleavedBufferAttribute, MeshBvh, NormalWeighting,
+               VertexAttribute};
+    use math::{Box3, Sphere, Vector2, Vector3};
+
+    fn unit_quad(flip_u: bool) -> BufferGeometry {
+        let u = if flip_u { -1.0 } else { 1.0 };
+        let mut geometry = BufferGeometry::new();
+        geometry.set_attribute(AttributeName::Position,
+                               BufferAttribute::from_vector3s(&[Vector3::new(0.0, 0.0, 0.0),
+                                                                Vector3::new(1.0, 0.0, 0.0),
+                                                                Vector3::new(1.0, 1.0, 0.0),
+                                                                Vector3::new(0.0, 1.0, 0.0)]));
+        geometry.set_attribute(AttributeName::Uv,
+                               BufferAttribute::from_vector2s(&[Vector2::new(0.0, 0.0),
+                                                                Vector2::new(u, 0.0),
+                                                                Vector2::new(u, 1.0),
+                                                                Vector2::new(0.0, 1.0)]));
+        geometry.set_index(Some(Index::from_vec(&[0, 1, 2, 0, 2, 3])));
+        geometry
+    }
+
+    fn unit_triangle() -> BufferGeometry {
+        let mut geometry = BufferGeometry::new();
+        geometry.set_attribute(AttributeName::Position,
+                               BufferAttribute::from_f32(vec![-1.0, 0.0, 0.0, 1.0, 0.0, 0.0,
+                                                              0.0, 2.0, 0.0],
+                                                         3));
+        geometry
+    }
+
+    #[test]
+    fn index_from_vec() {
+        let a = Index::from_vec(&[0, 1, 2]);
+        assert_eq!(a, Index::U16(vec![0, 1, 2]));
+        assert_eq!(a.get(2), 2);
+
+        let b = Index::from_vec(&[0, 70000]);
+        assert_eq!(b, Index::U32(vec![0, 70000]));
+        assert_eq!(b.len(), 2);
+    }
+
+    #[test]
+    fn compute_bounding_box() {
+        let a = BufferGeometry::new();
+        assert!(a.compute_bounding_box().is_empty());
+
+        let b = unit_triangle();
+        assert_eq!(b.compute_bounding_box(),
+                   Box3::new(&Vector3::new(-1.0, 0.0, 0.0), &Vector3::new(1.0, 2.0, 0.0)));
+    }
+
+    #[test]
+    fn compute_bounding_sphere() {
+        let a = BufferGeometry::new();
+        assert_eq!(a.compute_bounding_sphere(), Sphere::DEFAULT);
+
+        // the circle through the corners, tighter than the one around the bounding box
+        let b = unit_triangle().compute_bounding_sphere();
+        assert!(b.center.distance_to(&Vector3::new(0.0, 0.75, 0.0)) < 1e-6);
+        assert!((b.radius - 1.25).abs() < 1e-6);
+    }
+
+    #[test]
+    fn translate() {
+        let mut a = unit_triangle();
+        a.update_bounds();
+        a.translate(&Vector3::new(0.0, 0.0, 1.0));
+
+        let position = a.attribute(&AttributeName::Position).unwrap();
+        assert_eq!(position.vector3(2), Vector3::new(0.0, 2.0, 1.0));
+        assert_eq!(a.bounding_box.unwrap().min, Vector3::new(-1.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn interleaved_bounds() {
+        // position, normal
+        let buffer = InterleavedBuffer::shared(BufferArray::F32(vec![-1.0, 0.0, 0.0, 0.0, 0.0,
+                                                                     1.0, 1.0, 0.0, 0.0, 0.0,
+                                                                     0.0, 1.0, 0.0, 2.0, 0.0,
+                                                                     0.0, 0.0, 1.0]),
+                                               6);
+        let mut geometry = BufferGeometry::new();
+        geometry.set_attribute(AttributeName::Position,
+                               InterleavedBufferAttribute::new(&buffer, 3, 0, false));
+        geometry.set_attribute(AttributeName::Normal,
+                               InterleavedBufferAttribute::new(&buffer, 3, 3, false));
+
+        assert_eq!(geometry.vertex_count(), 3);
+        assert_eq!(geometry.compute_bounding_box(),
+                   Box3::new(&Vector3::new(-1.0, 0.0, 0.0), &Vector3::new(1.0, 2.0, 0.0)));
+        assert_eq!(geometry.compute_bounding_sphere(),
+                   unit_triangle().compute_bounding_sphere());
+    }
+
+    #[test]
+    fn clone_interleaved() {
+        let buffer = InterleavedBuffer::shared(BufferArray::F32(vec![-1.0, 0.0, 0.0, 0.0, 0.0,
+                                                                     1.0, 1.0, 0.0, 0.0, 0.0,
+                                                                     0.0, 1.0, 0.0, 2.0, 0.0,
+                                                                     0.0, 0.0, 1.0]),
+                                               6);
+        let mut geometry = BufferGeometry::new();
+        geometry.set_attribute(AttributeName::Position,
+                               InterleavedBufferAttribute::new(&buffer, 3, 0, false));
+        geometry.set_attribute(AttributeName::Normal,
+                               InterleavedBufferAttribute::new(&buffer, 3, 3, false));
+
+        let mut copy = geometry.clone();
+        copy.translate(&Vector3::new(0.0, 0.0, 5.0));
+        let position = geometry.attribute(&AttributeName::Position).unwrap();
+        assert_eq!(position.vector3(0), Vector3::new(-1.0, 0.0, 0.0));
+        let position = copy.attribute(&AttributeName::Position).unwrap();
+        assert_eq!(position.vector3(0), Vector3::new(-1.0, 0.0, 5.0));
+
+        // position and normal of the clone still read from one buffer
+        let data = |name| match *copy.attribute(name).unwrap() {
+            GeometryAttribute::Interleaved(ref a) => a.data.clone(),
+            _ => panic!("expected an interleaved attribute"),
+        };
+        let position = data(&AttributeName::Position);
+        assert!(Rc::ptr_eq(&position, &data(&AttributeName::Normal)));
+        assert!(!Rc::ptr_eq(&position, &buffer));
+    }
+
+    #[test]
+    fn compute_face_normals() {
+        let a = unit_quad(false);
+        assert_eq!(a.compute_face_normals(), vec![Vector3::Z, Vector3::Z]);
+    }
+
+    #[test]
+    fn compute_vertex_normals() {
+        let mut a = unit_quad(false);
+        a.compute_vertex_normals(NormalWeighting::Area);
+        let normal = a.attribute(&AttributeName::Normal).unwrap();
+        assert_eq!(normal.count(), 4);
+        for i in 0..4 {
+            assert_eq!(normal.vector3(i), Vector3::Z);
+        }
+
+        // a large face in xy and a small one in xz meeting at a right angle in the origin
+        let mut b = BufferGeometry::new();
+        b.set_attribute(AttributeName::Position,
+                        BufferAttribute::from_vector3s(&[Vector3::ZERO,
+                                                         Vector3::new(4.0, 0.0, 0.0),
+                                                         Vector3::new(0.0, 4.0, 0.0),
+                                                         Vector3::new(0.0, 0.0, 1.0),
+                                                         Vector3::new(1.0, 0.0, 0.0)]));
+        b.set_index(Some(Index::from_vec(&[0, 1, 2, 0, 3, 4])));
+
+        b.compute_vertex_normals(NormalWeighting::Angle);
+        let by_angle = b.attribute(&AttributeName::Normal).unwrap().vector3(0);
+        assert!(by_angle.distance_to(&Vector3::new(0.0, 1.0, 1.0).normalized()) < 0.0001);
+
+        b.compute_vertex_normals(NormalWeighting::Area);
+        let by_area = b.attribute(&AttributeName::Normal).unwrap().vector3(0);
+        assert!(by_area.distance_to(&Vector3::new(0.0, 0.5, 8.0).normalized()) < 0.0001);
+    }
+
+    #[test]
+    fn compute_tangents() {
+        let mut a = unit_quad(false);
+        assert!(!a.compute_tangents());
+        assert!(a.attribute(&AttributeName::Tangent).is_none());
+
+        a.compute_vertex_normals(NormalWeighting::Angle);
+        assert!(a.compute_tangents());
+
+        let tangent = a.attribute(&AttributeName::Tangent).unwrap();
+        assert_eq!(tangent.item_size(), 4);
+        for i in 0..4 {
+            assert!(tangent.vector3(i).distance_to(&Vector3::X) < 0.0001);
+            assert_eq!(tangent.w(i), 1.0);
+        }
+
+        // mirrored uvs flip the handedness
+        let mut b = unit_quad(true);
+        b.compute_vertex_normals(NormalWeighting::Angle);
+        assert!(b.compute_tangents());
+
+        let tangent = b.attribute(&AttributeName::Tangent).unwrap();
+        for i in 0..4 {
+            assert!(tangent.vector3(i).distance_to(&Vector3::NEG_X) < 0.0001);
+            assert_eq!(tangent.w(i), -1.0);
+        }
+
+        // tangents lie in the plane of the vertex normal
+        let mut c = unit_quad(false);
+        let tilted = Vector3::new(1.0, 0.0, 1.0).normalized();
+        c.set_attribute(AttributeName::Normal, BufferAttribute::from_vector3s(&[tilted; 4]));
+        assert!(c.compute_tangents());
+        let tangent = c.attribute(&AttributeName::Tangent).unwrap();
+        for i in 0..4 {
+            let expected = Vector3::new(1.0, 0.0, -1.0).normalized();
+            assert!(tangent.vector3(i).distance_to(&expected) < 0.0001);
+        }
+
+        // attributes shorter than position are rejected rather than read past their end
+        let mut d = unit_quad(false);
+        d.set_attribute(AttributeName::Normal,
+                        BufferAttribute::from_vector3s(&[Vector3::Z, Vector3::Z]));
+        assert!(!d.compute_tangents());
+        assert!(d.attribute(&AttributeName::Tangent).is_none());
+    }
+
+    #[test]
+    fn compute_tangents_welding() {
+        // the same quad without an index, the corners it repeats weld back together
+        let quad = unit_quad(false);
+        let mut a = BufferGeometry::new();
+        let (position, uv) = (quad.attribute(&AttributeName::Position).unwrap(),
+                              quad.attribute(&AttributeName::Uv).unwrap());
+        let corners = quad.triangle_indices().concat();
+        let positions: Vec<_> = corners.iter().map(|&i| position.vector3(i)).collect();
+        let uvs: Vec<_> = corners.iter().map(|&i| uv.vector2(i)).collect();
+        a.set_attribute(AttributeName::Position, BufferAttribute::from_vector3s(&positions));
+        a.set_attribute(AttributeName::Uv, BufferAttribute::from_vector2s(&uvs));
+        a.set_attribute(AttributeName::Normal,
+                        BufferAttribute::from_vector3s(&[Vector3::Z; 6]));
+        assert!(a.compute_tangents());
+
+        let tangent = a.attribute(&AttributeName::Tangent).unwrap();
+        assert_eq!(tangent.count(), 6);
+        for i in 0..6 {
+            assert!(tangent.vector3(i).distance_to(&Vector3::X) < 0.0001);
+            assert_eq!(tangent.w(i), 1.0);
+        }
+    }
+
+    #[test]
+    fn compute_tangents_mirrored_seam() {
+        // two quads sharing the edge from 0 to 3, the left one has its uvs mirrored
+        let mut a = BufferGeometry::new();
+        a.set_attribute(AttributeName::Position,
+                        BufferAttribute::from_vector3s(&[Vector3::new(0.0, 0.0, 0.0),
+                                                         Vector3::new(1.0, 0.0, 0.0),
+                                                         Vector3::new(1.0, 1.0, 0.0),
+                                                         Vector3::new(0.0, 1.0, 0.0),
+                                                         Vector3::new(-1.0, 0.0, 0.0),
+                                                         Vector3::new(-1.0, 1.0, 0.0)]));
+        a.set_attribute(AttributeName::Uv,
+                        BufferAttribute::from_vector2s(&[Vector2::new(0.0, 0.0),
+                                                         Vector2::new(1.0, 0.0),
+                                                         Vector2::new(1.0, 1.0),
+                                                         Vector2::new(0.0, 1.0),
+                                                         Vector2::new(1.0, 0.0),
+                                                         Vector2::new(1.0, 1.0)]));
+        a.set_attribute(AttributeName::Normal,
+                        BufferAttribute::from_vector3s(&[Vector3::Z; 6]));
+        a.set_index(Some(Index::from_vec(&[0, 1, 2, 0, 2, 3, 4, 0, 3, 4, 3, 5])));
+        a.update_bounds_tree();
+        assert!(a.compute_tangents());
+
+        // 0 and 3 are split, the mirrored faces use the copies
+        assert_eq!(a.vertex_count(), 8);
+        assert_eq!(a.triangle_indices(),
+                   vec![[0, 1, 2], [0, 2, 3], [4, 6, 7], [4, 7, 5]]);
+        let position = a.attribute(&AttributeName::Position).unwrap();
+        assert_eq!(position.vector3(6), Vector3::ZERO);
+        assert_eq!(position.vector3(7), Vector3::new(0.0, 1.0, 0.0));
+        assert_eq!(a.attribute(&AttributeName::Uv).unwrap().vector2(7), Vector2::new(0.0, 1.0));
+
+        let tangent = a.attribute(&AttributeName::Tangent).unwrap();
+        for i in 0..8 {
+            let (expected, w) = if i < 4 { (Vector3::X, 1.0) } else { (Vector3::NEG_X, -1.0) };
+            assert!(tangent.vector3(i).distance_to(&expected) < 0.0001);
+            assert_eq!(tangent.w(i), w);
+        }
+
+        // the bounds tree follows the new vertex numbers
+        let tree = MeshBvh::new(&a);
+        assert_eq!(a.bounds_tree, Some(tree));
+    }
+}
@@ -0,0 +1,33 @@
+use std::rc::Rc;
+use std::cell::RefCell;
+
+use core::BufferArray;
+
+// one array holding several attributes per vertex, stride is counted in array elements
+#[derive(Debug,PartialEq,Clone)]
+pub struct InterleavedBuffer {
+    pub array: BufferArray,
+    pub stride: usize,
+}
+
+impl InterleavedBuffer {
+    pub fn new(array: BufferArray, stride: usize) -> InterleavedBuffer {
+        if stride == 0 {
+            panic!("InterleavedBuffer stride must be greater than zero.");
+        }
+
+        InterleavedBuffer {
+            array: array,
+            stride: stride,
+        }
+    }
+
+    // the attributes reading from the buffer all hold a clone of the returned handle
+    pub fn shared(array: BufferArray, stride: usize) -> Rc<RefCell<InterleavedBuffer>> {
+        Rc::new(RefCell::new(InterleavedBuffer::new(array, stride)))
+    }
+
+    pub fn count(&self) -> usize {
+        self.array.len() / self.stride
+    }
+}
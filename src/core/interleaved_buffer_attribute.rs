@@ -0,0 +1,98 @@
+use std::rc::Rc;
+use std::cell::RefCell;
+
+use core::{InterleavedBuffer, VertexAttribute};
+
+// clones read and write the same buffer as the original, BufferGeometry::clone copies it
+#[derive(Debug,PartialEq,Clone)]
+pub struct InterleavedBufferAttribute {
+    pub data: Rc<RefCell<InterleavedBuffer>>,
+    pub item_size: usize,
+    pub offset: usize,
+    pub normalized: bool,
+}
+
+impl InterleavedBufferAttribute {
+    pub fn new(data: &Rc<RefCell<InterleavedBuffer>>,
+               item_size: usize,
+               offset: usize,
+               normalized: bool)
+               -> InterleavedBufferAttribute {
+        if offset + item_size > data.borrow().stride {
+            panic!("InterleavedBufferAttribute doesn't fit in the stride of its buffer.");
+        }
+
+        InterleavedBufferAttribute {
+            data: data.clone(),
+            item_size: item_size,
+            offset: offset,
+            normalized: normalized,
+        }
+    }
+}
+
+impl VertexAttribute for InterleavedBufferAttribute {
+    fn count(&self) -> usize {
+        self.data.borrow().count()
+    }
+
+    fn item_size(&self) -> usize {
+        self.item_size
+    }
+
+    fn component(&self, index: usize, component: usize) -> f32 {
+        let data = self.data.borrow();
+        let value = data.array.get(index * data.stride + self.offset + component);
+
+        if self.normalized {
+            data.array.denormalize(value)
+        } else {
+            value
+        }
+    }
+
+    fn set_component(&mut self, index: usize, component: usize, value: f32) {
+        let mut data = self.data.borrow_mut();
+        let stored = if self.normalized {
+            data.array.normalize(value)
+        } else {
+            value
+        };
+
+        let i = index * data.stride + self.offset + component;
+        data.array.set(i, stored);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::{BufferArray, InterleavedBuffer, InterleavedBufferAttribute, VertexAttribute};
+    use math::{Vector2, Vector3};
+
+    #[test]
+    fn shared_buffer() {
+        // position, uv
+        let buffer = InterleavedBuffer::shared(BufferArray::F32(vec![1.0, 2.0, 3.0, 0.0, 1.0,
+                                                                     4.0, 5.0, 6.0, 1.0, 0.0]),
+                                               5);
+        let mut position = InterleavedBufferAttribute::new(&buffer, 3, 0, false);
+        let uv = InterleavedBufferAttribute::new(&buffer, 2, 3, false);
+
+        assert_eq!(position.count(), 2);
+        assert_eq!(uv.count(), 2);
+        assert_eq!(position.vector3(1), Vector3::new(4.0, 5.0, 6.0));
+        assert_eq!(uv.vector2(0), Vector2::new(0.0, 1.0));
+
+        position.set_vector3(0, &Vector3::ZERO);
+        assert_eq!(buffer.borrow().array,
+                   BufferArray::F32(vec![0.0, 0.0, 0.0, 0.0, 1.0, 4.0, 5.0, 6.0, 1.0, 0.0]));
+        assert_eq!(uv.vector2(0), Vector2::new(0.0, 1.0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn outside_stride() {
+        let buffer = InterleavedBuffer::shared(BufferArray::F32(vec![0.0; 8]), 4);
+        InterleavedBufferAttribute::new(&buffer, 3, 2, false);
+    }
+}
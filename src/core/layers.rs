@@ -0,0 +1,65 @@
+// membership in 32 channels. a raycaster (or camera) only considers objects sharing at least
+// one channel with it, everything starts out in channel 0
+#[derive(Debug,PartialEq,Eq,Copy,Clone)]
+pub struct Layers {
+    pub mask: u32,
+}
+
+impl Layers {
+    pub const DEFAULT: Layers = Layers { mask: 1 };
+
+    pub const NONE: Layers = Layers { mask: 0 };
+
+    pub const ALL: Layers = Layers { mask: 0xffff_ffff };
+
+    // makes the channel the only one enabled
+    pub fn set(&mut self, channel: u32) {
+        self.mask = 1 << channel;
+    }
+
+    pub fn enable(&mut self, channel: u32) {
+        self.mask |= 1 << channel;
+    }
+
+    pub fn disable(&mut self, channel: u32) {
+        self.mask &= !(1 << channel);
+    }
+
+    pub fn toggle(&mut self, channel: u32) {
+        self.mask ^= 1 << channel;
+    }
+
+    pub fn is_enabled(&self, channel: u32) -> bool {
+        self.mask & (1 << channel) != 0
+    }
+
+    // true when at least one channel is enabled in both
+    pub fn test(&self, layers: &Layers) -> bool {
+        self.mask & layers.mask != 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::Layers;
+
+    #[test]
+    fn test() {
+        let mut a = Layers::DEFAULT;
+        let mut b = Layers::DEFAULT;
+        assert!(a.test(&b));
+
+        a.set(3);
+        assert!(!a.test(&b));
+        assert!(a.is_enabled(3));
+        assert!(!a.is_enabled(0));
+
+        b.enable(3);
+        assert!(a.test(&b));
+        b.toggle(3);
+        assert!(!a.test(&b));
+        b.disable(0);
+        assert_eq!(b, Layers::NONE);
+        assert!(!Layers::ALL.test(&Layers::NONE));
+    }
+}
@@ -0,0 +1,565 @@
+use std::cmp::Ordering;
+use std::f32::INFINITY;
+use core::{AttributeName, BufferGeometry, VertexAttribute};
+use math::{Box3, Ray, Sphere, Triangle, Vector3};
+
+// leaves with this many triangles or fewer are not split any further
+const MAX_LEAF_SIZE: usize = 8;
+// candidate split positions along each axis
+const BINS: usize = 16;
+// cost of visiting a node relative to testing a triangle
+const TRAVERSAL_COST: f32 = 1.0;
+
+#[derive(Debug,PartialEq,Copy,Clone)]
+pub struct MeshHit {
+    // along the ray, or from the query point for closest_point_to_point
+    pub distance: f32,
+    pub point: Vector3,
+    // position of the triangle in BufferGeometry::triangle_indices, and its vertex indices
+    pub face_index: usize,
+    pub vertices: [usize; 3],
+}
+
+#[derive(Debug,PartialEq,Copy,Clone)]
+enum Content {
+    // a range of the triangles in leaf order
+    Leaf { start: usize, count: usize },
+    // the first child directly follows its parent
+    Branch { second: usize },
+}
+
+#[derive(Debug,PartialEq,Copy,Clone)]
+struct Node {
+    bounds: Box3,
+    content: Content,
+}
+
+// bounding volume hierarchy over the triangles of a geometry, split by the surface area
+// heuristic. everything is in the geometry's local space. the positions are copied, call refit
+// after moving vertices, adding or removing triangles needs a new tree
+#[derive(Debug,PartialEq,Clone)]
+pub struct MeshBvh {
+    nodes: Vec<Node>,
+    // face indices, vertex indices and positions of the triangles in leaf order
+    faces: Vec<usize>,
+    vertices: Vec<[usize; 3]>,
+    triangles: Vec<Triangle>,
+}
+
+impl MeshBvh {
+    pub fn new(geometry: &BufferGeometry) -> MeshBvh {
+        let vertices = geometry.triangle_indices();
+        let triangles = read_triangles(geometry, &vertices);
+        let bounds: Vec<Box3> = triangles.iter().map(triangle_bounds).collect();
+        let centroids: Vec<Vector3> = bounds.iter().map(|b| b.center()).collect();
+
+        let mut faces: Vec<usize> = (0..triangles.len()).collect();
+        let mut nodes = vec![];
+        if !faces.is_empty() {
+            build(&mut nodes, &mut faces, 0, &bounds, &centroids);
+        }
+
+        MeshBvh {
+            nodes: nodes,
+            vertices: faces.iter().map(|&f| vertices[f]).collect(),
+            triangles: faces.iter().map(|&f| triangles[f]).collect(),
+            faces: faces,
+        }
+    }
+
+    pub fn bounding_box(&self) -> Box3 {
+        match self.nodes.first() {
+            Some(root) => root.bounds,
+            None => Box3::EMPTY,
+        }
+    }
+
+    // rereads the positions and grows or shrinks the nodes around them, the tree keeps its
+    // shape so it gets slower to query the further the vertices move
+    pub fn refit(&mut self, geometry: &BufferGeometry) {
+        self.triangles = read_triangles(geometry, &self.vertices);
+
+        // children always come after their parent
+        for index in (0..self.nodes.len()).rev() {
+            self.nodes[index].bounds = match self.nodes[index].content {
+                Content::Leaf { start, count } => {
+                    self.triangles[start..start + count]
+                        .iter()
+                        .fold(Box3::EMPTY, |acc, t| acc.union(&triangle_bounds(t)))
+                }
+                Content::Branch { second } => {
+                    self.nodes[index + 1].bounds.union(&self.nodes[second].bounds)
+                }
+            };
+        }
+    }
+
+    // both sides of the triangles count, the direction is assumed to be normalized
+    pub fn raycast_first(&self, ray: &Ray) -> Option<MeshHit> {
+        let mut closest: Option<MeshHit> = None;
+        let mut stack = self.root();
+
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            let far = closest.map_or(INFINITY, |hit| hit.distance);
+            match ray.distance_to_box(&node.bounds) {
+                Some(distance) if distance <= far => {}
+                _ => continue,
+            }
+
+            match node.content {
+                Content::Leaf { start, count } => {
+                    for i in start..start + count {
+                        if let Some(hit) = self.raycast_triangle(ray, i) {
+                            if hit.distance < closest.map_or(INFINITY, |c| c.distance) {
+                                closest = Some(hit);
+                            }
+                        }
+                    }
+                }
+                Content::Branch { second } => {
+                    // the nearer child goes on top so its hits can rule out the other one
+                    let first = index + 1;
+                    let first_distance = ray.distance_to_box(&self.nodes[first].bounds);
+                    let second_distance = ray.distance_to_box(&self.nodes[second].bounds);
+                    if first_distance.unwrap_or(INFINITY) <= second_distance.unwrap_or(INFINITY) {
+                        stack.push(second);
+                        stack.push(first);
+                    } else {
+                        stack.push(first);
+                        stack.push(second);
+                    }
+                }
+            }
+        }
+
+        closest
+    }
+
+    // every hit, closest first
+    pub fn raycast(&self, ray: &Ray) -> Vec<MeshHit> {
+        let mut hits: Vec<MeshHit> = self.leaves_entered(|b| ray.distance_to_box(b).is_some())
+            .into_iter()
+            .filter_map(|i| self.raycast_triangle(ray, i))
+            .collect();
+
+        hits.sort_by(|a, b| a.distance.partial_cmp(&b.distance).unwrap_or(Ordering::Equal));
+        hits
+    }
+
+    pub fn intersects_sphere(&self, sphere: &Sphere) -> bool {
+        !self.triangles_overlapping(|bounds| sphere.intersects_box(bounds),
+                                    |triangle| triangle.intersects_sphere(sphere),
+                                    true)
+            .is_empty()
+    }
+
+    pub fn intersects_box(&self, box3: &Box3) -> bool {
+        !self.triangles_overlapping(|bounds| box3.intersects_box(bounds),
+                                    |triangle| triangle.intersects_box(box3),
+                                    true)
+            .is_empty()
+    }
+
+    // face indices of the triangles touching the sphere, in no particular order
+    pub fn triangles_in_sphere(&self, sphere: &Sphere) -> Vec<usize> {
+        self.triangles_overlapping(|bounds| sphere.intersects_box(bounds),
+                                   |triangle| triangle.intersects_sphere(sphere),
+                                   false)
+    }
+
+    pub fn triangles_in_box(&self, box3: &Box3) -> Vec<usize> {
+        self.triangles_overlapping(|bounds| box3.intersects_box(bounds),
+                                   |triangle| triangle.intersects_box(box3),
+                                   false)
+    }
+
+    pub fn closest_point_to_point(&self, point: &Vector3) -> Option<MeshHit> {
+        let mut closest: Option<MeshHit> = None;
+        let mut stack = self.root();
+
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            if node.bounds.distance_to_point(point) > closest.map_or(INFINITY, |hit| hit.distance) {
+                continue;
+            }
+
+            match node.content {
+                Content::Leaf { start, count } => {
+                    for i in start..start + count {
+                        let on_triangle = self.triangles[i].closest_point_to_point(point);
+                        let distance = on_triangle.distance_to(point);
+                        if distance < closest.map_or(INFINITY, |hit| hit.distance) {
+                            closest = Some(self.hit(i, distance, &on_triangle));
+                        }
+                    }
+                }
+                Content::Branch { second } => {
+                    let first = index + 1;
+                    if self.nodes[first].bounds.distance_to_point(point) <=
+                       self.nodes[second].bounds.distance_to_point(point) {
+                        stack.push(second);
+                        stack.push(first);
+                    } else {
+                        stack.push(first);
+                        stack.push(second);
+                    }
+                }
+            }
+        }
+
+        closest
+    }
+
+    fn root(&self) -> Vec<usize> {
+        if self.nodes.is_empty() { vec![] } else { vec![0] }
+    }
+
+    fn hit(&self, i: usize, distance: f32, point: &Vector3) -> MeshHit {
+        MeshHit {
+            distance: distance,
+            point: *point,
+            face_index: self.faces[i],
+            vertices: self.vertices[i],
+        }
+    }
+
+    fn raycast_triangle(&self, ray: &Ray, i: usize) -> Option<MeshHit> {
+        let triangle = &self.triangles[i];
+        ray.intersect_triangle(&triangle.a, &triangle.b, &triangle.c, false)
+            .map(|point| self.hit(i, point.distance_to(&ray.origin), &point))
+    }
+
+    // positions in leaf order of the triangles in every leaf the predicate lets through
+    fn leaves_entered<F: Fn(&Box3) -> bool>(&self, enter: F) -> Vec<usize> {
+        let mut found = vec![];
+        let mut stack = self.root();
+
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            if !enter(&node.bounds) {
+                continue;
+            }
+
+            match node.content {
+                Content::Leaf { start, count } => {
+                    found.extend(start..start + count);
+                }
+                Content::Branch { second } => {
+                    stack.push(second);
+                    stack.push(index + 1);
+                }
+            }
+        }
+
+        found
+    }
+
+    fn triangles_overlapping<F, T>(&self, enter: F, test: T, first_only: bool) -> Vec<usize>
+        where F: Fn(&Box3) -> bool,
+              T: Fn(&Triangle) -> bool
+    {
+        let mut found = vec![];
+        let mut stack = self.root();
+
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            if !enter(&node.bounds) {
+                continue;
+            }
+
+            match node.content {
+                Content::Leaf { start, count } => {
+                    for i in start..start + count {
+                        if test(&self.triangles[i]) {
+                            found.push(self.faces[i]);
+                            if first_only {
+                                return found;
+                            }
+                        }
+                    }
+                }
+                Content::Branch { second } => {
+                    stack.push(second);
+                    stack.push(index + 1);
+                }
+            }
+        }
+
+        found
+    }
+}
+
+fn read_triangles(geometry: &BufferGeometry, vertices: &[[usize; 3]]) -> Vec<Triangle> {
+    match geometry.attribute(&AttributeName::Position) {
+        None => vec![],
+        Some(position) => {
+            vertices.iter()
+                .map(|v| {
+                    Triangle::new(&position.vector3(v[0]),
+                                  &position.vector3(v[1]),
+                                  &position.vector3(v[2]))
+                })
+                .collect()
+        }
+    }
+}
+
+fn triangle_bounds(triangle: &Triangle) -> Box3 {
+    Box3::from_points(&[&triangle.a, &triangle.b, &triangle.c])
+}
+
+// half the surface area, only ever compared with other boxes
+fn half_area(bounds: &Box3) -> f32 {
+    if bounds.is_empty() {
+        return 0.0;
+    }
+    let size = bounds.size();
+    size.x * size.y + size.y * size.z + size.z * size.x
+}
+
+// adds the node for faces, which start at start in the leaf order, and everything below it
+fn build(nodes: &mut Vec<Node>,
+         faces: &mut [usize],
+         start: usize,
+         bounds: &[Box3],
+         centroids: &[Vector3]) {
+    let node_bounds = faces.iter().fold(Box3::EMPTY, |acc, &f| acc.union(&bounds[f]));
+    let index = nodes.len();
+    nodes.push(Node {
+        bounds: node_bounds,
+        content: Content::Leaf {
+            start: start,
+            count: faces.len(),
+        },
+    });
+
+    if faces.len() <= MAX_LEAF_SIZE {
+        return;
+    }
+
+    // triangles with the same centroid can't be told apart, they stay together
+    let split = match split(faces, &node_bounds, bounds, centroids) {
+        Some(split) => split,
+        None => return,
+    };
+
+    let (first, second) = faces.split_at_mut(split);
+    build(nodes, first, start, bounds, centroids);
+    let second_index = nodes.len();
+    build(nodes, second, start + split, bounds, centroids);
+    nodes[index].content = Content::Branch { second: second_index };
+}
+
+// sorts the centroids into bins along each axis and partitions faces at the cheapest boundary
+// between two bins, returning where the second half starts
+fn split(faces: &mut [usize],
+         node_bounds: &Box3,
+         bounds: &[Box3],
+         centroids: &[Vector3])
+         -> Option<usize> {
+    let centroid_bounds =
+        faces.iter().fold(Box3::EMPTY, |acc, &f| acc.expand_by_point(&centroids[f]));
+    let bin_of = |f: usize, axis: i32| {
+        let min = *centroid_bounds.min.component(axis);
+        let extent = *centroid_bounds.max.component(axis) - min;
+        let bin = ((*centroids[f].component(axis) - min) / extent * BINS as f32) as usize;
+        bin.min(BINS - 1)
+    };
+
+    let area = half_area(node_bounds);
+    let mut best: Option<(f32, i32, usize)> = None;
+
+    for axis in 0..3 {
+        if *centroid_bounds.max.component(axis) <= *centroid_bounds.min.component(axis) {
+            continue;
+        }
+
+        let mut counts = [0usize; BINS];
+        let mut boxes = [Box3::EMPTY; BINS];
+        for &f in faces.iter() {
+            let bin = bin_of(f, axis);
+            counts[bin] += 1;
+            boxes[bin] = boxes[bin].union(&bounds[f]);
+        }
+
+        // cost of splitting after each bin, the left side swept forwards and the right
+        // side backwards
+        let mut right_costs = [0.0; BINS];
+        let mut right_box = Box3::EMPTY;
+        let mut right_count = 0;
+        for bin in (1..BINS).rev() {
+            right_box = right_box.union(&boxes[bin]);
+            right_count += counts[bin];
+            right_costs[bin - 1] = half_area(&right_box) * right_count as f32;
+        }
+
+        let mut left_box = Box3::EMPTY;
+        let mut left_count = 0;
+        for bin in 0..BINS - 1 {
+            left_box = left_box.union(&boxes[bin]);
+            left_count += counts[bin];
+            if left_count == 0 || left_count == faces.len() {
+                continue;
+            }
+
+            let cost = TRAVERSAL_COST +
+                       (half_area(&left_box) * left_count as f32 + right_costs[bin]) / area;
+            let better = match best {
+                Some((best_cost, _, _)) => cost < best_cost,
+                None => true,
+            };
+            if better {
+                best = Some((cost, axis, bin));
+            }
+        }
+    }
+
+    // in place partition, everything up to bin goes first
+    best.map(|(_, axis, bin)| {
+        let mut first = 0;
+        for i in 0..faces.len() {
+            if bin_of(faces[i], axis) <= bin {
+                faces.swap(first, i);
+                first += 1;
+            }
+        }
+        first
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use core::{AttributeName, BufferGeometry, MeshBvh, VertexAttribute};
+    use geometries::{PlaneGeometry, SphereGeometry};
+    use math::{Box3, Ray, Sphere, Vector3};
+
+    fn sphere() -> BufferGeometry {
+        SphereGeometry {
+                width_segments: 32,
+                height_segments: 16,
+                ..SphereGeometry::DEFAULT
+            }
+            .build()
+    }
+
+    // tests every triangle, what the tree has to agree with
+    fn brute_force(geometry: &BufferGeometry, ray: &Ray) -> Vec<(usize, f32)> {
+        let position = geometry.attribute(&AttributeName::Position).unwrap();
+        let mut hits: Vec<(usize, f32)> = geometry.triangle_indices()
+            .iter()
+            .enumerate()
+            .filter_map(|(f, v)| {
+                ray.intersect_triangle(&position.vector3(v[0]),
+                                        &position.vector3(v[1]),
+                                        &position.vector3(v[2]),
+                                        false)
+                    .map(|point| (f, point.distance_to(&ray.origin)))
+            })
+            .collect();
+        hits.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+        hits
+    }
+
+    #[test]
+    fn raycast() {
+        let geometry = sphere();
+        let bvh = MeshBvh::new(&geometry);
+        assert_eq!(bvh.bounding_box(), geometry.compute_bounding_box());
+
+        for &(origin, direction) in &[(Vector3::new(0.1, 0.2, 5.0), Vector3::NEG_Z),
+                                      (Vector3::new(-3.0, 0.3, 0.4), Vector3::X),
+                                      (Vector3::ZERO, Vector3::new(1.0, 1.0, 1.0).normalized()),
+                                      (Vector3::new(0.0, 2.0, 0.0), Vector3::Y)] {
+            let ray = Ray {
+                origin: origin,
+                direction: direction,
+            };
+            let expected = brute_force(&geometry, &ray);
+            let hits = bvh.raycast(&ray);
+            assert_eq!(hits.len(), expected.len());
+            for (hit, &(face, distance)) in hits.iter().zip(expected.iter()) {
+                assert_eq!(hit.face_index, face);
+                assert!((hit.distance - distance).abs() < 1e-5);
+            }
+
+            match bvh.raycast_first(&ray) {
+                Some(hit) => assert_eq!(hit.face_index, expected[0].0),
+                None => assert!(expected.is_empty()),
+            }
+        }
+
+        // starting inside, only the way out is hit
+        let ray = Ray {
+            origin: Vector3::ZERO,
+            direction: Vector3::NEG_Z,
+        };
+        let hit = bvh.raycast_first(&ray).unwrap();
+        assert!((hit.distance - 1.0).abs() < 1e-2);
+        assert_eq!(bvh.raycast(&ray).len(), 1);
+    }
+
+    #[test]
+    fn overlap() {
+        let geometry = PlaneGeometry {
+                width: 10.0,
+                height: 10.0,
+                width_segments: 10,
+                height_segments: 10,
+            }
+            .build();
+        let bvh = MeshBvh::new(&geometry);
+
+        let sphere = Sphere::new(&Vector3::new(0.5, 0.5, 0.5), 0.4);
+        assert!(!bvh.intersects_sphere(&sphere));
+        assert!(bvh.triangles_in_sphere(&sphere).is_empty());
+
+        // the two triangles of the cell right of and above the center, rows start at the top
+        let sphere = Sphere::new(&Vector3::new(0.5, 0.5, 0.1), 0.2);
+        assert!(bvh.intersects_sphere(&sphere));
+        let mut faces = bvh.triangles_in_sphere(&sphere);
+        faces.sort();
+        assert_eq!(faces, vec![90, 91]);
+
+        // overlaps the bounds of the cell's diagonal neighbours, but not their triangles
+        let box3 = Box3::new(&Vector3::new(0.1, 0.1, -0.1), &Vector3::new(0.9, 0.9, 0.1));
+        let mut faces = bvh.triangles_in_box(&box3);
+        faces.sort();
+        assert_eq!(faces, vec![90, 91]);
+        assert!(bvh.intersects_box(&box3));
+        assert!(!bvh.intersects_box(&box3.translate(&Vector3::new(0.0, 0.0, 0.5))));
+    }
+
+    #[test]
+    fn closest_point_to_point() {
+        let geometry = sphere();
+        let bvh = MeshBvh::new(&geometry);
+
+        let hit = bvh.closest_point_to_point(&Vector3::new(0.0, 0.0, 3.0)).unwrap();
+        assert!((hit.distance - 2.0).abs() < 1e-2);
+        assert!(hit.point.distance_to(&Vector3::Z) < 1e-2);
+        assert_eq!(hit.vertices, geometry.triangle_indices()[hit.face_index]);
+
+        assert_eq!(MeshBvh::new(&BufferGeometry::new()).closest_point_to_point(&Vector3::ZERO),
+                   None);
+    }
+
+    #[test]
+    fn refit() {
+        let mut geometry = sphere();
+        let mut bvh = MeshBvh::new(&geometry);
+        geometry.translate(&Vector3::new(10.0, 0.0, 0.0));
+
+        let ray = Ray {
+            origin: Vector3::new(10.0, 0.0, 5.0),
+            direction: Vector3::NEG_Z,
+        };
+        assert_eq!(bvh.raycast_first(&ray), None);
+
+        bvh.refit(&geometry);
+        assert_eq!(bvh.bounding_box(), geometry.compute_bounding_box());
+        let hit = bvh.raycast_first(&ray).unwrap();
+        assert!((hit.distance - 4.0).abs() < 1e-2);
+        assert_eq!(bvh.raycast(&ray).len(), MeshBvh::new(&geometry).raycast(&ray).len());
+    }
+}
@@ -1,5 +1,22 @@
 mod object3d;
-mod geometry;
+mod buffer_attribute;
+mod buffer_geometry;
+mod interleaved_buffer;
+mod interleaved_buffer_attribute;
+mod layers;
+mod mesh_bvh;
+mod octree;
+mod raycaster;
+mod scene_graph;
 
-pub use self::object3d::{Object3D, HasObject3D};
-pub use self::geometry::Geometry;
\ No newline at end of file
+pub use self::object3d::{Object3D, HasObject3D, ObjectKind};
+pub use self::buffer_attribute::{BufferArray, BufferAttribute, VertexAttribute};
+pub use self::buffer_geometry::{AttributeName, BufferGeometry, DrawRange, GeometryAttribute,
+                                GeometryGroup, Index, NormalWeighting};
+pub use self::interleaved_buffer::InterleavedBuffer;
+pub use self::interleaved_buffer_attribute::InterleavedBufferAttribute;
+pub use self::layers::Layers;
+pub use self::mesh_bvh::{MeshBvh, MeshHit};
+pub use self::octree::Octree;
+pub use self::raycaster::{Face, Intersection, Raycaster};
+pub use self::scene_graph::{SceneGraph, NodeId, Ancestors, Descendants};
\ No newline at end of file
@@ -1,25 +1,46 @@
-use std::rc::Rc;
-use math::{Matrix4, Quaternion, Vector3, Euler};
-use core::Geometry;
+use math::{Matrix4, Quaternion, Vector2, Vector3, Euler};
+use core::{BufferGeometry, Layers};
+
+// how the object draws its geometry, which also decides how a raycaster tests it
+#[derive(Debug,PartialEq,Copy,Clone)]
+pub enum ObjectKind {
+    Mesh,
+    // a strip through all the vertices
+    Line,
+    // a strip closed back to the first vertex
+    LineLoop,
+    // every pair of vertices is a separate line
+    LineSegments,
+    Points,
+    // a camera facing unit quad around the object, center is the anchor within the quad
+    Sprite { center: Vector2 },
+}
 
 #[derive(Debug,PartialEq)]
 pub struct Object3D {
-    pub parent: Option<Box<Object3D>>,
-    pub children: Vec<Box<Object3D>>,
     pub up: Vector3,
 
     pub position: Vector3,
+    // rotation and quaternion describe the same rotation, writing either one is enough. they are
+    // brought back in line by sync_rotation, which everything reading them calls first
     pub rotation: Euler,
     pub quaternion: Quaternion,
     pub scale: Vector3,
 
     pub matrix: Matrix4,
     pub matrix_world: Matrix4,
-    pub geometry: Option<Geometry>,
-
+    pub geometry: Option<BufferGeometry>,
+    pub kind: ObjectKind,
+    pub layers: Layers,
+
+    pub visible: bool,
+    // rotation and quaternion as of the last sync, to tell which one was written since
+    synced_rotation: Euler,
+    synced_quaternion: Quaternion,
+    // the transform of the ancestors that matrix_world was last computed with
+    parent_matrix_world: Matrix4,
     matrix_world_needs_update: bool,
     matrix_auto_update: bool,
-    visible: bool,
     casts_shadows: bool,
     receives_shadows: bool,
     frustum_culled: bool,
@@ -33,8 +54,6 @@ pub trait HasObject3D {
 impl Object3D {
     pub fn next() -> Object3D {
         Object3D {
-            parent: None,
-            children: vec![],
             up: Vector3::new(0.0, 1.0, 0.0),
             position: Vector3::ZERO,
             rotation: Euler::DEFAULT,
@@ -43,10 +62,15 @@ impl Object3D {
             matrix: Matrix4::IDENTITY,
             matrix_world: Matrix4::IDENTITY,
             geometry: None,
+            kind: ObjectKind::Mesh,
+            layers: Layers::DEFAULT,
 
+            visible: true,
+            synced_rotation: Euler::DEFAULT,
+            synced_quaternion: Quaternion::DEFAULT,
+            parent_matrix_world: Matrix4::IDENTITY,
             matrix_world_needs_update: false,
             matrix_auto_update: true,
-            visible: true,
             casts_shadows: false,
             receives_shadows: false,
             frustum_culled: true,
@@ -55,37 +79,63 @@ impl Object3D {
     }
 
     pub fn apply_matrix(&mut self, matrix: &Matrix4) {
+        if self.matrix_auto_update {
+            self.update_matrix();
+        }
         self.matrix = matrix.multiply(&self.matrix);
         let (position, quaternion, scale) = self.matrix.decompose();
         self.position = position;
-        self.quaternion = quaternion;
+        self.set_rotation_from_quaternion(&quaternion);
         self.scale = scale;
     }
 
+    // whichever of rotation and quaternion was written since the last sync is copied to the other,
+    // the euler keeps its order. a write to rotation wins when both were written
+    pub fn sync_rotation(&mut self) {
+        if self.rotation != self.synced_rotation {
+            self.quaternion = Quaternion::from_euler(&self.rotation);
+        } else if self.quaternion != self.synced_quaternion {
+            self.rotation = Euler::from_quaternion(&self.quaternion, &self.rotation.order);
+        } else {
+            return;
+        }
+
+        self.synced_rotation = self.rotation.clone();
+        self.synced_quaternion = self.quaternion.clone();
+    }
+
     pub fn set_rotation_from_axis_angle(&mut self, axis: &Vector3, angle: f32) {
         // assumes axis is normalized
-        self.quaternion = Quaternion::from_axis_angle(axis, angle);
+        self.set_rotation_from_quaternion(&Quaternion::from_axis_angle(axis, angle));
     }
 
     pub fn set_rotation_from_euler(&mut self, euler: &Euler) {
+        self.rotation = euler.clone();
         self.quaternion = Quaternion::from_euler(euler);
+        self.synced_rotation = self.rotation.clone();
+        self.synced_quaternion = self.quaternion.clone();
     }
 
     pub fn set_rotation_from_matrix(&mut self, m: &Matrix4) {
         // assumes the upper 3x3 of m is a pure rotation matrix (i.e, unscaled)
-        self.quaternion = Quaternion::from_rotation_matrix(&m);
+        self.set_rotation_from_quaternion(&Quaternion::from_rotation_matrix(&m));
     }
 
     pub fn set_rotation_from_quaternion(&mut self, q: &Quaternion) {
         // assumes q is normalized
-        self.quaternion = *q;
+        self.quaternion = q.clone();
+        self.rotation = Euler::from_quaternion(q, &self.rotation.order);
+        self.synced_rotation = self.rotation.clone();
+        self.synced_quaternion = self.quaternion.clone();
     }
 
     pub fn rotate_on_axis(&mut self, axis: &Vector3, angle: f32) {
         // rotate object on axis in object space
         // axis is assumed to be normalized
+        self.sync_rotation();
         let q1 = Quaternion::from_axis_angle(axis, angle);
-        self.quaternion = self.quaternion.multiply(&q1);
+        let q = self.quaternion.multiply(&q1);
+        self.set_rotation_from_quaternion(&q);
     }
 
     pub fn rotate_x(&mut self, angle: f32) {
@@ -104,6 +154,7 @@ impl Object3D {
 
         // translate object by distance along axis in object space
         // axis is assumed to be normalized
+        self.sync_rotation();
         let v1 = axis.apply_quaternion(&self.quaternion);
         self.position = self.position.add(&v1.multiply_scalar(distance));
     }
@@ -125,43 +176,43 @@ impl Object3D {
     }
 
     pub fn world_to_local(&self, vector: &Vector3) -> Vector3 {
-        vector.apply_matrix4(&self.matrix_world.inverse());
+        vector.apply_matrix4(&self.matrix_world.inverse())
     }
 
-    pub fn look_at(&mut self, vector: &Vector3) {
-        // This routine does not support objects with rotated and/or translated parent(s)
-        let m1 = Matrix4::IDENTITY.look_at(vector, &self.position, &self.up);
-        self.quaternion = Quaternion::from_rotation_matrix(&m1);
+    // turns the object so its +z faces target in world space, under the parent transform it was
+    // last updated with. up is taken to be in world space. SceneGraph::look_at brings the
+    // ancestors up to date first
+    pub fn look_at(&mut self, target: &Vector3) {
+        self.refresh_matrix_world();
+        let position = self.world_position();
+        let m = Matrix4::IDENTITY.look_at(target, &position, &self.up);
+        self.set_world_rotation(&m);
     }
 
-    pub fn add(&mut self, object: &mut Object3D) {
-
-        if object == self {
-            panic!("object '{:?}' can't be added as a child of itself.", self);
-        }
-
-        match object.parent {
-            Some(parent) => parent.remove(object),
-            None => {}
-        }
-
-        object.parent = Some(Box::new(*self));
-        self.children.push(Box::new(*object));
+    // how cameras and lights look, down their -z
+    pub fn look_at_negative_z(&mut self, target: &Vector3) {
+        self.refresh_matrix_world();
+        let position = self.world_position();
+        let m = Matrix4::IDENTITY.look_at(&position, target, &self.up);
+        self.set_world_rotation(&m);
     }
 
-    pub fn remove(&mut self, object: &mut Object3D) {
-        self.children = self.children.into_iter().filter(|o| Box::into_raw(*o) == object).collect();
+    // sets the local rotation that gives the rotation of m in world space
+    fn set_world_rotation(&mut self, m: &Matrix4) {
+        let parent = self.parent_matrix_world.extract_rotation();
+        let parent_rotation = Quaternion::from_rotation_matrix(&parent).inverse();
+        let q = Quaternion::from_rotation_matrix(m).premultiply(&parent_rotation);
+        self.set_rotation_from_quaternion(&q);
+        self.refresh_matrix_world();
     }
 
-    pub fn world_position(&mut self) -> Vector3 {
-        self.update_matrix_world(true);
+    // the world_* queries read matrix_world as it is, use SceneGraph::update_world_matrix
+    // first if the node or any of its ancestors have moved since the last update
+    pub fn world_position(&self) -> Vector3 {
         Vector3::from_matrix_position(&self.matrix_world)
     }
 
-    pub fn world_quaternion(&mut self) -> Quaternion {
-        unimplemented!();
-
-        self.update_matrix_world(true);
+    pub fn world_quaternion(&self) -> Quaternion {
         let (_, result, _) = self.matrix_world.decompose();
         result
     }
@@ -171,73 +222,94 @@ impl Object3D {
         Euler::from_quaternion(&q, &self.rotation.order)
     }
 
-    pub fn world_scale(&mut self) -> Vector3 {
-        self.update_matrix_world(true);
-        let (position, quaternion, scale) = self.matrix_world.decompose();
-        self.position = position;
-        self.quaternion = quaternion;
-        self.scale = scale;
+    pub fn world_scale(&self) -> Vector3 {
+        let (_, _, scale) = self.matrix_world.decompose();
         scale
     }
 
-    pub fn world_direction(&mut self) -> Vector3 {
+    pub fn world_direction(&self) -> Vector3 {
         let q = self.world_quaternion();
         Vector3::Z.apply_quaternion(&q)
     }
 
-    pub fn traverse(&self, callback: &Fn(&Object3D)) {
-        callback(self);
-
-        for child in self.children {
-            child.traverse(callback);
-        }
-    }
-
-    pub fn traverse_visible(&self, callback: &Fn(&Object3D)) {
-        if self.visible {
-            callback(self);
-
-            for child in self.children {
-                child.traverse_visible(callback);
-            }
-        }
+    pub fn parent_matrix_world(&self) -> Matrix4 {
+        self.parent_matrix_world
     }
 
-    pub fn traverse_ancestors(&self, callback: &Fn(&Object3D)) {
-        match self.parent {
-            Some(parent_rc) => {
-                let parent = Box::into_raw(parent_rc);
-                callback(&*parent);
-                parent.traverse_ancestors(callback);
-            }
-            None => {}
-        }
+    // recomputes matrix_world after the object itself moved, for objects outside a scene graph
+    // or when the ancestors are known not to have moved since the last update
+    pub fn refresh_matrix_world(&mut self) {
+        let parent = self.parent_matrix_world;
+        self.update_matrix_world(Some(&parent), true);
     }
 
     pub fn update_matrix(&mut self) {
+        self.sync_rotation();
         self.matrix = Matrix4::compose(&self.position, &self.quaternion, &self.scale);
         self.matrix_world_needs_update = true;
     }
 
-    pub fn update_matrix_world(&mut self, force: bool) {
-        let mut modifiable_forced = force;
+    // returns true when the world matrix changed, in which case the children have to be
+    // forced to recompute theirs as well
+    pub fn update_matrix_world(&mut self,
+                               parent_matrix_world: Option<&Matrix4>,
+                               force: bool)
+                               -> bool {
         if self.matrix_auto_update {
             self.update_matrix();
         }
 
         if self.matrix_world_needs_update || force {
-            self.matrix_world = match self.parent {
-                None => self.matrix,
-                Some(parent) => parent.matrix_world.multiply(&self.matrix),
+            self.parent_matrix_world = match parent_matrix_world {
+                None => Matrix4::IDENTITY,
+                Some(parent) => *parent,
             };
+            self.matrix_world = self.parent_matrix_world.multiply(&self.matrix);
 
             self.matrix_world_needs_update = false;
-            modifiable_forced = true;
+            true
+        } else {
+            false
         }
+    }
+}
 
-        // update children
-        for child in self.children {
-            child.update_matrix_world(modifiable_forced)
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+    use core::Object3D;
+    use math::{Euler, EulerOrder, Matrix4, Quaternion, Vector3};
+
+    #[test]
+    fn rotation_sync() {
+        // written like ported three.js code would, through the euler
+        let mut object = Object3D::next();
+        object.rotation.y = FRAC_PI_2;
+        object.update_matrix_world(None, false);
+        let turned = Vector3::X.apply_matrix4(&object.matrix_world);
+        assert!(turned.distance_to(&Vector3::NEG_Z) < 1e-6);
+        let expected = Quaternion::from_axis_angle(&Vector3::Y, FRAC_PI_2);
+        assert!(object.quaternion.dot(&expected).abs() > 1.0 - 1e-6);
+
+        // and through the quaternion, the euler follows in its own order
+        object.rotation = Euler::new(0.0, 0.0, 0.0, &EulerOrder::YXZ);
+        object.sync_rotation();
+        assert_eq!(object.quaternion, Quaternion::DEFAULT);
+        object.quaternion = Quaternion::from_axis_angle(&Vector3::X, 0.5);
+        object.sync_rotation();
+        assert_eq!(object.rotation.order, EulerOrder::YXZ);
+        assert!((object.rotation.x - 0.5).abs() < 1e-6);
+        assert!(object.rotation.y.abs() < 1e-6);
+
+        // a write to the euler after a method turned the object builds on it
+        let mut object = Object3D::next();
+        object.rotate_y(0.3);
+        assert!((object.rotation.y - 0.3).abs() < 1e-6);
+        object.rotation.x = 0.2;
+        object.update_matrix();
+        let expected = Matrix4::rotation_from_euler(&Euler::new(0.2, 0.3, 0.0, &EulerOrder::XYZ));
+        for i in 0..16 {
+            assert!((object.matrix.elements[i] - expected.elements[i]).abs() < 1e-6);
         }
     }
-}
\ No newline at end of file
+}
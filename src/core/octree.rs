@@ -0,0 +1,452 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use core::NodeId;
+use math::{Box3, Frustum, Ray, Sphere, Vector3};
+
+#[derive(Debug,Clone)]
+struct Cell {
+    center: Vector3,
+    // half the edge of the cell, the loose bounds reach twice as far
+    half_size: f32,
+    depth: usize,
+    parent: Option<usize>,
+    // the first of 8 consecutive cells, the octant is x + 2y + 4z
+    children: Option<usize>,
+    items: Vec<NodeId>,
+    // items here and in every cell below
+    count: usize,
+}
+
+impl Cell {
+    fn new(center: &Vector3, half_size: f32, depth: usize, parent: Option<usize>) -> Cell {
+        Cell {
+            center: *center,
+            half_size: half_size,
+            depth: depth,
+            parent: parent,
+            children: None,
+            items: vec![],
+            count: 0,
+        }
+    }
+
+    fn loose_bounds(&self) -> Box3 {
+        let reach = self.half_size * 2.0;
+        Box3::new(&self.center.subtract_scalar(reach), &self.center.add_scalar(reach))
+    }
+
+    fn octant(&self, point: &Vector3) -> usize {
+        (point.x >= self.center.x) as usize + 2 * (point.y >= self.center.y) as usize +
+        4 * (point.z >= self.center.z) as usize
+    }
+}
+
+#[derive(Debug,Clone)]
+struct Entry {
+    bounds: Box3,
+    cell: usize,
+}
+
+// loose octree over the world bounds of scene nodes. every cell accepts objects reaching up to
+// half its edge past it, so an object sits in the deepest cell about its size that holds its
+// center and never has to be split between cells. objects too big for the region or centered
+// outside of it stay in the root, which is always searched. the bounds are whatever the caller
+// passes, call update whenever a node or its geometry moves
+#[derive(Debug,Clone)]
+pub struct Octree {
+    cells: Vec<Cell>,
+    // unused blocks of 8 cells, left by subtrees that emptied
+    free: Vec<usize>,
+    entries: HashMap<NodeId, Entry>,
+    pub max_depth: usize,
+}
+
+impl Octree {
+    // bounds is the region to subdivide, it is made a cube around its center
+    pub fn new(bounds: &Box3, max_depth: usize) -> Octree {
+        let size = bounds.size();
+        let half_size = size.x.max(size.y).max(size.z) / 2.0;
+
+        Octree {
+            cells: vec![Cell::new(&bounds.center(), half_size, 0, None)],
+            free: vec![],
+            entries: HashMap::new(),
+            max_depth: max_depth,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn contains(&self, id: NodeId) -> bool {
+        self.entries.contains_key(&id)
+    }
+
+    pub fn bounds(&self, id: NodeId) -> Option<Box3> {
+        self.entries.get(&id).map(|entry| entry.bounds)
+    }
+
+    // replaces the bounds when the node is already in the tree
+    pub fn insert(&mut self, id: NodeId, bounds: &Box3) {
+        if self.entries.contains_key(&id) {
+            self.update(id, bounds);
+            return;
+        }
+
+        let cell = self.cell_for(bounds);
+        self.cells[cell].items.push(id);
+        self.count(cell, true);
+        self.entries.insert(id,
+                            Entry {
+                                bounds: *bounds,
+                                cell: cell,
+                            });
+    }
+
+    // moves the node to the cell that fits its new bounds, returns false when it isn't in the
+    // tree
+    pub fn update(&mut self, id: NodeId, bounds: &Box3) -> bool {
+        let current = match self.entries.get(&id) {
+            Some(entry) => entry.cell,
+            None => return false,
+        };
+
+        // small moves usually stay in the same cell
+        if self.fits(current, bounds) {
+            self.entries.get_mut(&id).unwrap().bounds = *bounds;
+            return true;
+        }
+
+        self.remove(id);
+        self.insert(id, bounds);
+        true
+    }
+
+    pub fn remove(&mut self, id: NodeId) -> bool {
+        let entry = match self.entries.remove(&id) {
+            Some(entry) => entry,
+            None => return false,
+        };
+
+        let items = &mut self.cells[entry.cell].items;
+        if let Some(i) = items.iter().position(|&item| item == id) {
+            items.swap_remove(i);
+        }
+        self.count(entry.cell, false);
+        true
+    }
+
+    pub fn clear(&mut self) {
+        let root = Cell::new(&self.cells[0].center, self.cells[0].half_size, 0, None);
+        self.cells = vec![root];
+        self.free.clear();
+        self.entries.clear();
+    }
+
+    // the queries return every node whose bounds pass the test, in no particular order
+    pub fn intersect_frustum(&self, frustum: &Frustum) -> Vec<NodeId> {
+        self.query(|bounds| frustum.intersects_box(bounds))
+    }
+
+    pub fn intersect_sphere(&self, sphere: &Sphere) -> Vec<NodeId> {
+        self.query(|bounds| bounds.intersects_sphere(sphere))
+    }
+
+    pub fn intersect_box(&self, box3: &Box3) -> Vec<NodeId> {
+        self.query(|bounds| bounds.intersects_box(box3))
+    }
+
+    // nodes whose bounds the ray enters, with how far along the ray it does, closest first
+    pub fn intersect_ray(&self, ray: &Ray) -> Vec<(NodeId, f32)> {
+        let mut hits: Vec<(NodeId, f32)> = self.query(|b| ray.distance_to_box(b).is_some())
+            .into_iter()
+            .filter_map(|id| ray.distance_to_box(&self.entries[&id].bounds).map(|d| (id, d)))
+            .collect();
+
+        hits.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(Ordering::Equal));
+        hits
+    }
+
+    fn query<F: Fn(&Box3) -> bool>(&self, test: F) -> Vec<NodeId> {
+        let mut found = vec![];
+        let mut stack = vec![0];
+
+        while let Some(index) = stack.pop() {
+            let cell = &self.cells[index];
+            if cell.count == 0 || (index != 0 && !test(&cell.loose_bounds())) {
+                continue;
+            }
+
+            found.extend(cell.items.iter().filter(|id| test(&self.entries[id].bounds)).cloned());
+
+            if let Some(first) = cell.children {
+                stack.extend(first..first + 8);
+            }
+        }
+
+        found
+    }
+
+    // the depth an object of this size belongs at, the cells there are at least as big as it
+    fn depth_for(&self, bounds: &Box3) -> usize {
+        let size = bounds.size();
+        let extent = size.x.max(size.y).max(size.z);
+        let mut depth = 0;
+        let mut cell_size = self.cells[0].half_size * 2.0;
+
+        while depth < self.max_depth && extent <= cell_size / 2.0 {
+            cell_size /= 2.0;
+            depth += 1;
+        }
+        depth
+    }
+
+    fn in_region(&self, bounds: &Box3) -> bool {
+        let root = &self.cells[0];
+        let center = bounds.center();
+        let size = bounds.size();
+        let reach = root.half_size;
+
+        (center.x - root.center.x).abs() <= reach && (center.y - root.center.y).abs() <= reach &&
+        (center.z - root.center.z).abs() <= reach && size.x.max(size.y).max(size.z) <= reach * 2.0
+    }
+
+    // whether the object belongs in exactly this cell
+    fn fits(&self, index: usize, bounds: &Box3) -> bool {
+        if !self.in_region(bounds) {
+            return index == 0;
+        }
+
+        let cell = &self.cells[index];
+        let center = bounds.center();
+        cell.depth == self.depth_for(bounds) &&
+        (center.x - cell.center.x).abs() <= cell.half_size &&
+        (center.y - cell.center.y).abs() <= cell.half_size &&
+        (center.z - cell.center.z).abs() <= cell.half_size
+    }
+
+    // walks down to the cell the object belongs in, splitting cells on the way
+    fn cell_for(&mut self, bounds: &Box3) -> usize {
+        if !self.in_region(bounds) {
+            return 0;
+        }
+
+        let depth = self.depth_for(bounds);
+        let center = bounds.center();
+        let mut index = 0;
+
+        while self.cells[index].depth < depth {
+            let first = match self.cells[index].children {
+                Some(first) => first,
+                None => self.split(index),
+            };
+            index = first + self.cells[index].octant(&center);
+        }
+        index
+    }
+
+    fn split(&mut self, index: usize) -> usize {
+        let (center, half_size, depth) = {
+            let cell = &self.cells[index];
+            (cell.center, cell.half_size / 2.0, cell.depth + 1)
+        };
+
+        let children: Vec<Cell> = (0..8)
+            .map(|octant| {
+                let offset = Vector3::new(if octant & 1 == 0 { -half_size } else { half_size },
+                                          if octant & 2 == 0 { -half_size } else { half_size },
+                                          if octant & 4 == 0 { -half_size } else { half_size });
+                Cell::new(&center.add(&offset), half_size, depth, Some(index))
+            })
+            .collect();
+
+        let first = match self.free.pop() {
+            Some(first) => {
+                for (i, child) in children.into_iter().enumerate() {
+                    self.cells[first + i] = child;
+                }
+                first
+            }
+            None => {
+                let first = self.cells.len();
+                self.cells.extend(children);
+                first
+            }
+        };
+
+        self.cells[index].children = Some(first);
+        first
+    }
+
+    // keeps the counts up to date from the cell to the root, and gives back the cells below
+    // the highest one that emptied
+    fn count(&mut self, index: usize, added: bool) {
+        let mut emptied = None;
+        let mut current = Some(index);
+
+        while let Some(i) = current {
+            let cell = &mut self.cells[i];
+            if added {
+                cell.count += 1;
+            } else {
+                cell.count -= 1;
+                if cell.count == 0 {
+                    emptied = Some(i);
+                }
+            }
+            current = cell.parent;
+        }
+
+        if let Some(i) = emptied {
+            self.release_children(i);
+        }
+    }
+
+    fn release_children(&mut self, index: usize) {
+        if let Some(first) = self.cells[index].children.take() {
+            for child in first..first + 8 {
+                self.release_children(child);
+            }
+            self.free.push(first);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::{Object3D, Octree, SceneGraph};
+    use math::{Box3, Frustum, Matrix4, Ray, Sphere, Vector3};
+
+    fn cube(center: &Vector3, size: f32) -> Box3 {
+        Box3::from_center_and_size(center, &Vector3::new(size, size, size))
+    }
+
+    #[test]
+    fn insert() {
+        let mut graph = SceneGraph::new();
+        let ids: Vec<_> = (0..4).map(|_| graph.insert(Object3D::next())).collect();
+        let region = cube(&Vector3::ZERO, 100.0);
+        let mut a = Octree::new(&region, 6);
+        assert!(a.is_empty());
+
+        a.insert(ids[0], &cube(&Vector3::new(10.0, 10.0, 10.0), 1.0));
+        a.insert(ids[1], &cube(&Vector3::new(-30.0, 5.0, 0.0), 2.0));
+        // bigger than the region, and outside of it
+        a.insert(ids[2], &cube(&Vector3::ZERO, 500.0));
+        a.insert(ids[3], &cube(&Vector3::new(0.0, 200.0, 0.0), 1.0));
+        assert_eq!(a.len(), 4);
+
+        let mut found = a.intersect_box(&cube(&Vector3::new(10.0, 10.0, 10.0), 2.0));
+        found.sort_by_key(|id| ids.iter().position(|i| i == id));
+        assert_eq!(found, vec![ids[0], ids[2]]);
+
+        let found = a.intersect_sphere(&Sphere::new(&Vector3::new(0.0, 200.0, 0.0), 1.0));
+        assert_eq!(found.len(), 2);
+        assert!(found.contains(&ids[3]));
+
+        // moved far enough to change cells
+        assert!(a.update(ids[0], &cube(&Vector3::new(-10.0, -10.0, -10.0), 1.0)));
+        assert!(!a.intersect_box(&cube(&Vector3::new(10.0, 10.0, 10.0), 2.0)).contains(&ids[0]));
+        assert!(a.intersect_box(&cube(&Vector3::new(-10.0, -10.0, -10.0), 2.0))
+            .contains(&ids[0]));
+
+        assert!(a.remove(ids[2]));
+        assert!(!a.remove(ids[2]));
+        assert!(!a.update(ids[2], &region));
+        assert_eq!(a.bounds(ids[2]), None);
+        assert!(a.intersect_box(&cube(&Vector3::new(40.0, 40.0, 40.0), 1.0)).is_empty());
+        assert_eq!(a.len(), 3);
+
+        a.clear();
+        assert!(a.is_empty());
+        assert!(a.intersect_box(&region).is_empty());
+    }
+
+    #[test]
+    fn reuse() {
+        let mut graph = SceneGraph::new();
+        let id = graph.insert(Object3D::next());
+        let mut a = Octree::new(&cube(&Vector3::ZERO, 64.0), 5);
+
+        // removing the only object gives the split cells back, so moving it around doesn't
+        // keep growing the tree
+        a.insert(id, &cube(&Vector3::new(1.0, 1.0, 1.0), 1.0));
+        let cells = a.cells.len();
+        for i in 0..20 {
+            let center = Vector3::new(i as f32 - 10.0, 1.0, -1.0);
+            a.remove(id);
+            a.insert(id, &cube(&center, 1.0));
+            assert_eq!(a.intersect_box(&cube(&center, 0.5)), vec![id]);
+        }
+        assert_eq!(a.cells.len(), cells);
+    }
+
+    #[test]
+    fn queries() {
+        let mut graph = SceneGraph::new();
+        let mut a = Octree::new(&cube(&Vector3::ZERO, 200.0), 8);
+        let mut ids = vec![];
+        for x in 0..10 {
+            for z in 0..10 {
+                let id = graph.insert(Object3D::next());
+                let center = Vector3::new(x as f32 * 10.0 - 45.0, 0.0, z as f32 * 10.0 - 45.0);
+                a.insert(id, &cube(&center, 2.0));
+                ids.push((id, center));
+            }
+        }
+
+        // everything in front of a camera at the origin looking down -z
+        let projection = Matrix4::from_frustum(-0.1, 0.1, -0.1, 0.1, 0.1, 1000.0);
+        let frustum = Frustum::from_matrix4(&projection);
+        let visible = a.intersect_frustum(&frustum);
+        let expected: Vec<_> = ids.iter()
+            .filter(|&&(_, center)| frustum.intersects_box(&cube(&center, 2.0)))
+            .map(|&(id, _)| id)
+            .collect();
+        assert!(!expected.is_empty() && expected.len() < ids.len());
+        assert_eq!(visible.len(), expected.len());
+        assert!(expected.iter().all(|id| visible.contains(id)));
+
+        let ray = Ray {
+            origin: Vector3::new(-100.0, 0.0, 5.0),
+            direction: Vector3::X,
+        };
+        let hits = a.intersect_ray(&ray);
+        assert_eq!(hits.len(), 10);
+        assert!(hits.windows(2).all(|pair| pair[0].1 <= pair[1].1));
+        assert_eq!(hits[0].0, ids[5].0);
+        assert_eq!(hits[0].1, 54.0);
+    }
+
+    #[test]
+    fn empty_bounds() {
+        // groups, cameras and lights have no geometry, so their bounds are empty
+        let mut graph = SceneGraph::new();
+        let group = graph.insert(Object3D::next());
+        let mesh = graph.insert(Object3D::next());
+        let mut a = Octree::new(&cube(&Vector3::ZERO, 200.0), 8);
+        a.insert(group, &Box3::from_object(&graph, group, false));
+        a.insert(mesh, &cube(&Vector3::new(10.0, 10.0, 10.0), 2.0));
+
+        let rays = [Ray {
+                        origin: Vector3::new(-50.0, -50.0, -50.0),
+                        direction: Vector3::ONE.normalized(),
+                    },
+                    Ray {
+                        origin: Vector3::new(5.0, 5.0, 5.0),
+                        direction: Vector3::new(1.0, -2.0, 3.0).normalized(),
+                    }];
+        for ray in &rays {
+            assert!(a.intersect_ray(ray).iter().all(|&(id, _)| id != group));
+        }
+
+        let hits = a.intersect_ray(&rays[0]);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].0, mesh);
+    }
+}
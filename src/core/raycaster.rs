@@ -0,0 +1,662 @@
+use std::cmp::Ordering;
+use std::f32::INFINITY;
+use cameras::Camera;
+use core::{AttributeName, BufferGeometry, DrawRange, Layers, NodeId, Object3D, ObjectKind,
+           SceneGraph, VertexAttribute};
+use math::{Matrix4, Ray, Sphere, Triangle, Vector2, Vector3};
+
+// the vertex indices of a hit triangle and its normal in world space
+#[derive(Debug,PartialEq,Copy,Clone)]
+pub struct Face {
+    pub a: usize,
+    pub b: usize,
+    pub c: usize,
+    pub normal: Vector3,
+}
+
+#[derive(Debug,PartialEq,Clone)]
+pub struct Intersection {
+    // from the ray origin to point
+    pub distance: f32,
+    pub point: Vector3,
+    pub object: NodeId,
+    // meshes, the triangle that was hit
+    pub face_index: Option<usize>,
+    pub face: Option<Face>,
+    // meshes and sprites, interpolated from the barycentric coordinates of the hit
+    pub uv: Option<Vector2>,
+    // lines and points, the position in the index (or vertex) list of the hit segment start
+    // or point
+    pub index: Option<usize>,
+    // points, how far the ray passes by the point
+    pub distance_to_ray: Option<f32>,
+}
+
+impl Intersection {
+    fn new(distance: f32, point: &Vector3, object: NodeId) -> Intersection {
+        Intersection {
+            distance: distance,
+            point: *point,
+            object: object,
+            face_index: None,
+            face: None,
+            uv: None,
+            index: None,
+            distance_to_ray: None,
+        }
+    }
+}
+
+// world matrices are read as they are, update them on the scene graph before casting
+#[derive(Debug,PartialEq,Clone)]
+pub struct Raycaster {
+    pub ray: Ray,
+    pub near: f32,
+    pub far: f32,
+    pub layers: Layers,
+    // how close in world units the ray has to pass by a line or a point to hit it
+    pub line_threshold: f32,
+    pub points_threshold: f32,
+    // sprites turn to face the camera, they are skipped as long as it is unknown
+    pub camera_matrix_world: Option<Matrix4>,
+}
+
+impl Raycaster {
+    // direction is assumed to be normalized
+    pub fn new(origin: &Vector3, direction: &Vector3) -> Raycaster {
+        Raycaster {
+            ray: Ray {
+                origin: *origin,
+                direction: *direction,
+            },
+            near: 0.0,
+            far: INFINITY,
+            layers: Layers::DEFAULT,
+            line_threshold: 1.0,
+            points_threshold: 1.0,
+            camera_matrix_world: None,
+        }
+    }
+
+    pub fn set(&mut self, origin: &Vector3, direction: &Vector3) {
+        self.ray = Ray {
+            origin: *origin,
+            direction: *direction,
+        };
+    }
+
+    // ndc runs from -1 to 1 on both axes, y up. perspective rays start at the camera and go
+    // through the point, orthographic ones are parallel to the view direction and start on the
+    // camera plane. the camera's world matrix has to be up to date
+    pub fn set_from_camera(&mut self, ndc: &Vector2, camera: &Camera) {
+        let projection = camera.projection_matrix();
+        let matrix_world = camera.scene_object().matrix_world;
+
+        // only perspective projections take w from the depth
+        if projection.elements[15] == 0.0 {
+            let origin = Vector3::ZERO.apply_matrix4(&matrix_world);
+            let direction = Vector3::new(ndc.x, ndc.y, 0.5)
+                .unproject(camera)
+                .subtract(&origin)
+                .normalized();
+            self.set(&origin, &direction);
+        } else {
+            // the depth the camera plane projects to
+            let origin = Vector3::new(ndc.x, ndc.y, projection.elements[14]).unproject(camera);
+            let direction = Vector3::NEG_Z.transform_direction(&matrix_world);
+            self.set(&origin, &direction);
+        }
+
+        self.camera_matrix_world = Some(matrix_world);
+    }
+
+    // closest first. the descendants are tested as well when recursive, whether or not the
+    // node itself is on a matching layer
+    pub fn intersect_object(&self,
+                            graph: &SceneGraph,
+                            id: NodeId,
+                            recursive: bool)
+                            -> Vec<Intersection> {
+        self.intersect_objects(graph, &[id], recursive)
+    }
+
+    pub fn intersect_objects(&self,
+                             graph: &SceneGraph,
+                             ids: &[NodeId],
+                             recursive: bool)
+                             -> Vec<Intersection> {
+        let mut intersections = vec![];
+
+        for &id in ids {
+            self.raycast(graph, id, &mut intersections);
+
+            if recursive {
+                for descendant in graph.descendants(id) {
+                    self.raycast(graph, descendant, &mut intersections);
+                }
+            }
+        }
+
+        intersections.sort_by(|a, b| {
+            a.distance.partial_cmp(&b.distance).unwrap_or(Ordering::Equal)
+        });
+        intersections
+    }
+
+    fn raycast(&self, graph: &SceneGraph, id: NodeId, intersections: &mut Vec<Intersection>) {
+        let object = &graph[id];
+        if !object.layers.test(&self.layers) {
+            return;
+        }
+
+        if let ObjectKind::Sprite { ref center } = object.kind {
+            return self.raycast_sprite(object, id, center, intersections);
+        }
+
+        let geometry = match object.geometry {
+            Some(ref geometry) => geometry,
+            None => return,
+        };
+
+        // degenerate objects have nothing to hit
+        if object.matrix_world.determinant() == 0.0 {
+            return;
+        }
+
+        match object.kind {
+            ObjectKind::Mesh => self.raycast_mesh(object, id, geometry, intersections),
+            ObjectKind::Line => self.raycast_line(object, id, geometry, 1, false, intersections),
+            ObjectKind::LineLoop => {
+                self.raycast_line(object, id, geometry, 1, true, intersections)
+            }
+            ObjectKind::LineSegments => {
+                self.raycast_line(object, id, geometry, 2, false, intersections)
+            }
+            ObjectKind::Points => self.raycast_points(object, id, geometry, intersections),
+            ObjectKind::Sprite { .. } => {}
+        }
+    }
+
+    fn in_range(&self, distance: f32) -> bool {
+        distance >= self.near && distance <= self.far
+    }
+
+    // the cheap rejection every kind starts with, grown by the threshold for lines and points
+    fn misses_bounds(&self, object: &Object3D, geometry: &BufferGeometry, grow: f32) -> bool {
+        let sphere = geometry.bounding_sphere.unwrap_or_else(|| geometry.compute_bounding_sphere());
+        let sphere = sphere.apply_matrix4(&object.matrix_world);
+        let sphere = Sphere::new(&sphere.center, sphere.radius + grow);
+        !self.ray.intersects_sphere(&sphere)
+    }
+
+    // the vertex positions covered by the draw range, through the index when there is one
+    fn draw_range(geometry: &BufferGeometry) -> Vec<usize> {
+        let count = match geometry.index {
+            Some(ref index) => index.len(),
+            None => geometry.vertex_count(),
+        };
+        let start = geometry.draw_range.start.min(count);
+        let end = match geometry.draw_range.count {
+            Some(range) => (start + range).min(count),
+            None => count,
+        };
+
+        match geometry.index {
+            Some(ref index) => (start..end).map(|i| index.get(i)).collect(),
+            None => (start..end).collect(),
+        }
+    }
+
+    fn raycast_mesh(&self,
+                    object: &Object3D,
+                    id: NodeId,
+                    geometry: &BufferGeometry,
+                    intersections: &mut Vec<Intersection>) {
+        let position = match geometry.attribute(&AttributeName::Position) {
+            Some(position) => position,
+            None => return,
+        };
+
+        if self.misses_bounds(object, geometry, 0.0) {
+            return;
+        }
+
+        let local_ray = self.ray.apply_matrix_4(&object.matrix_world.inverse());
+        let bounding_box = geometry.bounding_box.unwrap_or_else(|| geometry.compute_bounding_box());
+        if local_ray.intersect_box(&bounding_box).is_none() {
+            return;
+        }
+
+        // the tree covers every triangle, it can't be used to cast against part of them
+        if let Some(ref tree) = geometry.bounds_tree {
+            if geometry.draw_range == DrawRange::ALL {
+                for hit in tree.raycast(&local_ray) {
+                    let intersection =
+                        self.mesh_intersection(object, id, geometry, hit.face_index, hit.vertices,
+                                               &hit.point);
+                    intersections.extend(intersection);
+                }
+                return;
+            }
+        }
+
+        let vertices = Raycaster::draw_range(geometry);
+        let first_face = geometry.draw_range.start / 3;
+
+        for (f, face) in vertices.chunks(3).enumerate() {
+            if face.len() < 3 {
+                break;
+            }
+
+            // both sides count, there are no materials to say otherwise
+            let local_point = match local_ray.intersect_triangle(&position.vector3(face[0]),
+                                                                 &position.vector3(face[1]),
+                                                                 &position.vector3(face[2]),
+                                                                 false) {
+                Some(point) => point,
+                None => continue,
+            };
+
+            let intersection = self.mesh_intersection(object,
+                                                      id,
+                                                      geometry,
+                                                      first_face + f,
+                                                      [face[0], face[1], face[2]],
+                                                      &local_point);
+            intersections.extend(intersection);
+        }
+    }
+
+    // local_point is on the triangle with the given vertices, in the object's space
+    fn mesh_intersection(&self,
+                         object: &Object3D,
+                         id: NodeId,
+                         geometry: &BufferGeometry,
+                         face_index: usize,
+                         vertices: [usize; 3],
+                         local_point: &Vector3)
+                         -> Option<Intersection> {
+        let point = local_point.apply_matrix4(&object.matrix_world);
+        let distance = self.ray.origin.distance_to(&point);
+        if !self.in_range(distance) {
+            return None;
+        }
+
+        let [a, b, c] = vertices;
+        // raycast_mesh only gets here with positions
+        let position = geometry.attribute(&AttributeName::Position).unwrap();
+        let triangle = Triangle::new(&position.vector3(a),
+                                     &position.vector3(b),
+                                     &position.vector3(c));
+        let world = Triangle::new(&triangle.a.apply_matrix4(&object.matrix_world),
+                                  &triangle.b.apply_matrix4(&object.matrix_world),
+                                  &triangle.c.apply_matrix4(&object.matrix_world));
+
+        let mut intersection = Intersection::new(distance, &point, id);
+        intersection.face_index = Some(face_index);
+        intersection.face = Some(Face {
+            a: a,
+            b: b,
+            c: c,
+            normal: world.normal(),
+        });
+        let uv = geometry.attribute(&AttributeName::Uv);
+        let weights = triangle.barycentric_coordinates_from_point(local_point);
+        intersection.uv = match (uv, weights) {
+            (Some(uv), Some(weights)) => {
+                Some(uv.vector2(a)
+                    .multiply_scalar(weights.x)
+                    .add(&uv.vector2(b).multiply_scalar(weights.y))
+                    .add(&uv.vector2(c).multiply_scalar(weights.z)))
+            }
+            _ => None,
+        };
+        Some(intersection)
+    }
+
+    fn raycast_line(&self,
+                    object: &Object3D,
+                    id: NodeId,
+                    geometry: &BufferGeometry,
+                    step: usize,
+                    closed: bool,
+                    intersections: &mut Vec<Intersection>) {
+        let position = match geometry.attribute(&AttributeName::Position) {
+            Some(position) => position,
+            None => return,
+        };
+
+        if self.misses_bounds(object, geometry, self.line_threshold) {
+            return;
+        }
+
+        let local_ray = self.ray.apply_matrix_4(&object.matrix_world.inverse());
+        let scale = &object.scale;
+        let local_threshold = self.line_threshold / ((scale.x + scale.y + scale.z) / 3.0);
+        let local_threshold_squared = local_threshold * local_threshold;
+
+        let vertices = Raycaster::draw_range(geometry);
+        let start = geometry.draw_range.start;
+        let mut segments: Vec<(usize, usize, usize)> = (0..vertices.len().saturating_sub(1))
+            .filter(|i| i % step == 0)
+            .map(|i| (start + i, vertices[i], vertices[i + 1]))
+            .collect();
+        if closed && vertices.len() > 1 {
+            segments.push((start + vertices.len() - 1, vertices[vertices.len() - 1], vertices[0]));
+        }
+
+        for (i, a, b) in segments {
+            let (distance_squared, on_ray, on_segment) =
+                local_ray.distance_squared_to_segment(&position.vector3(a), &position.vector3(b));
+            if distance_squared > local_threshold_squared {
+                continue;
+            }
+
+            let distance = self.ray.origin.distance_to(&on_ray.apply_matrix4(&object.matrix_world));
+            if !self.in_range(distance) {
+                continue;
+            }
+
+            let point = on_segment.apply_matrix4(&object.matrix_world);
+            let mut intersection = Intersection::new(distance, &point, id);
+            intersection.index = Some(i);
+            intersections.push(intersection);
+        }
+    }
+
+    fn raycast_points(&self,
+                      object: &Object3D,
+                      id: NodeId,
+                      geometry: &BufferGeometry,
+                      intersections: &mut Vec<Intersection>) {
+        let position = match geometry.attribute(&AttributeName::Position) {
+            Some(position) => position,
+            None => return,
+        };
+
+        if self.misses_bounds(object, geometry, self.points_threshold) {
+            return;
+        }
+
+        let local_ray = self.ray.apply_matrix_4(&object.matrix_world.inverse());
+        let scale = &object.scale;
+        let local_threshold = self.points_threshold / ((scale.x + scale.y + scale.z) / 3.0);
+        let local_threshold_squared = local_threshold * local_threshold;
+        let start = geometry.draw_range.start;
+
+        for (i, vertex) in Raycaster::draw_range(geometry).into_iter().enumerate() {
+            let local_point = position.vector3(vertex);
+            let distance_squared = local_ray.distance_squared_to_point(&local_point);
+            if distance_squared >= local_threshold_squared {
+                continue;
+            }
+
+            let point = local_ray.closest_point_to_point(&local_point)
+                .apply_matrix4(&object.matrix_world);
+            let distance = self.ray.origin.distance_to(&point);
+            if !self.in_range(distance) {
+                continue;
+            }
+
+            let mut intersection = Intersection::new(distance, &point, id);
+            intersection.index = Some(start + i);
+            intersection.distance_to_ray = Some(distance_squared.sqrt());
+            intersections.push(intersection);
+        }
+    }
+
+    fn raycast_sprite(&self,
+                      object: &Object3D,
+                      id: NodeId,
+                      center: &Vector2,
+                      intersections: &mut Vec<Intersection>) {
+        let camera_matrix_world = match self.camera_matrix_world {
+            Some(ref matrix) => matrix,
+            None => return,
+        };
+
+        let world_scale = Vector3::from_matrix_scale(&object.matrix_world);
+        let model_view = camera_matrix_world.inverse().multiply(&object.matrix_world);
+        let mv_position = Vector3::from_matrix_position(&model_view);
+
+        // the quad corner in view space, moved back into world space
+        let corner = |x: f32, y: f32| {
+            let aligned = Vector2::new((x - center.x + 0.5) * world_scale.x,
+                                       (y - center.y + 0.5) * world_scale.y);
+            Vector3::new(mv_position.x + aligned.x,
+                         mv_position.y + aligned.y,
+                         mv_position.z)
+                .apply_matrix4(camera_matrix_world)
+        };
+
+        let a = corner(-0.5, -0.5);
+        let b = corner(0.5, -0.5);
+        let c = corner(0.5, 0.5);
+        let d = corner(-0.5, 0.5);
+
+        // the quad is split along a-c, a has uv (0, 0)
+        let halves = [(b, c, Vector2::new(1.0, 0.0), Vector2::new(1.0, 1.0)),
+                      (c, d, Vector2::new(1.0, 1.0), Vector2::new(0.0, 1.0))];
+        let hit = halves.iter().filter_map(|&(v1, v2, uv1, uv2)| {
+            self.ray.intersect_triangle(&a, &v1, &v2, false).map(|point| {
+                let weights = Triangle::new(&a, &v1, &v2)
+                    .barycentric_coordinates_from_point(&point);
+                (point, weights.map(|w| uv1.multiply_scalar(w.y).add(&uv2.multiply_scalar(w.z))))
+            })
+        }).next();
+
+        if let Some((point, uv)) = hit {
+            let distance = self.ray.origin.distance_to(&point);
+            if !self.in_range(distance) {
+                return;
+            }
+
+            let mut intersection = Intersection::new(distance, &point, id);
+            intersection.uv = uv;
+            intersections.push(intersection);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cameras::{Camera, OrthographicCamera, PerspectiveCamera};
+    use core::{BufferGeometry, Layers, Object3D, ObjectKind, Raycaster, SceneGraph};
+    use geometries::{PlaneGeometry, SphereGeometry};
+    use math::{Matrix4, Vector2, Vector3};
+
+    fn object(kind: ObjectKind, geometry: BufferGeometry, position: &Vector3) -> Object3D {
+        let mut object = Object3D::next();
+        object.kind = kind;
+        object.geometry = Some(geometry);
+        object.position = *position;
+        object
+    }
+
+    fn plane(position: &Vector3) -> Object3D {
+        let geometry = PlaneGeometry { width: 2.0, height: 2.0, ..PlaneGeometry::DEFAULT }.build();
+        object(ObjectKind::Mesh, geometry, position)
+    }
+
+    #[test]
+    fn mesh() {
+        let mut graph = SceneGraph::new();
+        let root = graph.insert(Object3D::next());
+        let near = graph.insert_child(root, plane(&Vector3::new(0.0, 0.0, 1.0)));
+        let far = graph.insert_child(near, plane(&Vector3::new(0.5, 0.0, -2.0)));
+        graph.update_matrix_world();
+
+        // off the diagonal shared by the two triangles of each plane
+        let raycaster = Raycaster::new(&Vector3::new(0.5, 0.25, 5.0), &Vector3::NEG_Z);
+        assert!(raycaster.intersect_object(&graph, root, false).is_empty());
+
+        let hits = raycaster.intersect_object(&graph, root, true);
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].object, near);
+        assert_eq!(hits[0].distance, 4.0);
+        assert_eq!(hits[0].point, Vector3::new(0.5, 0.25, 1.0));
+        assert_eq!(hits[0].face.unwrap().normal, Vector3::Z);
+        assert_eq!(hits[0].face_index, Some(1));
+        assert!(hits[0].uv.unwrap().distance_to(&Vector2::new(0.75, 0.625)) < 1e-6);
+
+        // the child is placed relative to its parent
+        assert_eq!(hits[1].object, far);
+        assert_eq!(hits[1].distance, 6.0);
+        assert_eq!(hits[1].face_index, Some(0));
+        assert!(hits[1].uv.unwrap().distance_to(&Vector2::new(0.5, 0.625)) < 1e-6);
+
+        let mut limited = raycaster.clone();
+        limited.far = 5.0;
+        assert_eq!(limited.intersect_object(&graph, root, true).len(), 1);
+        limited.near = 4.5;
+        assert!(limited.intersect_object(&graph, root, true).is_empty());
+
+        // layers filter the nodes, not their subtrees
+        graph[near].layers.set(1);
+        let hits = raycaster.intersect_object(&graph, root, true);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].object, far);
+
+        let mut layered = raycaster.clone();
+        layered.layers = Layers::ALL;
+        assert_eq!(layered.intersect_object(&graph, root, true).len(), 2);
+    }
+
+    #[test]
+    fn bounds_tree() {
+        let mut graph = SceneGraph::new();
+        let geometry = SphereGeometry::DEFAULT.build();
+        let plain = graph.insert(object(ObjectKind::Mesh, geometry.clone(), &Vector3::ZERO));
+        let mut tree = object(ObjectKind::Mesh, geometry, &Vector3::ZERO);
+        tree.geometry.as_mut().unwrap().update_bounds_tree();
+        let tree = graph.insert(tree);
+        graph.update_matrix_world();
+
+        let raycaster = Raycaster::new(&Vector3::new(0.3, 0.2, 5.0), &Vector3::NEG_Z);
+        let expected = raycaster.intersect_object(&graph, plain, false);
+        let hits = raycaster.intersect_object(&graph, tree, false);
+        assert_eq!(hits.len(), 2);
+        for (hit, expected) in hits.iter().zip(expected.iter()) {
+            assert_eq!(hit.object, tree);
+            assert_eq!(hit.distance, expected.distance);
+            assert_eq!(hit.face_index, expected.face_index);
+            assert_eq!(hit.face, expected.face);
+            assert_eq!(hit.uv, expected.uv);
+        }
+    }
+
+    #[test]
+    fn line() {
+        let mut graph = SceneGraph::new();
+        let points = [Vector3::new(-1.0, 0.0, 0.0),
+                      Vector3::new(1.0, 0.0, 0.0),
+                      Vector3::new(1.0, 2.0, 0.0)];
+        let strip = graph.insert(object(ObjectKind::Line,
+                                        BufferGeometry::from_points(&points),
+                                        &Vector3::ZERO));
+        graph.update_matrix_world();
+
+        let mut raycaster = Raycaster::new(&Vector3::new(0.0, 0.1, 5.0), &Vector3::NEG_Z);
+        raycaster.line_threshold = 0.2;
+        let hits = raycaster.intersect_object(&graph, strip, false);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].index, Some(0));
+        assert!(hits[0].point.distance_to(&Vector3::ZERO) < 1e-6);
+        // measured along the ray, not to the line
+        assert!((hits[0].distance - 5.0).abs() < 1e-5);
+
+        raycaster.line_threshold = 0.05;
+        assert!(raycaster.intersect_object(&graph, strip, false).is_empty());
+
+        // closing the loop adds the segment from the last point back to the first
+        raycaster.set(&Vector3::new(0.0, 1.0, 5.0), &Vector3::NEG_Z);
+        assert!(raycaster.intersect_object(&graph, strip, false).is_empty());
+        graph[strip].kind = ObjectKind::LineLoop;
+        let hits = raycaster.intersect_object(&graph, strip, false);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].index, Some(2));
+
+        // pairs don't connect to each other
+        graph[strip].kind = ObjectKind::LineSegments;
+        raycaster.set(&Vector3::new(1.0, 1.0, 5.0), &Vector3::NEG_Z);
+        assert!(raycaster.intersect_object(&graph, strip, false).is_empty());
+    }
+
+    #[test]
+    fn points() {
+        let mut graph = SceneGraph::new();
+        let points = [Vector3::ZERO, Vector3::new(0.0, 0.0, -1.0), Vector3::new(3.0, 0.0, 0.0)];
+        let cloud = graph.insert(object(ObjectKind::Points,
+                                        BufferGeometry::from_points(&points),
+                                        &Vector3::new(0.0, 0.0, 1.0)));
+        graph.update_matrix_world();
+
+        let mut raycaster = Raycaster::new(&Vector3::new(0.1, 0.0, 5.0), &Vector3::NEG_Z);
+        raycaster.points_threshold = 0.5;
+        let hits = raycaster.intersect_object(&graph, cloud, false);
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].index, Some(0));
+        assert_eq!(hits[1].index, Some(1));
+        assert!((hits[0].distance_to_ray.unwrap() - 0.1).abs() < 1e-6);
+        assert!(hits[0].point.distance_to(&Vector3::new(0.1, 0.0, 1.0)) < 1e-6);
+        assert!((hits[1].distance - 5.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn sprite() {
+        let mut graph = SceneGraph::new();
+        let mut sprite = Object3D::next();
+        sprite.kind = ObjectKind::Sprite { center: Vector2::new(0.5, 0.5) };
+        sprite.position = Vector3::new(1.0, 0.0, 0.0);
+        sprite.scale = Vector3::new(2.0, 2.0, 1.0);
+        let id = graph.insert(sprite);
+        graph.update_matrix_world();
+
+        let mut raycaster = Raycaster::new(&Vector3::new(1.5, 0.5, 5.0), &Vector3::NEG_Z);
+        assert!(raycaster.intersect_object(&graph, id, false).is_empty());
+
+        raycaster.camera_matrix_world = Some(Matrix4::from_translation(&Vector3::new(0.0,
+                                                                                     0.0,
+                                                                                     5.0)));
+        let hits = raycaster.intersect_object(&graph, id, false);
+        assert_eq!(hits.len(), 1);
+        assert!(hits[0].point.distance_to(&Vector3::new(1.5, 0.5, 0.0)) < 1e-6);
+        let uv = hits[0].uv.unwrap();
+        assert!(uv.distance_to(&Vector2::new(0.75, 0.75)) < 1e-6);
+
+        // the quad is 2 wide
+        raycaster.set(&Vector3::new(2.1, 0.0, 5.0), &Vector3::NEG_Z);
+        assert!(raycaster.intersect_object(&graph, id, false).is_empty());
+    }
+
+    #[test]
+    fn set_from_camera() {
+        let mut raycaster = Raycaster::new(&Vector3::ZERO, &Vector3::Z);
+
+        // orthographic rays are parallel, offset across the view volume
+        let orthographic = OrthographicCamera::new(-2.0, 2.0, 1.0, -1.0, None, None);
+        raycaster.set_from_camera(&Vector2::new(0.5, 0.5), &orthographic);
+        assert!(raycaster.ray.origin.distance_to(&Vector3::new(1.0, 0.5, 0.0)) < 1e-5);
+        assert!(raycaster.ray.direction.distance_to(&Vector3::NEG_Z) < 1e-6);
+        assert_eq!(raycaster.camera_matrix_world, Some(Matrix4::IDENTITY));
+
+        // 90 degrees wide and high, looking down -z from z = 10
+        let mut perspective = PerspectiveCamera::new(90.0, 1.0, Some(1.0), Some(100.0));
+        perspective.scene_object_mut().position = Vector3::new(0.0, 0.0, 10.0);
+        perspective.scene_object_mut().update_matrix_world(None, false);
+
+        raycaster.set_from_camera(&Vector2::new(0.5, 0.0), &perspective);
+        assert!(raycaster.ray.origin.distance_to(&Vector3::new(0.0, 0.0, 10.0)) < 1e-5);
+        let expected = Vector3::new(0.5, 0.0, -1.0).normalized();
+        assert!(raycaster.ray.direction.distance_to(&expected) < 1e-5);
+
+        // x = 0.05 on the near plane spreads to x = 0.5 ten units away
+        let mut graph = SceneGraph::new();
+        let id = graph.insert(plane(&Vector3::ZERO));
+        graph.update_matrix_world();
+        raycaster.set_from_camera(&Vector2::new(0.05, 0.0), &perspective);
+        let hits = raycaster.intersect_object(&graph, id, false);
+        assert_eq!(hits.len(), 1);
+        assert!(hits[0].point.distance_to(&Vector3::new(0.5, 0.0, 0.0)) < 1e-4);
+    }
+}
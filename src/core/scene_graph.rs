@@ -0,0 +1,574 @@
+use std::ops::{Index, IndexMut};
+
+use core::Object3D;
+use math::{Matrix4, Vector3};
+
+// Handle to a node owned by a SceneGraph. The generation makes handles to removed nodes
+// stale instead of silently pointing at whatever reuses the slot.
+#[derive(Debug,PartialEq,Eq,Hash,Copy,Clone)]
+pub struct NodeId {
+    index: usize,
+    generation: usize,
+}
+
+#[derive(Debug)]
+struct Node {
+    object: Object3D,
+    parent: Option<NodeId>,
+    children: Vec<NodeId>,
+}
+
+#[derive(Debug)]
+struct Slot {
+    generation: usize,
+    node: Option<Node>,
+}
+
+#[derive(Debug)]
+pub struct SceneGraph {
+    slots: Vec<Slot>,
+    free: Vec<usize>,
+    roots: Vec<NodeId>,
+}
+
+impl SceneGraph {
+    pub fn new() -> SceneGraph {
+        SceneGraph {
+            slots: vec![],
+            free: vec![],
+            roots: vec![],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.slots.len() - self.free.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // adds a parentless node
+    pub fn insert(&mut self, object: Object3D) -> NodeId {
+        let node = Node {
+            object: object,
+            parent: None,
+            children: vec![],
+        };
+
+        let id = match self.free.pop() {
+            Some(index) => {
+                let slot = &mut self.slots[index];
+                slot.node = Some(node);
+                NodeId {
+                    index: index,
+                    generation: slot.generation,
+                }
+            }
+            None => {
+                self.slots.push(Slot {
+                    generation: 0,
+                    node: Some(node),
+                });
+                NodeId {
+                    index: self.slots.len() - 1,
+                    generation: 0,
+                }
+            }
+        };
+
+        self.roots.push(id);
+        id
+    }
+
+    pub fn insert_child(&mut self, parent: NodeId, object: Object3D) -> NodeId {
+        let id = self.insert(object);
+        self.add(parent, id);
+        id
+    }
+
+    // removes the node together with all of its descendants, returns the node's object
+    pub fn remove(&mut self, id: NodeId) -> Option<Object3D> {
+        if !self.contains(id) {
+            return None;
+        }
+
+        self.detach(id);
+        self.roots.retain(|&root| root != id);
+
+        let mut doomed: Vec<NodeId> = self.descendants(id).collect();
+        doomed.push(id);
+
+        let mut result = None;
+        for node_id in doomed {
+            let slot = &mut self.slots[node_id.index];
+            let node = slot.node.take().unwrap();
+            slot.generation += 1;
+            self.free.push(node_id.index);
+
+            if node_id == id {
+                result = Some(node.object);
+            }
+        }
+        result
+    }
+
+    pub fn contains(&self, id: NodeId) -> bool {
+        self.node(id).is_some()
+    }
+
+    pub fn get(&self, id: NodeId) -> Option<&Object3D> {
+        self.node(id).map(|node| &node.object)
+    }
+
+    pub fn get_mut(&mut self, id: NodeId) -> Option<&mut Object3D> {
+        self.node_mut(id).map(|node| &mut node.object)
+    }
+
+    pub fn roots(&self) -> &[NodeId] {
+        &self.roots
+    }
+
+    pub fn parent(&self, id: NodeId) -> Option<NodeId> {
+        self.expect_node(id).parent
+    }
+
+    pub fn children(&self, id: NodeId) -> &[NodeId] {
+        &self.expect_node(id).children
+    }
+
+    // appends child as the last child of parent, detaching it from its previous parent
+    pub fn add(&mut self, parent: NodeId, child: NodeId) {
+        let index = self.expect_node(parent).children.len();
+        self.insert_at(parent, child, index);
+    }
+
+    pub fn insert_at(&mut self, parent: NodeId, child: NodeId, index: usize) {
+        if parent == child {
+            panic!("node {:?} can't be added as a child of itself.", child);
+        }
+
+        if self.ancestors(parent).any(|ancestor| ancestor == child) {
+            panic!("node {:?} can't be added as a child of its descendant {:?}.",
+                   child,
+                   parent);
+        }
+
+        self.expect_node(child);
+        self.detach(child);
+        self.roots.retain(|&root| root != child);
+
+        let siblings = &mut self.expect_node_mut(parent).children;
+        let index = index.min(siblings.len());
+        siblings.insert(index, child);
+
+        self.expect_node_mut(child).parent = Some(parent);
+    }
+
+    // like add, but the child keeps its place in the world. its local transform is recomputed
+    // relative to the parent, which can't represent shear from non uniformly scaled ancestors
+    pub fn attach(&mut self, parent: NodeId, child: NodeId) {
+        self.update_world_matrix(parent, true, false);
+        self.update_world_matrix(child, true, false);
+        let local = self[parent].matrix_world.inverse().multiply(&self[child].matrix_world);
+
+        self.add(parent, child);
+        let (position, quaternion, scale) = local.decompose();
+        {
+            let object = &mut self[child];
+            object.position = position;
+            object.set_rotation_from_quaternion(&quaternion);
+            object.scale = scale;
+        }
+        self.update_world_matrix(child, false, true);
+    }
+
+    // removes the node from its parent, the node (and its subtree) becomes a root
+    pub fn detach(&mut self, id: NodeId) {
+        if let Some(parent) = self.expect_node(id).parent {
+            self.expect_node_mut(parent).children.retain(|&child| child != id);
+            self.expect_node_mut(id).parent = None;
+            self.roots.push(id);
+        }
+    }
+
+    pub fn sibling_index(&self, id: NodeId) -> usize {
+        let siblings = self.siblings(id);
+        siblings.iter().position(|&sibling| sibling == id).unwrap()
+    }
+
+    pub fn next_sibling(&self, id: NodeId) -> Option<NodeId> {
+        let siblings = self.siblings(id);
+        siblings.get(self.sibling_index(id) + 1).cloned()
+    }
+
+    pub fn previous_sibling(&self, id: NodeId) -> Option<NodeId> {
+        match self.sibling_index(id) {
+            0 => None,
+            index => Some(self.siblings(id)[index - 1]),
+        }
+    }
+
+    // moves the node to a new position among its siblings
+    pub fn set_sibling_index(&mut self, id: NodeId, index: usize) {
+        let current = self.sibling_index(id);
+        let siblings = match self.expect_node(id).parent {
+            Some(parent) => &mut self.expect_node_mut(parent).children,
+            None => &mut self.roots,
+        };
+
+        siblings.remove(current);
+        let index = index.min(siblings.len());
+        siblings.insert(index, id);
+    }
+
+    // walks from the parent of the node up to its root
+    pub fn ancestors(&self, id: NodeId) -> Ancestors {
+        Ancestors {
+            graph: self,
+            current: self.expect_node(id).parent,
+        }
+    }
+
+    // depth first, pre-order walk over the subtree below the node (the node itself excluded)
+    pub fn descendants(&self, id: NodeId) -> Descendants {
+        let stack = self.expect_node(id).children.iter().rev().cloned().collect();
+        Descendants {
+            graph: self,
+            stack: stack,
+        }
+    }
+
+    pub fn traverse(&self, id: NodeId, callback: &mut FnMut(NodeId, &Object3D)) {
+        callback(id, &self[id]);
+
+        for &child in self.children(id) {
+            self.traverse(child, callback);
+        }
+    }
+
+    pub fn traverse_visible(&self, id: NodeId, callback: &mut FnMut(NodeId, &Object3D)) {
+        if self[id].visible {
+            callback(id, &self[id]);
+
+            for &child in self.children(id) {
+                self.traverse_visible(child, callback);
+            }
+        }
+    }
+
+    pub fn traverse_ancestors(&self, id: NodeId, callback: &mut FnMut(NodeId, &Object3D)) {
+        for ancestor in self.ancestors(id) {
+            callback(ancestor, &self[ancestor]);
+        }
+    }
+
+    // updates the world matrices of every node in the graph
+    pub fn update_matrix_world(&mut self) {
+        let roots = self.roots.clone();
+        for root in roots {
+            self.update_subtree(root, false);
+        }
+    }
+
+    pub fn update_world_matrix(&mut self, id: NodeId, update_parents: bool, update_children: bool) {
+        if update_parents {
+            let mut chain: Vec<NodeId> = self.ancestors(id).collect();
+            chain.reverse();
+
+            for ancestor in chain {
+                self.update_node(ancestor, false);
+            }
+        }
+
+        if update_children {
+            self.update_subtree(id, false);
+        } else {
+            self.update_node(id, false);
+        }
+    }
+
+    // Object3D::look_at with the ancestors brought up to date first
+    pub fn look_at(&mut self, id: NodeId, target: &Vector3) {
+        self.update_world_matrix(id, true, false);
+        self[id].look_at(target);
+    }
+
+    fn update_node(&mut self, id: NodeId, force: bool) -> bool {
+        let parent_matrix_world: Option<Matrix4> = self.expect_node(id)
+            .parent
+            .map(|parent| self[parent].matrix_world);
+        self[id].update_matrix_world(parent_matrix_world.as_ref(), force)
+    }
+
+    fn update_subtree(&mut self, id: NodeId, force: bool) {
+        let mut stack = vec![(id, force)];
+
+        while let Some((node_id, node_force)) = stack.pop() {
+            let children_force = self.update_node(node_id, node_force);
+
+            for &child in self.expect_node(node_id).children.iter().rev() {
+                stack.push((child, children_force));
+            }
+        }
+    }
+
+    fn siblings(&self, id: NodeId) -> &[NodeId] {
+        match self.expect_node(id).parent {
+            Some(parent) => &self.expect_node(parent).children,
+            None => &self.roots,
+        }
+    }
+
+    fn node(&self, id: NodeId) -> Option<&Node> {
+        match self.slots.get(id.index) {
+            Some(slot) if slot.generation == id.generation => slot.node.as_ref(),
+            _ => None,
+        }
+    }
+
+    fn node_mut(&mut self, id: NodeId) -> Option<&mut Node> {
+        match self.slots.get_mut(id.index) {
+            Some(slot) if slot.generation == id.generation => slot.node.as_mut(),
+            _ => None,
+        }
+    }
+
+    fn expect_node(&self, id: NodeId) -> &Node {
+        match self.node(id) {
+            Some(node) => node,
+            None => panic!("node {:?} is not part of the scene graph.", id),
+        }
+    }
+
+    fn expect_node_mut(&mut self, id: NodeId) -> &mut Node {
+        match self.node_mut(id) {
+            Some(node) => node,
+            None => panic!("node {:?} is not part of the scene graph.", id),
+        }
+    }
+}
+
+impl Index<NodeId> for SceneGraph {
+    type Output = Object3D;
+
+    fn index(&self, id: NodeId) -> &Object3D {
+        &self.expect_node(id).object
+    }
+}
+
+impl IndexMut<NodeId> for SceneGraph {
+    fn index_mut(&mut self, id: NodeId) -> &mut Object3D {
+        &mut self.expect_node_mut(id).object
+    }
+}
+
+pub struct Ancestors<'a> {
+    graph: &'a SceneGraph,
+    current: Option<NodeId>,
+}
+
+impl<'a> Iterator for Ancestors<'a> {
+    type Item = NodeId;
+
+    fn next(&mut self) -> Option<NodeId> {
+        let result = self.current;
+        if let Some(id) = result {
+            self.current = self.graph.parent(id);
+        }
+        result
+    }
+}
+
+pub struct Descendants<'a> {
+    graph: &'a SceneGraph,
+    stack: Vec<NodeId>,
+}
+
+impl<'a> Iterator for Descendants<'a> {
+    type Item = NodeId;
+
+    fn next(&mut self) -> Option<NodeId> {
+        let result = self.stack.pop();
+        if let Some(id) = result {
+            self.stack.extend(self.graph.children(id).iter().rev());
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+    use core::{Object3D, SceneGraph};
+    use math::Vector3;
+
+    #[test]
+    fn insert_remove() {
+        let mut graph = SceneGraph::new();
+        let a = graph.insert(Object3D::next());
+        let b = graph.insert_child(a, Object3D::next());
+        let c = graph.insert_child(b, Object3D::next());
+        assert_eq!(graph.len(), 3);
+        assert_eq!(graph.roots(), &[a]);
+
+        assert!(graph.remove(b).is_some());
+        assert_eq!(graph.len(), 1);
+        assert!(!graph.contains(b));
+        assert!(!graph.contains(c));
+        assert!(graph.children(a).is_empty());
+
+        // slots are reused but stale handles stay stale
+        let d = graph.insert(Object3D::next());
+        assert!(graph.contains(d));
+        assert!(!graph.contains(b));
+        assert!(!graph.contains(c));
+        assert_eq!(graph.remove(b), None);
+    }
+
+    #[test]
+    fn add_reparents() {
+        let mut graph = SceneGraph::new();
+        let a = graph.insert(Object3D::next());
+        let b = graph.insert(Object3D::next());
+        let c = graph.insert_child(a, Object3D::next());
+
+        graph.add(b, c);
+        assert_eq!(graph.parent(c), Some(b));
+        assert!(graph.children(a).is_empty());
+        assert_eq!(graph.children(b), &[c]);
+
+        graph.detach(c);
+        assert_eq!(graph.parent(c), None);
+        assert!(graph.children(b).is_empty());
+        assert_eq!(graph.roots(), &[a, b, c]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn add_to_descendant() {
+        let mut graph = SceneGraph::new();
+        let a = graph.insert(Object3D::next());
+        let b = graph.insert_child(a, Object3D::next());
+        graph.add(b, a);
+    }
+
+    #[test]
+    fn sibling_order() {
+        let mut graph = SceneGraph::new();
+        let parent = graph.insert(Object3D::next());
+        let a = graph.insert_child(parent, Object3D::next());
+        let b = graph.insert_child(parent, Object3D::next());
+        let c = graph.insert_child(parent, Object3D::next());
+
+        assert_eq!(graph.sibling_index(b), 1);
+        assert_eq!(graph.next_sibling(b), Some(c));
+        assert_eq!(graph.previous_sibling(b), Some(a));
+        assert_eq!(graph.previous_sibling(a), None);
+        assert_eq!(graph.next_sibling(c), None);
+
+        graph.set_sibling_index(c, 0);
+        assert_eq!(graph.children(parent), &[c, a, b]);
+
+        let d = graph.insert(Object3D::next());
+        graph.insert_at(parent, d, 1);
+        assert_eq!(graph.children(parent), &[c, d, a, b]);
+    }
+
+    #[test]
+    fn ancestors_descendants() {
+        let mut graph = SceneGraph::new();
+        let a = graph.insert(Object3D::next());
+        let b = graph.insert_child(a, Object3D::next());
+        let c = graph.insert_child(b, Object3D::next());
+        let d = graph.insert_child(a, Object3D::next());
+
+        assert_eq!(graph.ancestors(c).collect::<Vec<_>>(), vec![b, a]);
+        assert_eq!(graph.ancestors(a).count(), 0);
+        assert_eq!(graph.descendants(a).collect::<Vec<_>>(), vec![b, c, d]);
+
+        let mut visited = vec![];
+        graph.traverse(a, &mut |id, _| visited.push(id));
+        assert_eq!(visited, vec![a, b, c, d]);
+
+        graph[b].visible = false;
+        let mut visible = vec![];
+        graph.traverse_visible(a, &mut |id, _| visible.push(id));
+        assert_eq!(visible, vec![a, d]);
+    }
+
+    #[test]
+    fn update_matrix_world() {
+        let mut graph = SceneGraph::new();
+        let a = graph.insert(Object3D::next());
+        let b = graph.insert_child(a, Object3D::next());
+        let c = graph.insert_child(b, Object3D::next());
+
+        graph[a].position = Vector3::new(1.0, 0.0, 0.0);
+        graph[b].position = Vector3::new(0.0, 2.0, 0.0);
+        graph[c].position = Vector3::new(0.0, 0.0, 3.0);
+        graph.update_matrix_world();
+
+        assert_eq!(graph[c].world_position(), Vector3::new(1.0, 2.0, 3.0));
+        assert_eq!(graph[b].world_position(), Vector3::new(1.0, 2.0, 0.0));
+
+        graph[a].position = Vector3::new(-1.0, 0.0, 0.0);
+        graph.update_world_matrix(c, true, false);
+        assert_eq!(graph[c].world_position(), Vector3::new(-1.0, 2.0, 3.0));
+    }
+
+    #[test]
+    fn look_at() {
+        // the parent turns local +z to world +x and moves everything 5 along x
+        let mut graph = SceneGraph::new();
+        let parent = graph.insert(Object3D::next());
+        let child = graph.insert_child(parent, Object3D::next());
+        graph[parent].position = Vector3::new(5.0, 0.0, 0.0);
+        graph[parent].rotate_y(FRAC_PI_2);
+        graph[child].position = Vector3::new(0.0, 0.0, 2.0);
+
+        graph.look_at(child, &Vector3::new(7.0, 0.0, -5.0));
+        assert!(graph[child].world_position().distance_to(&Vector3::new(7.0, 0.0, 0.0)) < 1e-5);
+        assert!(graph[child].world_direction().distance_to(&Vector3::NEG_Z) < 1e-5);
+
+        graph.look_at(child, &Vector3::new(10.0, 4.0, 0.0));
+        graph.update_matrix_world();
+        let direction = graph[child].world_direction();
+        assert!(direction.distance_to(&Vector3::new(0.6, 0.8, 0.0)) < 1e-5);
+
+        // the parent moved since the last update, look_at catches up with it
+        graph[parent].position = Vector3::ZERO;
+        graph.look_at(child, &Vector3::new(2.0, 0.0, 3.0));
+        assert!(graph[child].world_position().distance_to(&Vector3::new(2.0, 0.0, 0.0)) < 1e-5);
+        assert!(graph[child].world_direction().distance_to(&Vector3::Z) < 1e-5);
+    }
+
+    #[test]
+    fn attach() {
+        let mut graph = SceneGraph::new();
+        let parent = graph.insert(Object3D::next());
+        let child = graph.insert(Object3D::next());
+        let grandchild = graph.insert_child(child, Object3D::next());
+
+        graph[parent].position = Vector3::new(1.0, 2.0, 3.0);
+        graph[parent].rotate_z(FRAC_PI_2);
+        graph[parent].scale = Vector3::new(2.0, 2.0, 2.0);
+        graph[child].position = Vector3::new(-4.0, 0.5, 1.0);
+        graph[child].rotate_x(0.3);
+        graph[grandchild].position = Vector3::new(0.0, 1.0, 0.0);
+        graph.update_matrix_world();
+        let before = graph[child].matrix_world;
+        let grandchild_before = graph[grandchild].world_position();
+
+        graph.attach(parent, child);
+        assert_eq!(graph.parent(child), Some(parent));
+        assert!(graph[child].scale.distance_to(&Vector3::new(0.5, 0.5, 0.5)) < 1e-5);
+
+        let after = graph[child].matrix_world;
+        for i in 0..16 {
+            assert!((before.elements[i] - after.elements[i]).abs() < 1e-5);
+        }
+        let grandchild_after = graph[grandchild].world_position();
+        assert!(grandchild_before.distance_to(&grandchild_after) < 1e-5);
+    }
+}
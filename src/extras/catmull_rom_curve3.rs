@@ -0,0 +1,201 @@
+use extras::{ArcLengths, Curve};
+use math::Vector3;
+
+// how the knots are spaced: uniform ignores the distance between the points, chordal uses
+// it as is and centripetal its square root, which avoids cusps and self intersections
+#[derive(Debug,PartialEq,Copy,Clone)]
+pub enum CatmullRomType {
+    Uniform,
+    Centripetal,
+    Chordal,
+}
+
+// passes through every point. tension only applies to the uniform type, 0.5 is the classic
+// Catmull-Rom spline
+#[derive(Debug,PartialEq,Clone)]
+pub struct CatmullRomCurve3 {
+    pub points: Vec<Vector3>,
+    pub closed: bool,
+    pub curve_type: CatmullRomType,
+    pub tension: f32,
+    arc_lengths: ArcLengths,
+}
+
+// x0 + t0 * t + c2 * t^2 + c3 * t^3, with the given values and tangents at 0 and 1
+fn cubic_poly(x0: f32, x1: f32, t0: f32, t1: f32, t: f32) -> f32 {
+    let c2 = -3.0 * x0 + 3.0 * x1 - 2.0 * t0 - t1;
+    let c3 = 2.0 * x0 - 2.0 * x1 + t0 + t1;
+    x0 + t0 * t + c2 * t * t + c3 * t * t * t
+}
+
+fn uniform(x0: f32, x1: f32, x2: f32, x3: f32, tension: f32, t: f32) -> f32 {
+    cubic_poly(x1, x2, tension * (x2 - x0), tension * (x3 - x1), t)
+}
+
+fn nonuniform(x0: f32, x1: f32, x2: f32, x3: f32, dt: (f32, f32, f32), t: f32) -> f32 {
+    let (dt0, dt1, dt2) = dt;
+
+    // tangents over [t1, t2], rescaled to 0..1
+    let t1 = (x1 - x0) / dt0 - (x2 - x0) / (dt0 + dt1) + (x2 - x1) / dt1;
+    let t2 = (x2 - x1) / dt1 - (x3 - x1) / (dt1 + dt2) + (x3 - x2) / dt2;
+    cubic_poly(x1, x2, t1 * dt1, t2 * dt1, t)
+}
+
+impl CatmullRomCurve3 {
+    pub fn new(points: &[Vector3], closed: bool, curve_type: CatmullRomType) -> CatmullRomCurve3 {
+        CatmullRomCurve3 {
+            points: points.to_vec(),
+            closed: closed,
+            curve_type: curve_type,
+            tension: 0.5,
+            arc_lengths: ArcLengths::new(),
+        }
+    }
+}
+
+impl Curve for CatmullRomCurve3 {
+    type Vector = Vector3;
+
+    fn point_at(&self, t: f32) -> Vector3 {
+        let points = &self.points;
+        let l = points.len();
+        match l {
+            0 => panic!("CatmullRomCurve3 needs at least one point."),
+            1 => return points[0],
+            _ => {}
+        }
+
+        let p = (l - if self.closed { 0 } else { 1 }) as f32 * t;
+        let mut int_point = p.floor() as isize;
+        let mut weight = p - p.floor();
+
+        if self.closed {
+            int_point = (int_point % l as isize + l as isize) % l as isize;
+        } else if int_point >= l as isize - 1 {
+            int_point = l as isize - 2;
+            weight = 1.0;
+        } else if int_point < 0 {
+            int_point = 0;
+            weight = 0.0;
+        }
+
+        let i = int_point as usize;
+        let p1 = points[i];
+        let p2 = points[(i + 1) % l];
+
+        // the open ends are extended by mirroring the neighbouring point
+        let p0 = if self.closed || i > 0 {
+            points[(i + l - 1) % l]
+        } else {
+            points[0].subtract(&points[1]).add(&points[0])
+        };
+        let p3 = if self.closed || i + 2 < l {
+            points[(i + 2) % l]
+        } else {
+            points[l - 1].subtract(&points[l - 2]).add(&points[l - 1])
+        };
+
+        let spline = |x0: f32, x1: f32, x2: f32, x3: f32, dt: Option<(f32, f32, f32)>| {
+            match dt {
+                None => uniform(x0, x1, x2, x3, self.tension, weight),
+                Some(dt) => nonuniform(x0, x1, x2, x3, dt, weight),
+            }
+        };
+
+        let dt = match self.curve_type {
+            CatmullRomType::Uniform => None,
+            CatmullRomType::Centripetal | CatmullRomType::Chordal => {
+                let pow = if self.curve_type == CatmullRomType::Chordal {
+                    0.5
+                } else {
+                    0.25
+                };
+                let mut dt0 = p0.distance_to_squared(&p1).powf(pow);
+                let mut dt1 = p1.distance_to_squared(&p2).powf(pow);
+                let mut dt2 = p2.distance_to_squared(&p3).powf(pow);
+
+                // repeated points
+                if dt1 < 1e-4 {
+                    dt1 = 1.0;
+                }
+                if dt0 < 1e-4 {
+                    dt0 = dt1;
+                }
+                if dt2 < 1e-4 {
+                    dt2 = dt1;
+                }
+                Some((dt0, dt1, dt2))
+            }
+        };
+
+        Vector3::new(spline(p0.x, p1.x, p2.x, p3.x, dt),
+                     spline(p0.y, p1.y, p2.y, p3.y, dt),
+                     spline(p0.z, p1.z, p2.z, p3.z, dt))
+    }
+
+    fn arc_lengths(&self) -> &ArcLengths {
+        &self.arc_lengths
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use extras::{CatmullRomCurve3, CatmullRomType, Curve};
+    use math::Vector3;
+
+    fn square() -> Vec<Vector3> {
+        vec![Vector3::ZERO,
+             Vector3::new(1.0, 0.0, 0.0),
+             Vector3::new(1.0, 0.0, 1.0),
+             Vector3::new(0.0, 0.0, 1.0)]
+    }
+
+    #[test]
+    fn point_at() {
+        for curve_type in &[CatmullRomType::Uniform,
+                            CatmullRomType::Centripetal,
+                            CatmullRomType::Chordal] {
+            let a = CatmullRomCurve3::new(&square(), false, *curve_type);
+
+            // passes through every point
+            for (i, point) in square().iter().enumerate() {
+                assert!(a.point_at(i as f32 / 3.0).distance_to(point) < 1e-5);
+            }
+        }
+
+        // with evenly spaced points the knot spacing makes no difference
+        let even = CatmullRomCurve3::new(&square(), false, CatmullRomType::Uniform);
+        let centripetal = CatmullRomCurve3::new(&square(), false, CatmullRomType::Centripetal);
+        for i in 0..13 {
+            let t = i as f32 / 12.0;
+            assert!(even.point_at(t).distance_to(&centripetal.point_at(t)) < 1e-5);
+        }
+
+        let points = [Vector3::ZERO,
+                      Vector3::new(0.1, 1.0, 0.0),
+                      Vector3::new(2.0, 1.0, 0.0),
+                      Vector3::new(2.1, 0.0, 0.0)];
+        let uniform = CatmullRomCurve3::new(&points, false, CatmullRomType::Uniform);
+        let centripetal = CatmullRomCurve3::new(&points, false, CatmullRomType::Centripetal);
+        let chordal = CatmullRomCurve3::new(&points, false, CatmullRomType::Chordal);
+        let middle = uniform.point_at(0.5);
+        assert!(middle.distance_to(&centripetal.point_at(0.5)) > 1e-2);
+        assert!(centripetal.point_at(0.5).distance_to(&chordal.point_at(0.5)) > 1e-2);
+    }
+
+    #[test]
+    fn closed() {
+        let a = CatmullRomCurve3::new(&square(), true, CatmullRomType::Centripetal);
+        assert_eq!(a.point_at(0.0), Vector3::ZERO);
+        assert!(a.point_at(1.0).distance_to(&Vector3::ZERO) < 1e-5);
+        assert!(a.point_at(0.25).distance_to(&Vector3::new(1.0, 0.0, 0.0)) < 1e-5);
+
+        // the loop is smooth where it meets itself
+        let start = a.tangent_at(0.0);
+        let end = a.tangent_at(1.0);
+        assert!(start.dot(&end) > 0.999);
+
+        let open = CatmullRomCurve3::new(&square(), false, CatmullRomType::Centripetal);
+        assert!(a.length() > open.length());
+    }
+}
@@ -0,0 +1,63 @@
+use extras::{ArcLengths, Curve, CurveVector};
+use math::{Vector2, Vector3};
+
+#[derive(Debug,PartialEq,Clone)]
+pub struct CubicBezierCurve<V: CurveVector> {
+    pub v0: V,
+    pub v1: V,
+    pub v2: V,
+    pub v3: V,
+    arc_lengths: ArcLengths,
+}
+
+pub type CubicBezierCurve2 = CubicBezierCurve<Vector2>;
+pub type CubicBezierCurve3 = CubicBezierCurve<Vector3>;
+
+impl<V: CurveVector> CubicBezierCurve<V> {
+    pub fn new(v0: &V, v1: &V, v2: &V, v3: &V) -> CubicBezierCurve<V> {
+        CubicBezierCurve {
+            v0: *v0,
+            v1: *v1,
+            v2: *v2,
+            v3: *v3,
+            arc_lengths: ArcLengths::new(),
+        }
+    }
+}
+
+impl<V: CurveVector> Curve for CubicBezierCurve<V> {
+    type Vector = V;
+
+    fn point_at(&self, t: f32) -> V {
+        let k = 1.0 - t;
+        self.v0
+            .multiply_scalar(k * k * k)
+            .add(&self.v1.multiply_scalar(3.0 * k * k * t))
+            .add(&self.v2.multiply_scalar(3.0 * k * t * t))
+            .add(&self.v3.multiply_scalar(t * t * t))
+    }
+
+    fn arc_lengths(&self) -> &ArcLengths {
+        &self.arc_lengths
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use extras::{CubicBezierCurve3, Curve};
+    use math::Vector3;
+
+    #[test]
+    fn point_at() {
+        let a = CubicBezierCurve3::new(&Vector3::ZERO,
+                                       &Vector3::new(0.0, 1.0, 0.0),
+                                       &Vector3::new(1.0, 1.0, 0.0),
+                                       &Vector3::new(1.0, 0.0, 1.0));
+        assert_eq!(a.point_at(0.0), Vector3::ZERO);
+        assert_eq!(a.point_at(1.0), Vector3::new(1.0, 0.0, 1.0));
+        assert_eq!(a.point_at(0.5), Vector3::new(0.5, 0.75, 0.125));
+
+        // the curve leaves the start towards the first control point
+        assert!(a.tangent_at(0.0).subtract(&Vector3::Y).length() < 1e-3);
+    }
+}
@@ -0,0 +1,366 @@
+use std::cell::{Ref, RefCell};
+use std::f32::EPSILON;
+use std::fmt::Debug;
+use math::{clamp, Vector2, Vector3};
+
+// the vector operations the curves are written against, so the same curve works in 2D and 3D
+pub trait CurveVector: Copy + Debug + PartialEq {
+    const ZERO: Self;
+
+    fn add(&self, v: &Self) -> Self;
+    fn subtract(&self, v: &Self) -> Self;
+    fn multiply_scalar(&self, s: f32) -> Self;
+    fn distance_to(&self, v: &Self) -> f32;
+    fn normalized(&self) -> Self;
+}
+
+impl CurveVector for Vector2 {
+    const ZERO: Vector2 = Vector2::ZERO;
+
+    fn add(&self, v: &Vector2) -> Vector2 {
+        Vector2::add(self, v)
+    }
+
+    fn subtract(&self, v: &Vector2) -> Vector2 {
+        Vector2::subtract(self, v)
+    }
+
+    fn multiply_scalar(&self, s: f32) -> Vector2 {
+        Vector2::multiply_scalar(self, s)
+    }
+
+    fn distance_to(&self, v: &Vector2) -> f32 {
+        Vector2::distance_to(self, v)
+    }
+
+    fn normalized(&self) -> Vector2 {
+        self.normalize()
+    }
+}
+
+impl CurveVector for Vector3 {
+    const ZERO: Vector3 = Vector3::ZERO;
+
+    fn add(&self, v: &Vector3) -> Vector3 {
+        Vector3::add(self, v)
+    }
+
+    fn subtract(&self, v: &Vector3) -> Vector3 {
+        Vector3::subtract(self, v)
+    }
+
+    fn multiply_scalar(&self, s: f32) -> Vector3 {
+        Vector3::multiply_scalar(self, s)
+    }
+
+    fn distance_to(&self, v: &Vector3) -> f32 {
+        Vector3::distance_to(self, v)
+    }
+
+    fn normalized(&self) -> Vector3 {
+        Vector3::normalized(self)
+    }
+}
+
+// cumulative lengths at evenly spaced t, filled on first use. the cache is not part of a
+// curve's value, so it never takes part in comparisons
+#[derive(Debug,Clone,Default)]
+pub struct ArcLengths {
+    lengths: RefCell<Option<Vec<f32>>>,
+}
+
+impl ArcLengths {
+    pub fn new() -> ArcLengths {
+        ArcLengths { lengths: RefCell::new(None) }
+    }
+
+    pub fn clear(&self) {
+        *self.lengths.borrow_mut() = None;
+    }
+}
+
+impl PartialEq for ArcLengths {
+    fn eq(&self, _: &ArcLengths) -> bool {
+        true
+    }
+}
+
+// a parametric curve over t in 0..1. the point_at/tangent_at family follows t, which does not
+// move along the curve at constant speed, the spaced_* family follows u, the fraction of the
+// arc length. implementations that expose their control points have to call
+// update_arc_lengths after changing them
+pub trait Curve {
+    type Vector: CurveVector;
+
+    fn point_at(&self, t: f32) -> Self::Vector;
+
+    // storage for the arc length table
+    fn arc_lengths(&self) -> &ArcLengths;
+
+    // central difference, clamped at the ends
+    fn tangent_at(&self, t: f32) -> Self::Vector {
+        let delta = 0.0001;
+        let t1 = clamp(t - delta, 0.0, 1.0);
+        let t2 = clamp(t + delta, 0.0, 1.0);
+        self.point_at(t2).subtract(&self.point_at(t1)).normalized()
+    }
+
+    // number of samples in the arc length table
+    fn arc_length_divisions(&self) -> usize {
+        200
+    }
+
+    // number of samples taken of this curve when it is part of a path divided into divisions
+    fn resolution(&self, divisions: usize) -> usize {
+        divisions
+    }
+
+    fn points(&self, divisions: usize) -> Vec<Self::Vector> {
+        let divisions = divisions.max(1);
+        (0..divisions + 1).map(|d| self.point_at(d as f32 / divisions as f32)).collect()
+    }
+
+    fn spaced_points(&self, divisions: usize) -> Vec<Self::Vector> {
+        let divisions = divisions.max(1);
+        (0..divisions + 1).map(|d| self.spaced_point_at(d as f32 / divisions as f32)).collect()
+    }
+
+    fn spaced_point_at(&self, u: f32) -> Self::Vector {
+        self.point_at(self.u_to_t(u))
+    }
+
+    fn spaced_tangent_at(&self, u: f32) -> Self::Vector {
+        self.tangent_at(self.u_to_t(u))
+    }
+
+    fn length(&self) -> f32 {
+        let lengths = self.lengths();
+        lengths[lengths.len() - 1]
+    }
+
+    // cumulative length at arc_length_divisions + 1 evenly spaced t, the first one is 0
+    fn lengths(&self) -> Ref<Vec<f32>> {
+        let cache = &self.arc_lengths().lengths;
+        let divisions = self.arc_length_divisions().max(1);
+        let stale = match *cache.borrow() {
+            Some(ref lengths) => lengths.len() != divisions + 1,
+            None => true,
+        };
+
+        if stale {
+            let mut lengths = Vec::with_capacity(divisions + 1);
+            let mut last = self.point_at(0.0);
+            let mut sum = 0.0;
+            lengths.push(sum);
+
+            for d in 1..divisions + 1 {
+                let current = self.point_at(d as f32 / divisions as f32);
+                sum += current.distance_to(&last);
+                lengths.push(sum);
+                last = current;
+            }
+
+            *cache.borrow_mut() = Some(lengths);
+        }
+
+        Ref::map(cache.borrow(), |l| l.as_ref().unwrap())
+    }
+
+    fn update_arc_lengths(&self) {
+        self.arc_lengths().clear();
+    }
+
+    // maps the arc length fraction u to t, interpolating linearly inside the table
+    fn u_to_t(&self, u: f32) -> f32 {
+        let lengths = self.lengths();
+        let last = lengths.len() - 1;
+        let target = clamp(u, 0.0, 1.0) * lengths[last];
+
+        // binary search for the last entry not past the target
+        let mut low = 0;
+        let mut high = last;
+        while low < high {
+            let i = high - (high - low) / 2;
+            if lengths[i] > target {
+                high = i - 1;
+            } else {
+                low = i;
+            }
+        }
+
+        let i = low;
+        if lengths[i] == target || i == last {
+            return i as f32 / last as f32;
+        }
+
+        let fraction = (target - lengths[i]) / (lengths[i + 1] - lengths[i]);
+        (i as f32 + fraction) / last as f32
+    }
+}
+
+impl<C: Curve + ?Sized> Curve for Box<C> {
+    type Vector = C::Vector;
+
+    fn point_at(&self, t: f32) -> C::Vector {
+        (**self).point_at(t)
+    }
+
+    fn arc_lengths(&self) -> &ArcLengths {
+        (**self).arc_lengths()
+    }
+
+    fn tangent_at(&self, t: f32) -> C::Vector {
+        (**self).tangent_at(t)
+    }
+
+    fn arc_length_divisions(&self) -> usize {
+        (**self).arc_length_divisions()
+    }
+
+    fn resolution(&self, divisions: usize) -> usize {
+        (**self).resolution(divisions)
+    }
+
+    fn points(&self, divisions: usize) -> Vec<C::Vector> {
+        (**self).points(divisions)
+    }
+
+    fn spaced_points(&self, divisions: usize) -> Vec<C::Vector> {
+        (**self).spaced_points(divisions)
+    }
+
+    fn spaced_point_at(&self, u: f32) -> C::Vector {
+        (**self).spaced_point_at(u)
+    }
+
+    fn spaced_tangent_at(&self, u: f32) -> C::Vector {
+        (**self).spaced_tangent_at(u)
+    }
+
+    fn length(&self) -> f32 {
+        (**self).length()
+    }
+
+    fn lengths(&self) -> Ref<Vec<f32>> {
+        (**self).lengths()
+    }
+
+    fn update_arc_lengths(&self) {
+        (**self).update_arc_lengths()
+    }
+
+    fn u_to_t(&self, u: f32) -> f32 {
+        (**self).u_to_t(u)
+    }
+}
+
+// one frame per sample along the curve, all unit length and perpendicular to each other
+#[derive(Debug,PartialEq,Clone)]
+pub struct FrenetFrames {
+    pub tangents: Vec<Vector3>,
+    pub normals: Vec<Vector3>,
+    pub binormals: Vec<Vector3>,
+}
+
+impl FrenetFrames {
+    // frames at segments + 1 samples evenly spaced by arc length. rather than using the actual
+    // curvature, which flips at inflection points and is undefined on straight parts, the
+    // normal is carried from one sample to the next by the rotation between their tangents
+    // (parallel transport). closed spreads the remaining twist between the first and last
+    // frame over the whole curve so they line up
+    pub fn from_curve<C>(curve: &C, segments: usize, closed: bool) -> FrenetFrames
+        where C: Curve<Vector = Vector3> + ?Sized
+    {
+        let segments = segments.max(1);
+        let tangents: Vec<Vector3> = (0..segments + 1)
+            .map(|i| curve.spaced_tangent_at(i as f32 / segments as f32))
+            .collect();
+
+        // initial normal in the direction of the smallest tangent component, ties go to the
+        // later axis
+        let t = &tangents[0];
+        let (tx, ty, tz) = (t.x.abs(), t.y.abs(), t.z.abs());
+        let axis = if tz <= tx && tz <= ty {
+            Vector3::Z
+        } else if ty <= tx {
+            Vector3::Y
+        } else {
+            Vector3::X
+        };
+        let v = t.cross(&axis).normalized();
+
+        let mut normals = vec![t.cross(&v)];
+        let mut binormals = vec![t.cross(&normals[0])];
+
+        for i in 1..segments + 1 {
+            let mut normal = normals[i - 1];
+
+            let v = tangents[i - 1].cross(&tangents[i]);
+            if v.length() > EPSILON {
+                let theta = clamp(tangents[i - 1].dot(&tangents[i]), -1.0, 1.0).acos();
+                normal = normal.apply_axis_angle(&v.normalized(), theta);
+            }
+
+            binormals.push(tangents[i].cross(&normal));
+            normals.push(normal);
+        }
+
+        if closed {
+            let mut theta = clamp(normals[0].dot(&normals[segments]), -1.0, 1.0).acos() /
+                            segments as f32;
+            if tangents[0].dot(&normals[0].cross(&normals[segments])) > 0.0 {
+                theta = -theta;
+            }
+
+            for i in 1..segments + 1 {
+                normals[i] = normals[i].apply_axis_angle(&tangents[i], theta * i as f32);
+                binormals[i] = tangents[i].cross(&normals[i]);
+            }
+        }
+
+        FrenetFrames {
+            tangents: tangents,
+            normals: normals,
+            binormals: binormals,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use extras::{Curve, LineCurve3, QuadraticBezierCurve2};
+    use math::{Vector2, Vector3};
+
+    #[test]
+    fn lengths() {
+        let a = LineCurve3::new(&Vector3::ZERO, &Vector3::new(3.0, 4.0, 0.0));
+        assert_eq!(a.lengths().len(), 201);
+        assert_eq!(a.lengths()[0], 0.0);
+        assert!((a.length() - 5.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn u_to_t() {
+        let a = QuadraticBezierCurve2::new(&Vector2::ZERO,
+                                           &Vector2::new(4.0, 0.0),
+                                           &Vector2::new(4.0, 1.0));
+        assert_eq!(a.u_to_t(0.0), 0.0);
+        assert_eq!(a.u_to_t(1.0), 1.0);
+
+        // t runs faster where the control points are far apart, u does not. the chords are a
+        // little shorter than the arcs in the bend
+        let points = a.spaced_points(10);
+        let step = a.length() / 10.0;
+        for pair in points.windows(2) {
+            assert!((pair[0].distance_to(&pair[1]) - step).abs() < 2e-2);
+        }
+        let t_points = a.points(10);
+        assert!((t_points[0].distance_to(&t_points[1]) - step).abs() > 1e-1);
+
+        let mut b = LineCurve3::new(&Vector3::ZERO, &Vector3::X);
+        assert!((b.length() - 1.0).abs() < 1e-6);
+        b.v2 = Vector3::new(2.0, 0.0, 0.0);
+        b.update_arc_lengths();
+        assert!((b.length() - 2.0).abs() < 1e-6);
+    }
+}
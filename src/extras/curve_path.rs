@@ -0,0 +1,178 @@
+use extras::{ArcLengths, Curve, CurveVector, LineCurve};
+
+// cumulative lengths of the curves, the last one is the length of the whole path
+pub fn curve_lengths<C: Curve>(curves: &[C]) -> Vec<f32> {
+    let mut sum = 0.0;
+    curves.iter()
+        .map(|curve| {
+            sum += curve.length();
+            sum
+        })
+        .collect()
+}
+
+// t is the fraction of the length of the whole path, each curve is walked by arc length. a path
+// without curves is a single point at the origin
+pub fn point_at<C: Curve>(curves: &[C], t: f32) -> C::Vector {
+    let lengths = curve_lengths(curves);
+    let d = t * lengths.last().cloned().unwrap_or(0.0);
+
+    for (curve, length) in curves.iter().zip(lengths.iter()) {
+        if *length >= d {
+            let segment_length = curve.length();
+            let u = if segment_length == 0.0 {
+                0.0
+            } else {
+                1.0 - (length - d) / segment_length
+            };
+            return curve.spaced_point_at(u);
+        }
+    }
+
+    // rounding can leave d just past the end
+    match curves.last() {
+        Some(curve) => curve.point_at(1.0),
+        None => <C::Vector as CurveVector>::ZERO,
+    }
+}
+
+// samples every curve at its own resolution, consecutive duplicates (like the joints between
+// curves) are only returned once
+pub fn points<C: Curve>(curves: &[C], divisions: usize, auto_close: bool) -> Vec<C::Vector> {
+    let mut points: Vec<C::Vector> = vec![];
+
+    for curve in curves {
+        let resolution = curve.resolution(divisions).max(1);
+
+        for d in 0..resolution + 1 {
+            let point = curve.point_at(d as f32 / resolution as f32);
+            if points.last() != Some(&point) {
+                points.push(point);
+            }
+        }
+    }
+
+    close(points, auto_close)
+}
+
+pub fn close<V: CurveVector>(mut points: Vec<V>, auto_close: bool) -> Vec<V> {
+    if auto_close && points.len() > 1 && points.last() != points.first() {
+        let first = points[0];
+        points.push(first);
+    }
+    points
+}
+
+// curves joined end to end, possibly of different kinds
+pub struct CurvePath<V: CurveVector> {
+    pub curves: Vec<Box<Curve<Vector = V>>>,
+    pub auto_close: bool,
+    arc_lengths: ArcLengths,
+}
+
+impl<V: CurveVector + 'static> CurvePath<V> {
+    pub fn new() -> CurvePath<V> {
+        CurvePath {
+            curves: vec![],
+            auto_close: false,
+            arc_lengths: ArcLengths::new(),
+        }
+    }
+
+    pub fn add<C: Curve<Vector = V> + 'static>(&mut self, curve: C) {
+        self.curves.push(Box::new(curve));
+        self.arc_lengths.clear();
+    }
+
+    // adds a line from the end back to the start, unless they already meet
+    pub fn close_path(&mut self) {
+        let ends = match (self.curves.first(), self.curves.last()) {
+            (Some(first), Some(last)) => Some((first.point_at(0.0), last.point_at(1.0))),
+            _ => None,
+        };
+
+        if let Some((start, end)) = ends {
+            if start != end {
+                self.add(LineCurve::new(&end, &start));
+            }
+        }
+    }
+
+    pub fn curve_lengths(&self) -> Vec<f32> {
+        curve_lengths(&self.curves)
+    }
+}
+
+impl<V: CurveVector + 'static> Curve for CurvePath<V> {
+    type Vector = V;
+
+    fn point_at(&self, t: f32) -> V {
+        point_at(&self.curves, t)
+    }
+
+    fn arc_lengths(&self) -> &ArcLengths {
+        &self.arc_lengths
+    }
+
+    fn points(&self, divisions: usize) -> Vec<V> {
+        points(&self.curves, divisions, self.auto_close)
+    }
+
+    fn spaced_points(&self, divisions: usize) -> Vec<V> {
+        let divisions = divisions.max(1);
+        let points = (0..divisions + 1).map(|d| self.point_at(d as f32 / divisions as f32));
+        close(points.collect(), self.auto_close)
+    }
+
+    fn length(&self) -> f32 {
+        self.curve_lengths().last().cloned().unwrap_or(0.0)
+    }
+
+    fn update_arc_lengths(&self) {
+        self.arc_lengths.clear();
+        for curve in &self.curves {
+            curve.update_arc_lengths();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use extras::{Curve, CurvePath, LineCurve3, QuadraticBezierCurve3};
+    use math::Vector3;
+
+    #[test]
+    fn point_at() {
+        let mut a = CurvePath::new();
+        a.add(LineCurve3::new(&Vector3::ZERO, &Vector3::new(2.0, 0.0, 0.0)));
+        a.add(LineCurve3::new(&Vector3::new(2.0, 0.0, 0.0), &Vector3::new(2.0, 2.0, 0.0)));
+        assert!((a.curve_lengths()[0] - 2.0).abs() < 1e-4);
+        assert!((a.length() - 4.0).abs() < 1e-4);
+
+        // t follows the length of the whole path
+        assert!(a.point_at(0.25).distance_to(&Vector3::new(1.0, 0.0, 0.0)) < 1e-4);
+        assert!(a.point_at(0.75).distance_to(&Vector3::new(2.0, 1.0, 0.0)) < 1e-4);
+        assert_eq!(a.points(4).len(), 3);
+
+        a.close_path();
+        assert_eq!(a.curves.len(), 3);
+        assert_eq!(a.curves[2].point_at(1.0), Vector3::ZERO);
+        a.close_path();
+        assert_eq!(a.curves.len(), 3);
+
+        a.add(QuadraticBezierCurve3::new(&Vector3::ZERO,
+                                         &Vector3::new(0.0, 0.0, 1.0),
+                                         &Vector3::new(1.0, 0.0, 1.0)));
+        assert_eq!(a.points(4).len(), 4 + 4);
+        assert_eq!(a.spaced_points(4).len(), 5);
+    }
+
+    #[test]
+    fn empty() {
+        let a: CurvePath<Vector3> = CurvePath::new();
+        assert_eq!(a.length(), 0.0);
+        assert_eq!(a.point_at(0.5), Vector3::ZERO);
+        assert_eq!(a.spaced_points(2), vec![Vector3::ZERO; 3]);
+        assert!(a.points(4).is_empty());
+    }
+}
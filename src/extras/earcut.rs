@@ -0,0 +1,828 @@
+// port of the mapbox earcut ear clipping triangulation that three.js ships with
+use std::cmp::Ordering;
+use std::f32::{INFINITY, NEG_INFINITY};
+use math::{Box2, Vector2};
+
+struct Node {
+    // index of the vertex in the input
+    i: usize,
+    point: Vector2,
+
+    // previous and next node of the polygon ring
+    prev: usize,
+    next: usize,
+
+    // z-order curve value and the neighbours in z-order
+    z: u32,
+    prev_z: Option<usize>,
+    next_z: Option<usize>,
+
+    // a hole made of a single point
+    steiner: bool,
+}
+
+struct Earcut<'a> {
+    vertices: &'a [Vector2],
+    nodes: Vec<Node>,
+    triangles: Vec<usize>,
+
+    // minimum and inverse size of the outer bounds, only set for polygons large enough to
+    // be worth hashing
+    hash: Option<(Vector2, f32)>,
+}
+
+// returns a flat list of vertex indices, three per triangle. hole_indices are the indices
+// of the first vertex of every hole, everything before the first one is the outer contour
+pub fn triangulate(vertices: &[Vector2], hole_indices: &[usize]) -> Vec<usize> {
+    let outer_len = hole_indices.first().cloned().unwrap_or(vertices.len());
+
+    let mut earcut = Earcut {
+        vertices: vertices,
+        nodes: vec![],
+        triangles: vec![],
+        hash: None,
+    };
+
+    let mut outer_node = match earcut.linked_list(0, outer_len, true) {
+        Some(node) => node,
+        None => return vec![],
+    };
+    if earcut.nodes[outer_node].next == earcut.nodes[outer_node].prev {
+        return vec![];
+    }
+
+    if !hole_indices.is_empty() {
+        outer_node = earcut.eliminate_holes(hole_indices, outer_node);
+    }
+
+    // if the shape is not too simple, a z-order curve hash speeds up the ear checks
+    if vertices.len() > 80 {
+        let bounds = vertices[..outer_len]
+            .iter()
+            .fold(Box2::EMPTY, |acc, p| acc.expand_by_point(p));
+        let size = bounds.size();
+        let size = size.x.max(size.y);
+
+        if size != 0.0 {
+            earcut.hash = Some((bounds.min, 32767.0 / size));
+        }
+    }
+
+    earcut.earcut_linked(Some(outer_node), 0);
+    earcut.triangles
+}
+
+impl<'a> Earcut<'a> {
+    // creates a circular doubly linked list from the vertices in the specified winding order
+    fn linked_list(&mut self, start: usize, end: usize, clockwise: bool) -> Option<usize> {
+        let mut last = None;
+
+        if clockwise == (signed_area(&self.vertices[start..end]) > 0.0) {
+            for i in start..end {
+                last = Some(self.insert_node(i, last));
+            }
+        } else {
+            for i in (start..end).rev() {
+                last = Some(self.insert_node(i, last));
+            }
+        }
+
+        if let Some(node) = last {
+            let next = self.nodes[node].next;
+            if self.equals(node, next) {
+                self.remove_node(node);
+                last = Some(next);
+            }
+        }
+
+        last
+    }
+
+    // eliminates colinear or duplicate points
+    fn filter_points(&mut self, start: usize, end: Option<usize>) -> usize {
+        let mut end = end.unwrap_or(start);
+        let mut p = start;
+
+        loop {
+            let mut again = false;
+            let prev = self.nodes[p].prev;
+            let next = self.nodes[p].next;
+
+            if !self.nodes[p].steiner && (self.equals(p, next) || self.area(prev, p, next) == 0.0) {
+                self.remove_node(p);
+                p = prev;
+                end = prev;
+                if p == self.nodes[p].next {
+                    break;
+                }
+                again = true;
+            } else {
+                p = next;
+            }
+
+            if !again && p == end {
+                break;
+            }
+        }
+
+        end
+    }
+
+    // main ear slicing loop which triangulates a polygon given as a linked list
+    fn earcut_linked(&mut self, ear: Option<usize>, pass: usize) {
+        let mut ear = match ear {
+            Some(ear) => ear,
+            None => return,
+        };
+
+        // interlink polygon nodes in z-order
+        if pass == 0 && self.hash.is_some() {
+            self.index_curve(ear);
+        }
+
+        let mut stop = ear;
+
+        // iterate through ears, slicing them one by one
+        while self.nodes[ear].prev != self.nodes[ear].next {
+            let prev = self.nodes[ear].prev;
+            let next = self.nodes[ear].next;
+
+            let is_ear = if self.hash.is_some() {
+                self.is_ear_hashed(ear)
+            } else {
+                self.is_ear(ear)
+            };
+
+            if is_ear {
+                self.triangles.extend_from_slice(&[self.nodes[prev].i,
+                                                   self.nodes[ear].i,
+                                                   self.nodes[next].i]);
+                self.remove_node(ear);
+
+                // skipping the next vertex leads to less sliver triangles
+                ear = self.nodes[next].next;
+                stop = ear;
+                continue;
+            }
+
+            ear = next;
+
+            // looped through the whole remaining polygon and can't find any more ears
+            if ear == stop {
+                match pass {
+                    // try filtering points and slicing again
+                    0 => {
+                        let ear = self.filter_points(ear, None);
+                        self.earcut_linked(Some(ear), 1);
+                    }
+                    // if this didn't work, try curing all small self-intersections locally
+                    1 => {
+                        let ear = self.filter_points(ear, None);
+                        let ear = self.cure_local_intersections(ear);
+                        self.earcut_linked(Some(ear), 2);
+                    }
+                    // as a last resort, try splitting the remaining polygon into two
+                    _ => self.split_earcut(ear),
+                }
+                break;
+            }
+        }
+    }
+
+    // checks whether a polygon node forms a valid ear with adjacent nodes
+    fn is_ear(&self, ear: usize) -> bool {
+        let a = self.nodes[ear].prev;
+        let b = ear;
+        let c = self.nodes[ear].next;
+
+        // reflex, can't be an ear
+        if self.area(a, b, c) >= 0.0 {
+            return false;
+        }
+
+        // make sure we don't have other points inside the potential ear
+        let bounds = self.triangle_bounds(a, b, c);
+        let mut p = self.nodes[c].next;
+        while p != a {
+            if self.blocks_ear(p, a, b, c, &bounds) {
+                return false;
+            }
+            p = self.nodes[p].next;
+        }
+
+        true
+    }
+
+    fn is_ear_hashed(&self, ear: usize) -> bool {
+        let a = self.nodes[ear].prev;
+        let b = ear;
+        let c = self.nodes[ear].next;
+
+        if self.area(a, b, c) >= 0.0 {
+            return false;
+        }
+
+        // z-order range for the current triangle bbox
+        let bounds = self.triangle_bounds(a, b, c);
+        let (min, inv_size) = self.hash.unwrap();
+        let min_z = z_order(&bounds.min, &min, inv_size);
+        let max_z = z_order(&bounds.max, &min, inv_size);
+
+        let mut p = self.nodes[ear].prev_z;
+        let mut n = self.nodes[ear].next_z;
+
+        // look for points inside the triangle in both directions
+        while let (Some(pp), Some(nn)) = (p, n) {
+            if self.nodes[pp].z < min_z || self.nodes[nn].z > max_z {
+                break;
+            }
+
+            if self.blocks_ear(pp, a, b, c, &bounds) {
+                return false;
+            }
+            p = self.nodes[pp].prev_z;
+
+            if self.blocks_ear(nn, a, b, c, &bounds) {
+                return false;
+            }
+            n = self.nodes[nn].next_z;
+        }
+
+        // look for remaining points in decreasing z-order
+        while let Some(pp) = p {
+            if self.nodes[pp].z < min_z {
+                break;
+            }
+            if self.blocks_ear(pp, a, b, c, &bounds) {
+                return false;
+            }
+            p = self.nodes[pp].prev_z;
+        }
+
+        // look for remaining points in increasing z-order
+        while let Some(nn) = n {
+            if self.nodes[nn].z > max_z {
+                break;
+            }
+            if self.blocks_ear(nn, a, b, c, &bounds) {
+                return false;
+            }
+            n = self.nodes[nn].next_z;
+        }
+
+        true
+    }
+
+    fn triangle_bounds(&self, a: usize, b: usize, c: usize) -> Box2 {
+        Box2::EMPTY.expand_by_point(&self.nodes[a].point)
+            .expand_by_point(&self.nodes[b].point)
+            .expand_by_point(&self.nodes[c].point)
+    }
+
+    // a non reflex point inside the triangle a, b, c
+    fn blocks_ear(&self, p: usize, a: usize, b: usize, c: usize, bounds: &Box2) -> bool {
+        let point = &self.nodes[p].point;
+
+        p != a && p != c && bounds.contains_point(point) &&
+        point_in_triangle(&self.nodes[a].point,
+                          &self.nodes[b].point,
+                          &self.nodes[c].point,
+                          point) &&
+        self.area(self.nodes[p].prev, p, self.nodes[p].next) >= 0.0
+    }
+
+    // go through all polygon nodes and cure small local self-intersections
+    fn cure_local_intersections(&mut self, start: usize) -> usize {
+        let mut start = start;
+        let mut p = start;
+
+        loop {
+            let a = self.nodes[p].prev;
+            let p_next = self.nodes[p].next;
+            let b = self.nodes[p_next].next;
+
+            if !self.equals(a, b) && self.intersects(a, p, p_next, b) &&
+               self.locally_inside(a, b) && self.locally_inside(b, a) {
+                self.triangles.extend_from_slice(&[self.nodes[a].i,
+                                                   self.nodes[p].i,
+                                                   self.nodes[b].i]);
+
+                // remove two nodes involved
+                self.remove_node(p);
+                self.remove_node(p_next);
+
+                p = b;
+                start = b;
+            }
+
+            p = self.nodes[p].next;
+            if p == start {
+                break;
+            }
+        }
+
+        self.filter_points(p, None)
+    }
+
+    // try splitting polygon into two and triangulate them independently
+    fn split_earcut(&mut self, start: usize) {
+        // look for a valid diagonal that divides the polygon into two
+        let mut a = start;
+
+        loop {
+            let mut b = self.nodes[self.nodes[a].next].next;
+
+            while b != self.nodes[a].prev {
+                if self.nodes[a].i != self.nodes[b].i && self.is_valid_diagonal(a, b) {
+                    // split the polygon in two by the diagonal
+                    let c = self.split_polygon(a, b);
+
+                    // filter colinear points around the cuts
+                    let a_next = self.nodes[a].next;
+                    let a = self.filter_points(a, Some(a_next));
+                    let c_next = self.nodes[c].next;
+                    let c = self.filter_points(c, Some(c_next));
+
+                    // run earcut on each half
+                    self.earcut_linked(Some(a), 0);
+                    self.earcut_linked(Some(c), 0);
+                    return;
+                }
+                b = self.nodes[b].next;
+            }
+
+            a = self.nodes[a].next;
+            if a == start {
+                break;
+            }
+        }
+    }
+
+    // link every hole into the outer loop, producing a single-ring polygon without holes
+    fn eliminate_holes(&mut self, hole_indices: &[usize], outer_node: usize) -> usize {
+        let mut queue = vec![];
+
+        for (i, &start) in hole_indices.iter().enumerate() {
+            let end = hole_indices.get(i + 1).cloned().unwrap_or(self.vertices.len());
+
+            if let Some(list) = self.linked_list(start, end, false) {
+                if list == self.nodes[list].next {
+                    self.nodes[list].steiner = true;
+                }
+                queue.push(self.leftmost(list));
+            }
+        }
+
+        queue.sort_by(|&a, &b| {
+            self.nodes[a].point.x.partial_cmp(&self.nodes[b].point.x).unwrap_or(Ordering::Equal)
+        });
+
+        // process holes from left to right
+        let mut outer_node = outer_node;
+        for hole in queue {
+            outer_node = self.eliminate_hole(hole, outer_node);
+        }
+
+        outer_node
+    }
+
+    // find a bridge between vertices that connects hole with an outer ring and link it
+    fn eliminate_hole(&mut self, hole: usize, outer_node: usize) -> usize {
+        let bridge = match self.find_hole_bridge(hole, outer_node) {
+            Some(bridge) => bridge,
+            None => return outer_node,
+        };
+
+        let bridge_reverse = self.split_polygon(bridge, hole);
+
+        // filter collinear points around the cuts
+        let next = self.nodes[bridge_reverse].next;
+        self.filter_points(bridge_reverse, Some(next));
+
+        let next = self.nodes[bridge].next;
+        self.filter_points(bridge, Some(next))
+    }
+
+    // David Eberly's algorithm for finding a bridge between hole and outer polygon
+    fn find_hole_bridge(&self, hole: usize, outer_node: usize) -> Option<usize> {
+        let h = self.nodes[hole].point;
+        let mut p = outer_node;
+        let mut qx = NEG_INFINITY;
+        let mut m = None;
+
+        // find a segment intersected by a ray from the hole's leftmost point to the left;
+        // segment's endpoint with lesser x will be potential connection point
+        loop {
+            let next = self.nodes[p].next;
+            let a = self.nodes[p].point;
+            let b = self.nodes[next].point;
+
+            if h.y <= a.y && h.y >= b.y && b.y != a.y {
+                let x = a.x + (h.y - a.y) * (b.x - a.x) / (b.y - a.y);
+
+                if x <= h.x && x > qx {
+                    qx = x;
+                    m = Some(if a.x < b.x { p } else { next });
+
+                    // hole touches outer segment, pick leftmost endpoint
+                    if x == h.x {
+                        return m;
+                    }
+                }
+            }
+
+            p = next;
+            if p == outer_node {
+                break;
+            }
+        }
+
+        let mut m = m?;
+
+        // look for points inside the triangle of hole point, segment intersection and
+        // endpoint; if there are no points found, we have a valid connection; otherwise
+        // choose the point of the minimum angle with the ray as connection point
+        let stop = m;
+        let mp = self.nodes[m].point;
+        let mut tan_min = INFINITY;
+
+        let (left, right) = if h.y < mp.y {
+            (Vector2::new(h.x, h.y), Vector2::new(qx, h.y))
+        } else {
+            (Vector2::new(qx, h.y), Vector2::new(h.x, h.y))
+        };
+
+        p = m;
+        loop {
+            let point = self.nodes[p].point;
+
+            if h.x >= point.x && point.x >= mp.x && h.x != point.x &&
+               point_in_triangle(&left, &mp, &right, &point) {
+                let tan = (h.y - point.y).abs() / (h.x - point.x);
+                let m_x = self.nodes[m].point.x;
+
+                if self.locally_inside(p, hole) &&
+                   (tan < tan_min ||
+                    (tan == tan_min &&
+                     (point.x > m_x || (point.x == m_x && self.sector_contains_sector(m, p))))) {
+                    m = p;
+                    tan_min = tan;
+                }
+            }
+
+            p = self.nodes[p].next;
+            if p == stop {
+                break;
+            }
+        }
+
+        Some(m)
+    }
+
+    // whether sector in vertex m contains sector in vertex p in the same coordinates
+    fn sector_contains_sector(&self, m: usize, p: usize) -> bool {
+        self.area(self.nodes[m].prev, m, self.nodes[p].prev) < 0.0 &&
+        self.area(self.nodes[p].next, m, self.nodes[m].next) < 0.0
+    }
+
+    // interlink polygon nodes in z-order
+    fn index_curve(&mut self, start: usize) {
+        let (min, inv_size) = self.hash.unwrap();
+        let mut p = start;
+
+        loop {
+            if self.nodes[p].z == 0 {
+                self.nodes[p].z = z_order(&self.nodes[p].point, &min, inv_size);
+            }
+            self.nodes[p].prev_z = Some(self.nodes[p].prev);
+            self.nodes[p].next_z = Some(self.nodes[p].next);
+
+            p = self.nodes[p].next;
+            if p == start {
+                break;
+            }
+        }
+
+        let prev_z = self.nodes[p].prev_z.unwrap();
+        self.nodes[prev_z].next_z = None;
+        self.nodes[p].prev_z = None;
+
+        self.sort_linked(p);
+    }
+
+    // Simon Tatham's linked list merge sort algorithm
+    fn sort_linked(&mut self, list: usize) {
+        let mut list = Some(list);
+        let mut in_size = 1;
+
+        loop {
+            let mut p = list;
+            let mut tail: Option<usize> = None;
+            let mut num_merges = 0;
+            list = None;
+
+            while p.is_some() {
+                num_merges += 1;
+
+                let mut q = p;
+                let mut p_size = 0;
+                for _ in 0..in_size {
+                    p_size += 1;
+                    q = self.nodes[q.unwrap()].next_z;
+                    if q.is_none() {
+                        break;
+                    }
+                }
+                let mut q_size = in_size;
+
+                while p_size > 0 || (q_size > 0 && q.is_some()) {
+                    let take_p = match (p, q) {
+                        (Some(pp), Some(qq)) if p_size != 0 && q_size != 0 => {
+                            self.nodes[pp].z <= self.nodes[qq].z
+                        }
+                        _ => p_size != 0,
+                    };
+
+                    let e = if take_p {
+                        let e = p.unwrap();
+                        p = self.nodes[e].next_z;
+                        p_size -= 1;
+                        e
+                    } else {
+                        let e = q.unwrap();
+                        q = self.nodes[e].next_z;
+                        q_size -= 1;
+                        e
+                    };
+
+                    match tail {
+                        Some(tail) => self.nodes[tail].next_z = Some(e),
+                        None => list = Some(e),
+                    }
+                    self.nodes[e].prev_z = tail;
+                    tail = Some(e);
+                }
+
+                p = q;
+            }
+
+            if let Some(tail) = tail {
+                self.nodes[tail].next_z = None;
+            }
+            in_size *= 2;
+
+            if num_merges <= 1 {
+                break;
+            }
+        }
+    }
+
+    // find the leftmost node of a polygon ring
+    fn leftmost(&self, start: usize) -> usize {
+        let mut p = start;
+        let mut leftmost = start;
+
+        loop {
+            let a = self.nodes[p].point;
+            let b = self.nodes[leftmost].point;
+            if a.x < b.x || (a.x == b.x && a.y < b.y) {
+                leftmost = p;
+            }
+
+            p = self.nodes[p].next;
+            if p == start {
+                break;
+            }
+        }
+
+        leftmost
+    }
+
+    // check if a diagonal between two polygon nodes is valid (lies in polygon interior)
+    fn is_valid_diagonal(&self, a: usize, b: usize) -> bool {
+        let (a_prev, a_next) = (self.nodes[a].prev, self.nodes[a].next);
+        let (b_prev, b_next) = (self.nodes[b].prev, self.nodes[b].next);
+
+        // doesn't intersect other edges
+        self.nodes[a_next].i != self.nodes[b].i && self.nodes[a_prev].i != self.nodes[b].i &&
+        !self.intersects_polygon(a, b) &&
+        // locally visible and does not create opposite-facing sectors
+        ((self.locally_inside(a, b) && self.locally_inside(b, a) && self.middle_inside(a, b) &&
+          (self.area(a_prev, a, b_prev) != 0.0 || self.area(a, b_prev, b) != 0.0)) ||
+         // special zero-length case
+         (self.equals(a, b) && self.area(a_prev, a, a_next) > 0.0 &&
+          self.area(b_prev, b, b_next) > 0.0))
+    }
+
+    // signed area of a triangle
+    fn area(&self, p: usize, q: usize, r: usize) -> f32 {
+        let p = &self.nodes[p].point;
+        let q = &self.nodes[q].point;
+        let r = &self.nodes[r].point;
+
+        (q.y - p.y) * (r.x - q.x) - (q.x - p.x) * (r.y - q.y)
+    }
+
+    fn equals(&self, a: usize, b: usize) -> bool {
+        self.nodes[a].point == self.nodes[b].point
+    }
+
+    // check if two segments intersect
+    fn intersects(&self, p1: usize, q1: usize, p2: usize, q2: usize) -> bool {
+        let o1 = sign(self.area(p1, q1, p2));
+        let o2 = sign(self.area(p1, q1, q2));
+        let o3 = sign(self.area(p2, q2, p1));
+        let o4 = sign(self.area(p2, q2, q1));
+
+        if o1 != o2 && o3 != o4 {
+            return true;
+        }
+
+        let on_segment = |p: usize, q: usize, r: usize| {
+            let p = &self.nodes[p].point;
+            let q = &self.nodes[q].point;
+            let r = &self.nodes[r].point;
+            q.x <= p.x.max(r.x) && q.x >= p.x.min(r.x) && q.y <= p.y.max(r.y) &&
+            q.y >= p.y.min(r.y)
+        };
+
+        // collinear and lying on the other segment
+        (o1 == 0 && on_segment(p1, p2, q1)) || (o2 == 0 && on_segment(p1, q2, q1)) ||
+        (o3 == 0 && on_segment(p2, p1, q2)) || (o4 == 0 && on_segment(p2, q1, q2))
+    }
+
+    // check if a polygon diagonal intersects any polygon segments
+    fn intersects_polygon(&self, a: usize, b: usize) -> bool {
+        let (a_i, b_i) = (self.nodes[a].i, self.nodes[b].i);
+        let mut p = a;
+
+        loop {
+            let next = self.nodes[p].next;
+            let (p_i, next_i) = (self.nodes[p].i, self.nodes[next].i);
+
+            if p_i != a_i && next_i != a_i && p_i != b_i && next_i != b_i &&
+               self.intersects(p, next, a, b) {
+                return true;
+            }
+
+            p = next;
+            if p == a {
+                break;
+            }
+        }
+
+        false
+    }
+
+    // check if a polygon diagonal is locally inside the polygon
+    fn locally_inside(&self, a: usize, b: usize) -> bool {
+        let (prev, next) = (self.nodes[a].prev, self.nodes[a].next);
+
+        if self.area(prev, a, next) < 0.0 {
+            self.area(a, b, next) >= 0.0 && self.area(a, prev, b) >= 0.0
+        } else {
+            self.area(a, b, prev) < 0.0 || self.area(a, next, b) < 0.0
+        }
+    }
+
+    // check if the middle point of a polygon diagonal is inside the polygon
+    fn middle_inside(&self, a: usize, b: usize) -> bool {
+        let middle = self.nodes[a].point.add(&self.nodes[b].point).multiply_scalar(0.5);
+        let mut inside = false;
+        let mut p = a;
+
+        loop {
+            let next = self.nodes[p].next;
+            let pp = self.nodes[p].point;
+            let np = self.nodes[next].point;
+
+            if (pp.y > middle.y) != (np.y > middle.y) && np.y != pp.y &&
+               middle.x < (np.x - pp.x) * (middle.y - pp.y) / (np.y - pp.y) + pp.x {
+                inside = !inside;
+            }
+
+            p = next;
+            if p == a {
+                break;
+            }
+        }
+
+        inside
+    }
+
+    // link two polygon vertices with a bridge; if the vertices belong to the same ring,
+    // it splits polygon into two; if one belongs to the outer ring and another to a hole,
+    // it merges it into a single ring
+    fn split_polygon(&mut self, a: usize, b: usize) -> usize {
+        let a2 = self.insert_node(self.nodes[a].i, None);
+        let b2 = self.insert_node(self.nodes[b].i, None);
+        let an = self.nodes[a].next;
+        let bp = self.nodes[b].prev;
+
+        self.nodes[a].next = b;
+        self.nodes[b].prev = a;
+
+        self.nodes[a2].next = an;
+        self.nodes[an].prev = a2;
+
+        self.nodes[b2].next = a2;
+        self.nodes[a2].prev = b2;
+
+        self.nodes[bp].next = b2;
+        self.nodes[b2].prev = bp;
+
+        b2
+    }
+
+    // create a node and optionally link it with previous one (in a circular doubly linked
+    // list)
+    fn insert_node(&mut self, i: usize, last: Option<usize>) -> usize {
+        let p = self.nodes.len();
+        self.nodes.push(Node {
+            i: i,
+            point: self.vertices[i],
+            prev: p,
+            next: p,
+            z: 0,
+            prev_z: None,
+            next_z: None,
+            steiner: false,
+        });
+
+        if let Some(last) = last {
+            let last_next = self.nodes[last].next;
+            self.nodes[p].next = last_next;
+            self.nodes[p].prev = last;
+            self.nodes[last_next].prev = p;
+            self.nodes[last].next = p;
+        }
+
+        p
+    }
+
+    // unlinks the node, it keeps its own links so iteration can continue from it
+    fn remove_node(&mut self, p: usize) {
+        let (prev, next) = (self.nodes[p].prev, self.nodes[p].next);
+        self.nodes[next].prev = prev;
+        self.nodes[prev].next = next;
+
+        let (prev_z, next_z) = (self.nodes[p].prev_z, self.nodes[p].next_z);
+        if let Some(prev_z) = prev_z {
+            self.nodes[prev_z].next_z = next_z;
+        }
+        if let Some(next_z) = next_z {
+            self.nodes[next_z].prev_z = prev_z;
+        }
+    }
+}
+
+// z-order of a point given coords and inverse of the longer side of data bbox
+fn z_order(point: &Vector2, min: &Vector2, inv_size: f32) -> u32 {
+    let mut x = ((point.x - min.x) * inv_size) as u32;
+    let mut y = ((point.y - min.y) * inv_size) as u32;
+
+    x = (x | (x << 8)) & 0x00FF00FF;
+    x = (x | (x << 4)) & 0x0F0F0F0F;
+    x = (x | (x << 2)) & 0x33333333;
+    x = (x | (x << 1)) & 0x55555555;
+
+    y = (y | (y << 8)) & 0x00FF00FF;
+    y = (y | (y << 4)) & 0x0F0F0F0F;
+    y = (y | (y << 2)) & 0x33333333;
+    y = (y | (y << 1)) & 0x55555555;
+
+    x | (y << 1)
+}
+
+// check if a point lies within a convex triangle
+fn point_in_triangle(a: &Vector2, b: &Vector2, c: &Vector2, p: &Vector2) -> bool {
+    (c.x - p.x) * (a.y - p.y) >= (a.x - p.x) * (c.y - p.y) &&
+    (a.x - p.x) * (b.y - p.y) >= (b.x - p.x) * (a.y - p.y) &&
+    (b.x - p.x) * (c.y - p.y) >= (c.x - p.x) * (b.y - p.y)
+}
+
+fn sign(value: f32) -> i32 {
+    if value > 0.0 {
+        1
+    } else if value < 0.0 {
+        -1
+    } else {
+        0
+    }
+}
+
+fn signed_area(points: &[Vector2]) -> f32 {
+    if points.is_empty() {
+        return 0.0;
+    }
+
+    let mut sum = 0.0;
+    let mut j = points.len() - 1;
+
+    for i in 0..points.len() {
+        sum += (points[j].x - points[i].x) * (points[i].y + points[j].y);
+        j = i;
+    }
+
+    sum
+}
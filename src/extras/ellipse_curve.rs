@@ -0,0 +1,121 @@
+use std::f32::EPSILON;
+use std::f32::consts::PI;
+use extras::{ArcLengths, Curve};
+use math::Vector2;
+
+// angles are in radians, counterclockwise from +X. rotation turns the ellipse around its center
+#[derive(Debug,PartialEq,Clone)]
+pub struct EllipseCurve {
+    pub center: Vector2,
+    pub x_radius: f32,
+    pub y_radius: f32,
+    pub start_angle: f32,
+    pub end_angle: f32,
+    pub clockwise: bool,
+    pub rotation: f32,
+    arc_lengths: ArcLengths,
+}
+
+// an arc is an ellipse with equal radii
+pub type ArcCurve = EllipseCurve;
+
+impl EllipseCurve {
+    pub fn new(center: &Vector2,
+               x_radius: f32,
+               y_radius: f32,
+               start_angle: f32,
+               end_angle: f32,
+               clockwise: bool,
+               rotation: f32)
+               -> EllipseCurve {
+        EllipseCurve {
+            center: *center,
+            x_radius: x_radius,
+            y_radius: y_radius,
+            start_angle: start_angle,
+            end_angle: end_angle,
+            clockwise: clockwise,
+            rotation: rotation,
+            arc_lengths: ArcLengths::new(),
+        }
+    }
+
+    pub fn arc(center: &Vector2,
+               radius: f32,
+               start_angle: f32,
+               end_angle: f32,
+               clockwise: bool)
+               -> ArcCurve {
+        EllipseCurve::new(center, radius, radius, start_angle, end_angle, clockwise, 0.0)
+    }
+}
+
+impl Curve for EllipseCurve {
+    type Vector = Vector2;
+
+    fn point_at(&self, t: f32) -> Vector2 {
+        let two_pi = PI * 2.0;
+        let mut delta_angle = self.end_angle - self.start_angle;
+        let same_points = delta_angle.abs() < EPSILON;
+
+        // ensures that delta_angle is 0 .. 2 PI
+        while delta_angle < 0.0 {
+            delta_angle += two_pi;
+        }
+        while delta_angle > two_pi {
+            delta_angle -= two_pi;
+        }
+
+        if delta_angle < EPSILON {
+            delta_angle = if same_points { 0.0 } else { two_pi };
+        }
+
+        if self.clockwise && !same_points {
+            delta_angle = if delta_angle == two_pi {
+                -two_pi
+            } else {
+                delta_angle - two_pi
+            };
+        }
+
+        let angle = self.start_angle + t * delta_angle;
+        let point = Vector2::new(self.center.x + self.x_radius * angle.cos(),
+                                 self.center.y + self.y_radius * angle.sin());
+
+        if self.rotation != 0.0 {
+            point.rotate_around(&self.center, self.rotation)
+        } else {
+            point
+        }
+    }
+
+    fn arc_lengths(&self) -> &ArcLengths {
+        &self.arc_lengths
+    }
+
+    fn resolution(&self, divisions: usize) -> usize {
+        divisions * 2
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+    use extras::{Curve, EllipseCurve};
+    use math::Vector2;
+
+    #[test]
+    fn point_at() {
+        let a = EllipseCurve::arc(&Vector2::ZERO, 2.0, 0.0, PI, false);
+        assert!(a.point_at(0.5).distance_to(&Vector2::new(0.0, 2.0)) < 1e-6);
+        assert!((a.length() - 2.0 * PI).abs() < 1e-3);
+
+        // clockwise goes the long way round through the bottom
+        let b = EllipseCurve::arc(&Vector2::ZERO, 2.0, 0.0, PI, true);
+        assert!(b.point_at(0.5).distance_to(&Vector2::new(0.0, -2.0)) < 1e-6);
+
+        let c = EllipseCurve::new(&Vector2::ZERO, 2.0, 1.0, 0.0, PI * 2.0, false, PI / 2.0);
+        assert!(c.point_at(0.0).distance_to(&Vector2::new(0.0, 2.0)) < 1e-6);
+        assert!(c.point_at(0.25).distance_to(&Vector2::new(-1.0, 0.0)) < 1e-6);
+    }
+}
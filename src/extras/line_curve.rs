@@ -0,0 +1,56 @@
+use extras::{ArcLengths, Curve, CurveVector};
+use math::{Vector2, Vector3};
+
+#[derive(Debug,PartialEq,Clone)]
+pub struct LineCurve<V: CurveVector> {
+    pub v1: V,
+    pub v2: V,
+    arc_lengths: ArcLengths,
+}
+
+pub type LineCurve2 = LineCurve<Vector2>;
+pub type LineCurve3 = LineCurve<Vector3>;
+
+impl<V: CurveVector> LineCurve<V> {
+    pub fn new(v1: &V, v2: &V) -> LineCurve<V> {
+        LineCurve {
+            v1: *v1,
+            v2: *v2,
+            arc_lengths: ArcLengths::new(),
+        }
+    }
+}
+
+impl<V: CurveVector> Curve for LineCurve<V> {
+    type Vector = V;
+
+    fn point_at(&self, t: f32) -> V {
+        if t == 1.0 {
+            self.v2
+        } else {
+            self.v2.subtract(&self.v1).multiply_scalar(t).add(&self.v1)
+        }
+    }
+
+    fn arc_lengths(&self) -> &ArcLengths {
+        &self.arc_lengths
+    }
+
+    fn tangent_at(&self, _: f32) -> V {
+        self.v2.subtract(&self.v1).normalized()
+    }
+
+    // the end points describe a line exactly
+    fn resolution(&self, _: usize) -> usize {
+        1
+    }
+
+    // t already moves at constant speed
+    fn spaced_point_at(&self, u: f32) -> V {
+        self.point_at(u)
+    }
+
+    fn spaced_tangent_at(&self, u: f32) -> V {
+        self.tangent_at(u)
+    }
+}
@@ -0,0 +1,28 @@
+mod catmull_rom_curve3;
+mod cubic_bezier_curve;
+mod curve;
+mod curve_path;
+mod earcut;
+mod ellipse_curve;
+mod line_curve;
+mod nurbs_curve;
+mod nurbs_surface;
+mod nurbs_utils;
+mod path;
+mod quadratic_bezier_curve;
+mod shape;
+mod shape_utils;
+
+pub use self::catmull_rom_curve3::{CatmullRomCurve3, CatmullRomType};
+pub use self::cubic_bezier_curve::{CubicBezierCurve, CubicBezierCurve2, CubicBezierCurve3};
+pub use self::curve::{ArcLengths, Curve, CurveVector, FrenetFrames};
+pub use self::curve_path::CurvePath;
+pub use self::ellipse_curve::{ArcCurve, EllipseCurve};
+pub use self::line_curve::{LineCurve, LineCurve2, LineCurve3};
+pub use self::nurbs_curve::NurbsCurve;
+pub use self::nurbs_surface::NurbsSurface;
+pub use self::path::{Path, PathSegment};
+pub use self::quadratic_bezier_curve::{QuadraticBezierCurve, QuadraticBezierCurve2,
+                                       QuadraticBezierCurve3};
+pub use self::shape::Shape;
+pub use self::shape_utils::ShapeUtils;
@@ -0,0 +1,120 @@
+use extras::{nurbs_utils, ArcLengths, Curve};
+use math::{Vector3, Vector4};
+
+// control points are cartesian with their weight in w. t runs over the knots from start_knot
+// to end_knot
+#[derive(Debug,PartialEq,Clone)]
+pub struct NurbsCurve {
+    pub degree: usize,
+    pub knots: Vec<f32>,
+    pub control_points: Vec<Vector4>,
+    pub start_knot: usize,
+    pub end_knot: usize,
+    arc_lengths: ArcLengths,
+}
+
+impl NurbsCurve {
+    // a curve of degree p with n control points needs n + p + 1 knots
+    pub fn new(degree: usize, knots: &[f32], control_points: &[Vector4]) -> NurbsCurve {
+        if knots.len() != control_points.len() + degree + 1 {
+            panic!("NurbsCurve needs control points + degree + 1 knots.");
+        }
+
+        NurbsCurve {
+            degree: degree,
+            knots: knots.to_vec(),
+            control_points: control_points.to_vec(),
+            start_knot: 0,
+            end_knot: knots.len() - 1,
+            arc_lengths: ArcLengths::new(),
+        }
+    }
+
+    fn knot_at(&self, t: f32) -> f32 {
+        let start = self.knots[self.start_knot];
+        start + t * (self.knots[self.end_knot] - start)
+    }
+
+    // the point followed by its derivatives up to the given order, with respect to the knot
+    // value rather than t
+    pub fn derivatives(&self, t: f32, order: usize) -> Vec<Vector3> {
+        nurbs_utils::curve_derivatives(self.degree,
+                                       &self.knots,
+                                       &self.control_points,
+                                       self.knot_at(t),
+                                       order)
+    }
+}
+
+impl Curve for NurbsCurve {
+    type Vector = Vector3;
+
+    fn point_at(&self, t: f32) -> Vector3 {
+        let point = nurbs_utils::curve_point(self.degree,
+                                             &self.knots,
+                                             &self.control_points,
+                                             self.knot_at(t));
+        point.xyz().divide_scalar(point.w)
+    }
+
+    fn arc_lengths(&self) -> &ArcLengths {
+        &self.arc_lengths
+    }
+
+    fn tangent_at(&self, t: f32) -> Vector3 {
+        self.derivatives(t, 1)[1].normalized()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::SQRT_2;
+    use core::BufferGeometry;
+    use extras::{Curve, NurbsCurve};
+    use geometries::TubeGeometry;
+    use math::{Vector3, Vector4};
+
+    // a quarter circle, the exact conic a polynomial spline can't represent
+    fn quarter_circle() -> NurbsCurve {
+        NurbsCurve::new(2,
+                        &[0.0, 0.0, 0.0, 1.0, 1.0, 1.0],
+                        &[Vector4::new(1.0, 0.0, 0.0, 1.0),
+                          Vector4::new(1.0, 1.0, 0.0, SQRT_2 / 2.0),
+                          Vector4::new(0.0, 1.0, 0.0, 1.0)])
+    }
+
+    #[test]
+    fn point_at() {
+        let a = quarter_circle();
+        assert_eq!(a.point_at(0.0), Vector3::X);
+        assert_eq!(a.point_at(1.0), Vector3::Y);
+        for i in 0..11 {
+            let point = a.point_at(i as f32 / 10.0);
+            assert!((point.length() - 1.0).abs() < 1e-6);
+        }
+        assert!((a.length() - ::std::f32::consts::PI / 2.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn derivatives() {
+        let a = quarter_circle();
+        let ders = a.derivatives(0.5, 2);
+        assert_eq!(ders.len(), 3);
+        assert!(ders[0].subtract(&a.point_at(0.5)).length() < 1e-6);
+
+        // the first derivative is tangent to the circle, the second points inwards
+        assert!(ders[1].dot(&ders[0]).abs() < 1e-5);
+        assert!(ders[2].dot(&ders[0]) < 0.0);
+        assert!(a.tangent_at(0.0).subtract(&Vector3::Y).length() < 1e-6);
+        assert!(a.tangent_at(1.0).subtract(&Vector3::NEG_X).length() < 1e-6);
+    }
+
+    #[test]
+    fn tessellate() {
+        let a = quarter_circle();
+        assert_eq!(BufferGeometry::from_points(&a.spaced_points(16)).vertex_count(), 17);
+
+        let tube = TubeGeometry::new(a, 16, 0.1, 8, false).build();
+        assert_eq!(tube.vertex_count(), 17 * 9);
+    }
+}
@@ -0,0 +1,130 @@
+use extras::nurbs_utils;
+use math::{Vector3, Vector4};
+
+// control_points[i][j] is the ith point along u and the jth along v, cartesian with the
+// weight in w. u and v run from 0 to 1 over the whole knot vectors
+#[derive(Debug,PartialEq,Clone)]
+pub struct NurbsSurface {
+    pub degree1: usize,
+    pub degree2: usize,
+    pub knots1: Vec<f32>,
+    pub knots2: Vec<f32>,
+    pub control_points: Vec<Vec<Vector4>>,
+}
+
+impl NurbsSurface {
+    pub fn new(degree1: usize,
+               degree2: usize,
+               knots1: &[f32],
+               knots2: &[f32],
+               control_points: &[Vec<Vector4>])
+               -> NurbsSurface {
+        let rows = control_points.len();
+        let columns = control_points.first().map_or(0, |row| row.len());
+
+        if knots1.len() != rows + degree1 + 1 || knots2.len() != columns + degree2 + 1 ||
+           control_points.iter().any(|row| row.len() != columns) {
+            panic!("NurbsSurface needs control points + degree + 1 knots in both directions.");
+        }
+
+        NurbsSurface {
+            degree1: degree1,
+            degree2: degree2,
+            knots1: knots1.to_vec(),
+            knots2: knots2.to_vec(),
+            control_points: control_points.to_vec(),
+        }
+    }
+
+    fn knots_at(&self, u: f32, v: f32) -> (f32, f32) {
+        let knot = |knots: &[f32], t: f32| knots[0] + t * (knots[knots.len() - 1] - knots[0]);
+        (knot(&self.knots1, u), knot(&self.knots2, v))
+    }
+
+    pub fn point_at(&self, u: f32, v: f32) -> Vector3 {
+        let (u, v) = self.knots_at(u, v);
+        nurbs_utils::surface_point(self.degree1,
+                                   self.degree2,
+                                   &self.knots1,
+                                   &self.knots2,
+                                   &self.control_points,
+                                   u,
+                                   v)
+    }
+
+    // derivatives[k][l] is differentiated k times along u and l times along v, for
+    // k + l <= order. derivatives[0][0] is the point itself
+    pub fn derivatives(&self, u: f32, v: f32, order: usize) -> Vec<Vec<Vector3>> {
+        let (u, v) = self.knots_at(u, v);
+        nurbs_utils::surface_derivatives(self.degree1,
+                                         self.degree2,
+                                         &self.knots1,
+                                         &self.knots2,
+                                         &self.control_points,
+                                         u,
+                                         v,
+                                         order)
+    }
+
+    // the cross product of the partial derivatives, zero where the surface is degenerate
+    pub fn normal_at(&self, u: f32, v: f32) -> Vector3 {
+        let ders = self.derivatives(u, v, 1);
+        let normal = ders[1][0].cross(&ders[0][1]);
+
+        if normal.length_squared() > 0.0 {
+            normal.normalized()
+        } else {
+            Vector3::ZERO
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use extras::NurbsSurface;
+    use math::{Vector3, Vector4};
+
+    // a bilinear patch curved along v by a raised middle row
+    fn patch() -> NurbsSurface {
+        let row = |x: f32| {
+            vec![Vector4::new(x, 0.0, 0.0, 1.0),
+                 Vector4::new(x, 1.0, 1.0, 2.0),
+                 Vector4::new(x, 0.0, 2.0, 1.0)]
+        };
+        NurbsSurface::new(1,
+                          2,
+                          &[0.0, 0.0, 1.0, 1.0],
+                          &[0.0, 0.0, 0.0, 1.0, 1.0, 1.0],
+                          &[row(0.0), row(1.0)])
+    }
+
+    #[test]
+    fn point_at() {
+        let a = patch();
+        assert_eq!(a.point_at(0.0, 0.0), Vector3::ZERO);
+        assert_eq!(a.point_at(1.0, 1.0), Vector3::new(1.0, 0.0, 2.0));
+
+        // the middle row pulls the surface up, more so for its weight of 2
+        let middle = a.point_at(0.5, 0.5);
+        assert!(middle.subtract(&Vector3::new(0.5, 2.0 / 3.0, 1.0)).length() < 1e-6);
+    }
+
+    #[test]
+    fn derivatives() {
+        let a = patch();
+        let ders = a.derivatives(0.25, 0.5, 2);
+        assert_eq!(ders[0][0], a.point_at(0.25, 0.5));
+        assert!(ders[1][0].subtract(&Vector3::X).length() < 1e-6);
+        assert!(ders[2][0].length() < 1e-6);
+
+        // compare with central differences
+        let h = 1e-3;
+        let dv = a.point_at(0.25, 0.3 + h)
+            .subtract(&a.point_at(0.25, 0.3 - h))
+            .divide_scalar(2.0 * h);
+        assert!(a.derivatives(0.25, 0.3, 1)[0][1].subtract(&dv).length() < 1e-2);
+
+        // u runs along x and v along z at the top of the arch, x cross z is -y
+        assert!(a.normal_at(0.5, 0.5).subtract(&Vector3::NEG_Y).length() < 1e-5);
+    }
+}
@@ -0,0 +1,318 @@
+// B-spline and NURBS evaluation, after "The NURBS Book" by Piegl and Tiller like the three.js
+// NURBSUtils. control points are cartesian with the weight in w, they are only turned into
+// homogeneous coordinates (x * w, y * w, z * w, w) while evaluating
+use math::{Vector3, Vector4};
+
+fn homogeneous(point: &Vector4) -> Vector4 {
+    Vector4::new(point.x * point.w, point.y * point.w, point.z * point.w, point.w)
+}
+
+// the knot span u lies in, A2.1
+pub fn find_span(p: usize, u: f32, knots: &[f32]) -> usize {
+    let n = knots.len() - p - 1;
+
+    if u >= knots[n] {
+        return n - 1;
+    }
+
+    if u <= knots[p] {
+        return p;
+    }
+
+    let mut low = p;
+    let mut high = n;
+    let mut mid = (low + high) / 2;
+
+    while u < knots[mid] || u >= knots[mid + 1] {
+        if u < knots[mid] {
+            high = mid;
+        } else {
+            low = mid;
+        }
+        mid = (low + high) / 2;
+    }
+
+    mid
+}
+
+// the p + 1 non zero basis functions at u, A2.2
+pub fn basis_functions(span: usize, u: f32, p: usize, knots: &[f32]) -> Vec<f32> {
+    let mut n = vec![0.0; p + 1];
+    let mut left = vec![0.0; p + 1];
+    let mut right = vec![0.0; p + 1];
+    n[0] = 1.0;
+
+    for j in 1..p + 1 {
+        left[j] = u - knots[span + 1 - j];
+        right[j] = knots[span + j] - u;
+
+        let mut saved = 0.0;
+        for r in 0..j {
+            let rv = right[r + 1];
+            let lv = left[j - r];
+            let temp = n[r] / (rv + lv);
+            n[r] = saved + rv * temp;
+            saved = lv * temp;
+        }
+        n[j] = saved;
+    }
+
+    n
+}
+
+// the basis functions and their derivatives up to order n, ders[k][j] is the kth derivative
+// of the jth function, A2.3
+pub fn basis_function_derivatives(span: usize,
+                                  u: f32,
+                                  p: usize,
+                                  n: usize,
+                                  knots: &[f32])
+                                  -> Vec<Vec<f32>> {
+    let mut ders = vec![vec![0.0; p + 1]; n + 1];
+    let mut ndu = vec![vec![0.0; p + 1]; p + 1];
+    let mut left = vec![0.0; p + 1];
+    let mut right = vec![0.0; p + 1];
+    ndu[0][0] = 1.0;
+
+    for j in 1..p + 1 {
+        left[j] = u - knots[span + 1 - j];
+        right[j] = knots[span + j] - u;
+
+        let mut saved = 0.0;
+        for r in 0..j {
+            let rv = right[r + 1];
+            let lv = left[j - r];
+            ndu[j][r] = rv + lv;
+
+            let temp = ndu[r][j - 1] / ndu[j][r];
+            ndu[r][j] = saved + rv * temp;
+            saved = lv * temp;
+        }
+        ndu[j][j] = saved;
+    }
+
+    for j in 0..p + 1 {
+        ders[0][j] = ndu[j][p];
+    }
+
+    let p = p as isize;
+    for r in 0..p + 1 {
+        let mut s1 = 0;
+        let mut s2 = 1;
+        let mut a = vec![vec![0.0; p as usize + 1]; 2];
+        a[0][0] = 1.0;
+
+        for k in 1..n as isize + 1 {
+            let mut d = 0.0;
+            let rk = r - k;
+            let pk = p - k;
+
+            if r >= k {
+                a[s2][0] = a[s1][0] / ndu[(pk + 1) as usize][rk as usize];
+                d = a[s2][0] * ndu[rk as usize][pk as usize];
+            }
+
+            let j1 = if rk >= -1 { 1 } else { -rk };
+            let j2 = if r - 1 <= pk { k - 1 } else { p - r };
+
+            for j in j1..j2 + 1 {
+                let ju = j as usize;
+                a[s2][ju] = (a[s1][ju] - a[s1][ju - 1]) /
+                            ndu[(pk + 1) as usize][(rk + j) as usize];
+                d += a[s2][ju] * ndu[(rk + j) as usize][pk as usize];
+            }
+
+            if r <= pk {
+                a[s2][k as usize] = -a[s1][k as usize - 1] / ndu[(pk + 1) as usize][r as usize];
+                d += a[s2][k as usize] * ndu[r as usize][pk as usize];
+            }
+
+            ders[k as usize][r as usize] = d;
+            ::std::mem::swap(&mut s1, &mut s2);
+        }
+    }
+
+    let mut r = p as f32;
+    for (k, row) in ders.iter_mut().enumerate().skip(1) {
+        for d in row.iter_mut() {
+            *d *= r;
+        }
+        r *= (p - k as isize) as f32;
+    }
+
+    ders
+}
+
+// k over i
+pub fn binomial(k: usize, i: usize) -> f32 {
+    let factorial = |n: usize| (2..n + 1).fold(1.0, |f, j| f * j as f32);
+    factorial(k) / (factorial(i) * factorial(k - i))
+}
+
+// the curve point in homogeneous coordinates, A4.1
+pub fn curve_point(p: usize, knots: &[f32], control_points: &[Vector4], u: f32) -> Vector4 {
+    let span = find_span(p, u, knots);
+    let n = basis_functions(span, u, p, knots);
+
+    (0..p + 1).fold(Vector4::ZERO, |c, j| {
+        c.add(&homogeneous(&control_points[span - p + j]).multiply_scalar(n[j]))
+    })
+}
+
+// derivatives of the homogeneous curve up to order nd, the ones past the degree are zero, A3.2
+fn homogeneous_curve_derivatives(p: usize,
+                                 knots: &[f32],
+                                 control_points: &[Vector4],
+                                 u: f32,
+                                 nd: usize)
+                                 -> Vec<Vector4> {
+    let du = nd.min(p);
+    let span = find_span(p, u, knots);
+    let nders = basis_function_derivatives(span, u, p, du, knots);
+
+    (0..nd + 1)
+        .map(|k| if k > du {
+            Vector4::ZERO
+        } else {
+            (0..p + 1).fold(Vector4::ZERO, |c, j| {
+                c.add(&homogeneous(&control_points[span - p + j]).multiply_scalar(nders[k][j]))
+            })
+        })
+        .collect()
+}
+
+// the point and its derivatives up to order nd of the rational curve, A4.2
+pub fn curve_derivatives(p: usize,
+                         knots: &[f32],
+                         control_points: &[Vector4],
+                         u: f32,
+                         nd: usize)
+                         -> Vec<Vector3> {
+    let ders = homogeneous_curve_derivatives(p, knots, control_points, u, nd);
+    let mut ck: Vec<Vector3> = vec![];
+
+    for k in 0..nd + 1 {
+        let mut v = ders[k].xyz();
+        for i in 1..k + 1 {
+            v = v.subtract(&ck[k - i].multiply_scalar(binomial(k, i) * ders[i].w));
+        }
+        ck.push(v.divide_scalar(ders[0].w));
+    }
+
+    ck
+}
+
+// derivatives of the rational surface, skl[k][l] is differentiated k times in u and l times
+// in v, with k + l <= d. A3.6 and A4.4
+pub fn surface_derivatives(p: usize,
+                           q: usize,
+                           knots1: &[f32],
+                           knots2: &[f32],
+                           control_points: &[Vec<Vector4>],
+                           u: f32,
+                           v: f32,
+                           d: usize)
+                           -> Vec<Vec<Vector3>> {
+    let du = d.min(p);
+    let dv = d.min(q);
+    let uspan = find_span(p, u, knots1);
+    let vspan = find_span(q, v, knots2);
+    let nu = basis_function_derivatives(uspan, u, p, du, knots1);
+    let nv = basis_function_derivatives(vspan, v, q, dv, knots2);
+
+    // homogeneous derivatives, zero past either degree
+    let mut aders = vec![vec![Vector4::ZERO; d + 1]; d + 1];
+    for k in 0..du + 1 {
+        let temp: Vec<Vector4> = (0..q + 1)
+            .map(|s| {
+                (0..p + 1).fold(Vector4::ZERO, |t, r| {
+                    let point = &control_points[uspan - p + r][vspan - q + s];
+                    t.add(&homogeneous(point).multiply_scalar(nu[k][r]))
+                })
+            })
+            .collect();
+
+        for l in 0..(d - k).min(dv) + 1 {
+            aders[k][l] = (0..q + 1)
+                .fold(Vector4::ZERO, |s, i| s.add(&temp[i].multiply_scalar(nv[l][i])));
+        }
+    }
+
+    let w = aders[0][0].w;
+    let mut skl = vec![vec![Vector3::ZERO; d + 1]; d + 1];
+    for k in 0..d + 1 {
+        for l in 0..d - k + 1 {
+            let mut v = aders[k][l].xyz();
+
+            for j in 1..l + 1 {
+                v = v.subtract(&skl[k][l - j].multiply_scalar(binomial(l, j) * aders[0][j].w));
+            }
+
+            for i in 1..k + 1 {
+                v = v.subtract(&skl[k - i][l].multiply_scalar(binomial(k, i) * aders[i][0].w));
+
+                let mut v2 = Vector3::ZERO;
+                for j in 1..l + 1 {
+                    v2 = v2.add(&skl[k - i][l - j]
+                        .multiply_scalar(binomial(l, j) * aders[i][j].w));
+                }
+                v = v.subtract(&v2.multiply_scalar(binomial(k, i)));
+            }
+
+            skl[k][l] = v.divide_scalar(w);
+        }
+    }
+
+    skl
+}
+
+// the surface point in cartesian coordinates, A4.3
+pub fn surface_point(p: usize,
+                     q: usize,
+                     knots1: &[f32],
+                     knots2: &[f32],
+                     control_points: &[Vec<Vector4>],
+                     u: f32,
+                     v: f32)
+                     -> Vector3 {
+    let uspan = find_span(p, u, knots1);
+    let vspan = find_span(q, v, knots2);
+    let nu = basis_functions(uspan, u, p, knots1);
+    let nv = basis_functions(vspan, v, q, knots2);
+
+    let sw = (0..q + 1).fold(Vector4::ZERO, |sw, l| {
+        let temp = (0..p + 1).fold(Vector4::ZERO, |t, k| {
+            let point = &control_points[uspan - p + k][vspan - q + l];
+            t.add(&homogeneous(point).multiply_scalar(nu[k]))
+        });
+        sw.add(&temp.multiply_scalar(nv[l]))
+    });
+
+    sw.xyz().divide_scalar(sw.w)
+}
+
+#[cfg(test)]
+mod tests {
+    use extras::nurbs_utils::{basis_function_derivatives, basis_functions, binomial, find_span};
+
+    // the example from the NURBS book, degree 2 with a double interior knot
+    const KNOTS: [f32; 11] = [0.0, 0.0, 0.0, 1.0, 2.0, 3.0, 4.0, 4.0, 5.0, 5.0, 5.0];
+
+    #[test]
+    fn basis() {
+        assert_eq!(find_span(2, 2.5, &KNOTS), 4);
+        assert_eq!(find_span(2, 0.0, &KNOTS), 2);
+        assert_eq!(find_span(2, 5.0, &KNOTS), 7);
+
+        let n = basis_functions(4, 2.5, 2, &KNOTS);
+        assert_eq!(n, vec![0.125, 0.75, 0.125]);
+
+        let ders = basis_function_derivatives(4, 2.5, 2, 2, &KNOTS);
+        assert_eq!(ders[0], n);
+        assert_eq!(ders[1], vec![-0.5, 0.0, 0.5]);
+        assert_eq!(ders[2], vec![1.0, -2.0, 1.0]);
+
+        assert_eq!(binomial(4, 2), 6.0);
+        assert_eq!(binomial(3, 0), 1.0);
+    }
+}
@@ -0,0 +1,279 @@
+use extras::{curve_path, ArcLengths, CubicBezierCurve2, Curve, EllipseCurve, LineCurve2,
+             QuadraticBezierCurve2};
+use math::Vector2;
+
+// the kinds of curves a path is drawn with
+#[derive(Debug,PartialEq,Clone)]
+pub enum PathSegment {
+    Line(LineCurve2),
+    QuadraticBezier(QuadraticBezierCurve2),
+    CubicBezier(CubicBezierCurve2),
+    Ellipse(EllipseCurve),
+}
+
+impl PathSegment {
+    fn curve(&self) -> &Curve<Vector = Vector2> {
+        match *self {
+            PathSegment::Line(ref c) => c,
+            PathSegment::QuadraticBezier(ref c) => c,
+            PathSegment::CubicBezier(ref c) => c,
+            PathSegment::Ellipse(ref c) => c,
+        }
+    }
+}
+
+impl Curve for PathSegment {
+    type Vector = Vector2;
+
+    fn point_at(&self, t: f32) -> Vector2 {
+        self.curve().point_at(t)
+    }
+
+    fn arc_lengths(&self) -> &ArcLengths {
+        self.curve().arc_lengths()
+    }
+
+    fn tangent_at(&self, t: f32) -> Vector2 {
+        self.curve().tangent_at(t)
+    }
+
+    fn resolution(&self, divisions: usize) -> usize {
+        self.curve().resolution(divisions)
+    }
+
+    fn spaced_point_at(&self, u: f32) -> Vector2 {
+        self.curve().spaced_point_at(u)
+    }
+
+    fn spaced_tangent_at(&self, u: f32) -> Vector2 {
+        self.curve().spaced_tangent_at(u)
+    }
+}
+
+#[derive(Debug,PartialEq,Clone)]
+pub struct Path {
+    pub segments: Vec<PathSegment>,
+    pub current_point: Vector2,
+    pub auto_close: bool,
+    arc_lengths: ArcLengths,
+}
+
+impl Path {
+    pub fn new() -> Path {
+        Path {
+            segments: vec![],
+            current_point: Vector2::ZERO,
+            auto_close: false,
+            arc_lengths: ArcLengths::new(),
+        }
+    }
+
+    pub fn from_points(points: &[Vector2]) -> Path {
+        let mut path = Path::new();
+
+        if let Some((first, rest)) = points.split_first() {
+            path.move_to(first);
+            for point in rest {
+                path.line_to(point);
+            }
+        }
+
+        path
+    }
+
+    fn push(&mut self, segment: PathSegment) {
+        self.current_point = segment.point_at(1.0);
+        self.segments.push(segment);
+        self.arc_lengths.clear();
+    }
+
+    pub fn move_to(&mut self, point: &Vector2) {
+        self.current_point = *point;
+    }
+
+    pub fn line_to(&mut self, point: &Vector2) {
+        let line = LineCurve2::new(&self.current_point, point);
+        self.push(PathSegment::Line(line));
+    }
+
+    pub fn quadratic_curve_to(&mut self, control: &Vector2, point: &Vector2) {
+        let curve = QuadraticBezierCurve2::new(&self.current_point, control, point);
+        self.push(PathSegment::QuadraticBezier(curve));
+    }
+
+    pub fn bezier_curve_to(&mut self, control1: &Vector2, control2: &Vector2, point: &Vector2) {
+        let curve = CubicBezierCurve2::new(&self.current_point, control1, control2, point);
+        self.push(PathSegment::CubicBezier(curve));
+    }
+
+    // center is relative to the current point
+    pub fn arc(&mut self,
+               center: &Vector2,
+               radius: f32,
+               start_angle: f32,
+               end_angle: f32,
+               clockwise: bool) {
+        let center = center.add(&self.current_point);
+        self.absarc(&center, radius, start_angle, end_angle, clockwise);
+    }
+
+    pub fn absarc(&mut self,
+                  center: &Vector2,
+                  radius: f32,
+                  start_angle: f32,
+                  end_angle: f32,
+                  clockwise: bool) {
+        self.absellipse(center, radius, radius, start_angle, end_angle, clockwise, 0.0);
+    }
+
+    // center is relative to the current point
+    pub fn ellipse(&mut self,
+                   center: &Vector2,
+                   x_radius: f32,
+                   y_radius: f32,
+                   start_angle: f32,
+                   end_angle: f32,
+                   clockwise: bool,
+                   rotation: f32) {
+        let center = center.add(&self.current_point);
+        self.absellipse(&center,
+                        x_radius,
+                        y_radius,
+                        start_angle,
+                        end_angle,
+                        clockwise,
+                        rotation);
+    }
+
+    // connects the current point to the start of the ellipse with a line if they differ
+    pub fn absellipse(&mut self,
+                      center: &Vector2,
+                      x_radius: f32,
+                      y_radius: f32,
+                      start_angle: f32,
+                      end_angle: f32,
+                      clockwise: bool,
+                      rotation: f32) {
+        let curve = EllipseCurve::new(center,
+                                      x_radius,
+                                      y_radius,
+                                      start_angle,
+                                      end_angle,
+                                      clockwise,
+                                      rotation);
+
+        if !self.segments.is_empty() {
+            let first_point = curve.point_at(0.0);
+            if first_point != self.current_point {
+                self.line_to(&first_point);
+            }
+        }
+
+        self.push(PathSegment::Ellipse(curve));
+    }
+}
+
+// t runs along the whole path by arc length
+impl Curve for Path {
+    type Vector = Vector2;
+
+    fn point_at(&self, t: f32) -> Vector2 {
+        curve_path::point_at(&self.segments, t)
+    }
+
+    fn arc_lengths(&self) -> &ArcLengths {
+        &self.arc_lengths
+    }
+
+    // samples every segment at its own resolution, consecutive duplicates (like the joints
+    // between segments) are only returned once
+    fn points(&self, divisions: usize) -> Vec<Vector2> {
+        curve_path::points(&self.segments, divisions, self.auto_close)
+    }
+
+    fn spaced_points(&self, divisions: usize) -> Vec<Vector2> {
+        let divisions = divisions.max(1);
+        let points = (0..divisions + 1).map(|d| self.point_at(d as f32 / divisions as f32));
+        curve_path::close(points.collect(), self.auto_close)
+    }
+
+    fn length(&self) -> f32 {
+        curve_path::curve_lengths(&self.segments).last().cloned().unwrap_or(0.0)
+    }
+
+    fn update_arc_lengths(&self) {
+        self.arc_lengths.clear();
+        for segment in &self.segments {
+            segment.update_arc_lengths();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+    use extras::{Curve, Path, PathSegment};
+    use math::Vector2;
+
+    #[test]
+    fn points() {
+        let mut a = Path::from_points(&[Vector2::ZERO, Vector2::new(1.0, 0.0)]);
+        a.quadratic_curve_to(&Vector2::new(1.0, 1.0), &Vector2::new(0.0, 1.0));
+        assert_eq!(a.segments.len(), 2);
+        assert_eq!(a.current_point, Vector2::new(0.0, 1.0));
+
+        let points = a.points(4);
+        assert_eq!(points.len(), 2 + 4);
+        assert_eq!(points[3], Vector2::new(0.75, 0.75));
+
+        a.auto_close = true;
+        assert_eq!(a.points(4).len(), 2 + 4 + 1);
+        assert_eq!(a.points(4).last(), Some(&Vector2::ZERO));
+    }
+
+    #[test]
+    fn empty() {
+        let a = Path::new();
+        assert_eq!(a.point_at(0.0), Vector2::ZERO);
+        assert_eq!(a.point_at(1.0), Vector2::ZERO);
+        assert_eq!(a.length(), 0.0);
+    }
+
+    #[test]
+    fn absarc() {
+        let mut a = Path::new();
+        a.move_to(&Vector2::new(2.0, 0.0));
+        a.absarc(&Vector2::ZERO, 1.0, 0.0, PI, false);
+        // nothing to connect the first segment to
+        assert_eq!(a.segments.len(), 1);
+        assert!(a.current_point.distance_to(&Vector2::new(-1.0, 0.0)) < 1e-6);
+
+        a.absarc(&Vector2::ZERO, 2.0, PI, 0.0, true);
+        assert_eq!(a.segments.len(), 3);
+        match a.segments[1] {
+            PathSegment::Line(ref line) => {
+                assert!(line.v2.distance_to(&Vector2::new(-2.0, 0.0)) < 1e-6)
+            }
+            _ => panic!("expected a line"),
+        }
+
+        // clockwise from PI to 0 passes through the top
+        assert!(a.segments[2].point_at(0.5).distance_to(&Vector2::new(0.0, 2.0)) < 1e-6);
+        assert_eq!(a.points(2).len(), 5 + 5);
+    }
+
+    #[test]
+    fn spaced_points() {
+        let mut a = Path::from_points(&[Vector2::ZERO,
+                                        Vector2::new(3.0, 0.0),
+                                        Vector2::new(3.0, 1.0)]);
+        assert!((a.length() - 4.0).abs() < 1e-4);
+        assert!(a.point_at(0.5).distance_to(&Vector2::new(2.0, 0.0)) < 1e-4);
+
+        a.auto_close = true;
+        let points = a.spaced_points(4);
+        assert_eq!(points.len(), 6);
+        assert!(points[3].distance_to(&Vector2::new(3.0, 0.0)) < 1e-4);
+        assert_eq!(points[5], Vector2::ZERO);
+    }
+}
@@ -0,0 +1,40 @@
+use extras::{ArcLengths, Curve, CurveVector};
+use math::{Vector2, Vector3};
+
+#[derive(Debug,PartialEq,Clone)]
+pub struct QuadraticBezierCurve<V: CurveVector> {
+    pub v0: V,
+    pub v1: V,
+    pub v2: V,
+    arc_lengths: ArcLengths,
+}
+
+pub type QuadraticBezierCurve2 = QuadraticBezierCurve<Vector2>;
+pub type QuadraticBezierCurve3 = QuadraticBezierCurve<Vector3>;
+
+impl<V: CurveVector> QuadraticBezierCurve<V> {
+    pub fn new(v0: &V, v1: &V, v2: &V) -> QuadraticBezierCurve<V> {
+        QuadraticBezierCurve {
+            v0: *v0,
+            v1: *v1,
+            v2: *v2,
+            arc_lengths: ArcLengths::new(),
+        }
+    }
+}
+
+impl<V: CurveVector> Curve for QuadraticBezierCurve<V> {
+    type Vector = V;
+
+    fn point_at(&self, t: f32) -> V {
+        let k = 1.0 - t;
+        self.v0
+            .multiply_scalar(k * k)
+            .add(&self.v1.multiply_scalar(2.0 * k * t))
+            .add(&self.v2.multiply_scalar(t * t))
+    }
+
+    fn arc_lengths(&self) -> &ArcLengths {
+        &self.arc_lengths
+    }
+}
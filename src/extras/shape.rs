@@ -0,0 +1,31 @@
+use extras::{Curve, Path};
+use math::Vector2;
+
+#[derive(Debug,PartialEq,Clone)]
+pub struct Shape {
+    pub contour: Path,
+    pub holes: Vec<Path>,
+}
+
+impl Shape {
+    pub fn new(contour: Path) -> Shape {
+        Shape {
+            contour: contour,
+            holes: vec![],
+        }
+    }
+
+    pub fn from_points(points: &[Vector2]) -> Shape {
+        Shape::new(Path::from_points(points))
+    }
+
+    pub fn add_hole(&mut self, hole: Path) {
+        self.holes.push(hole);
+    }
+
+    // contour points first, then the points of every hole
+    pub fn extract_points(&self, divisions: usize) -> (Vec<Vector2>, Vec<Vec<Vector2>>) {
+        let holes = self.holes.iter().map(|hole| hole.points(divisions)).collect();
+        (self.contour.points(divisions), holes)
+    }
+}
@@ -0,0 +1,158 @@
+use extras::earcut;
+use math::Vector2;
+
+pub struct ShapeUtils;
+
+impl ShapeUtils {
+    // signed area, positive for counterclockwise contours
+    pub fn area(contour: &[Vector2]) -> f32 {
+        if contour.is_empty() {
+            return 0.0;
+        }
+
+        let mut a = 0.0;
+        let mut p = contour.len() - 1;
+
+        for q in 0..contour.len() {
+            a += contour[p].x * contour[q].y - contour[q].x * contour[p].y;
+            p = q;
+        }
+
+        a * 0.5
+    }
+
+    pub fn is_clockwise(points: &[Vector2]) -> bool {
+        ShapeUtils::area(points) < 0.0
+    }
+
+    // closed paths repeat their first point at the end
+    pub fn remove_closing_point(points: &mut Vec<Vector2>) {
+        let len = points.len();
+        if len > 2 && points[len - 1] == points[0] {
+            points.pop();
+        }
+    }
+
+    // works with either winding for the contour and the holes. the face indices refer to
+    // the contour points followed by the points of each hole in order
+    pub fn triangulate(contour: &[Vector2], holes: &[Vec<Vector2>]) -> Vec<[usize; 3]> {
+        let mut vertices = contour.to_vec();
+        let mut hole_indices = vec![];
+
+        for hole in holes {
+            hole_indices.push(vertices.len());
+            vertices.extend_from_slice(hole);
+        }
+
+        earcut::triangulate(&vertices, &hole_indices)
+            .chunks(3)
+            .map(|face| [face[0], face[1], face[2]])
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+    use extras::ShapeUtils;
+    use math::Vector2;
+
+    fn square(size: f32, offset: f32) -> Vec<Vector2> {
+        vec![Vector2::new(offset, offset),
+             Vector2::new(offset + size, offset),
+             Vector2::new(offset + size, offset + size),
+             Vector2::new(offset, offset + size)]
+    }
+
+    fn circle(radius: f32, segments: usize) -> Vec<Vector2> {
+        (0..segments)
+            .map(|i| {
+                let angle = i as f32 / segments as f32 * PI * 2.0;
+                Vector2::new(radius * angle.cos(), radius * angle.sin())
+            })
+            .collect()
+    }
+
+    fn triangulated_area(points: &[Vector2], faces: &[[usize; 3]]) -> f32 {
+        faces.iter()
+            .map(|f| ShapeUtils::area(&[points[f[0]], points[f[1]], points[f[2]]]).abs())
+            .sum()
+    }
+
+    #[test]
+    fn area() {
+        let a = square(2.0, 0.0);
+        assert_eq!(ShapeUtils::area(&a), 4.0);
+        assert!(!ShapeUtils::is_clockwise(&a));
+
+        let mut b = a.clone();
+        b.reverse();
+        assert_eq!(ShapeUtils::area(&b), -4.0);
+        assert!(ShapeUtils::is_clockwise(&b));
+    }
+
+    #[test]
+    fn triangulate() {
+        let a = square(1.0, 0.0);
+        assert_eq!(ShapeUtils::triangulate(&a, &[]).len(), 2);
+
+        let mut b = a.clone();
+        b.reverse();
+        let faces = ShapeUtils::triangulate(&b, &[]);
+        assert_eq!(faces.len(), 2);
+        assert_eq!(triangulated_area(&b, &faces), 1.0);
+
+        // the closing point of a closed path is skipped
+        let mut c = a.clone();
+        c.push(a[0]);
+        let faces = ShapeUtils::triangulate(&c, &[]);
+        assert_eq!(faces.len(), 2);
+        assert!(faces.iter().all(|f| f.iter().all(|&i| i < 4)));
+    }
+
+    #[test]
+    fn collinear() {
+        let a = vec![Vector2::new(0.0, 0.0),
+                     Vector2::new(1.0, 0.0),
+                     Vector2::new(2.0, 0.0),
+                     Vector2::new(2.0, 1.0),
+                     Vector2::new(1.0, 1.0),
+                     Vector2::new(0.0, 1.0)];
+        let faces = ShapeUtils::triangulate(&a, &[]);
+        assert_eq!(triangulated_area(&a, &faces), 2.0);
+        assert!(faces.iter().all(|f| ShapeUtils::area(&[a[f[0]], a[f[1]], a[f[2]]]) != 0.0));
+    }
+
+    #[test]
+    fn holes() {
+        let a = square(4.0, 0.0);
+        let mut hole = square(2.0, 1.0);
+        // same winding as the contour on purpose
+        let faces = ShapeUtils::triangulate(&a, &[hole.clone()]);
+        let points: Vec<Vector2> = a.iter().chain(hole.iter()).cloned().collect();
+        assert_eq!(faces.len(), 8);
+        assert_eq!(triangulated_area(&points, &faces), 12.0);
+
+        hole.reverse();
+        let faces = ShapeUtils::triangulate(&a, &[hole.clone()]);
+        assert_eq!(faces.len(), 8);
+
+        // large enough to go through the z-order hashed path
+        let b = circle(10.0, 100);
+        let holes = vec![circle(2.0, 30)
+                             .iter()
+                             .map(|p| p.add(&Vector2::new(4.0, 0.0)))
+                             .collect(),
+                         circle(2.0, 30)
+                             .iter()
+                             .map(|p| p.subtract(&Vector2::new(4.0, 0.0)))
+                             .collect::<Vec<_>>()];
+        let faces = ShapeUtils::triangulate(&b, &holes);
+        let points: Vec<Vector2> =
+            b.iter().chain(holes.iter().flat_map(|h| h.iter())).cloned().collect();
+        assert_eq!(faces.len(), 100 + 2 * 30 + 2 * 2 - 2);
+
+        let expected = ShapeUtils::area(&b) - 2.0 * ShapeUtils::area(&holes[0]);
+        assert!((triangulated_area(&points, &faces) - expected).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,161 @@
+use core::BufferGeometry;
+use geometries::assemble;
+
+#[derive(Debug,PartialEq,Copy,Clone)]
+pub struct BoxGeometry {
+    pub width: f32,
+    pub height: f32,
+    pub depth: f32,
+    pub width_segments: usize,
+    pub height_segments: usize,
+    pub depth_segments: usize,
+}
+
+struct Builder {
+    indices: Vec<usize>,
+    vertices: Vec<f32>,
+    normals: Vec<f32>,
+    uvs: Vec<f32>,
+    groups: Vec<(usize, usize, usize)>,
+}
+
+impl BoxGeometry {
+    pub const DEFAULT: BoxGeometry = BoxGeometry {
+        width: 1.0,
+        height: 1.0,
+        depth: 1.0,
+        width_segments: 1,
+        height_segments: 1,
+        depth_segments: 1,
+    };
+
+    pub fn build(&self) -> BufferGeometry {
+        let mut builder = Builder {
+            indices: vec![],
+            vertices: vec![],
+            normals: vec![],
+            uvs: vec![],
+            groups: vec![],
+        };
+
+        let (w, h, d) = (self.width, self.height, self.depth);
+        let (ws, hs, ds) = (self.width_segments.max(1),
+                            self.height_segments.max(1),
+                            self.depth_segments.max(1));
+
+        // same face order as three.js so material indices match: px, nx, py, ny, pz, nz
+        builder.plane((2, 1, 0), -1.0, -1.0, d, h, w, ds, hs, 0);
+        builder.plane((2, 1, 0), 1.0, -1.0, d, h, -w, ds, hs, 1);
+        builder.plane((0, 2, 1), 1.0, 1.0, w, d, h, ws, ds, 2);
+        builder.plane((0, 2, 1), 1.0, -1.0, w, d, -h, ws, ds, 3);
+        builder.plane((0, 1, 2), 1.0, -1.0, w, h, d, ws, hs, 4);
+        builder.plane((0, 1, 2), -1.0, -1.0, w, h, -d, ws, hs, 5);
+
+        let mut geometry = assemble(Some(&builder.indices),
+                                    builder.vertices,
+                                    builder.normals,
+                                    builder.uvs);
+        for (start, count, material_index) in builder.groups {
+            geometry.add_group(start, count, material_index);
+        }
+        geometry
+    }
+}
+
+impl Builder {
+    // axes are the (u, v, w) component indices of the face
+    fn plane(&mut self,
+             axes: (usize, usize, usize),
+             u_dir: f32,
+             v_dir: f32,
+             width: f32,
+             height: f32,
+             depth: f32,
+             grid_x: usize,
+             grid_y: usize,
+             material_index: usize) {
+        let segment_width = width / grid_x as f32;
+        let segment_height = height / grid_y as f32;
+        let width_half = width / 2.0;
+        let height_half = height / 2.0;
+        let depth_half = depth / 2.0;
+        let grid_x1 = grid_x + 1;
+        let grid_y1 = grid_y + 1;
+
+        let vertex_offset = self.vertices.len() / 3;
+        let group_start = self.indices.len();
+
+        for iy in 0..grid_y1 {
+            let y = iy as f32 * segment_height - height_half;
+
+            for ix in 0..grid_x1 {
+                let x = ix as f32 * segment_width - width_half;
+
+                let mut vertex = [0.0; 3];
+                vertex[axes.0] = x * u_dir;
+                vertex[axes.1] = y * v_dir;
+                vertex[axes.2] = depth_half;
+                self.vertices.extend_from_slice(&vertex);
+
+                let mut normal = [0.0; 3];
+                normal[axes.2] = if depth > 0.0 { 1.0 } else { -1.0 };
+                self.normals.extend_from_slice(&normal);
+
+                self.uvs.push(ix as f32 / grid_x as f32);
+                self.uvs.push(1.0 - (iy as f32 / grid_y as f32));
+            }
+        }
+
+        for iy in 0..grid_y {
+            for ix in 0..grid_x {
+                let a = vertex_offset + ix + grid_x1 * iy;
+                let b = vertex_offset + ix + grid_x1 * (iy + 1);
+                let c = vertex_offset + (ix + 1) + grid_x1 * (iy + 1);
+                let d = vertex_offset + (ix + 1) + grid_x1 * iy;
+
+                self.indices.extend_from_slice(&[a, b, d, b, c, d]);
+            }
+        }
+
+        self.groups.push((group_start, self.indices.len() - group_start, material_index));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::{AttributeName, VertexAttribute};
+    use geometries::BoxGeometry;
+    use math::{Box3, Vector3};
+
+    #[test]
+    fn build() {
+        let a = BoxGeometry::DEFAULT.build();
+        assert_eq!(a.vertex_count(), 24);
+        assert_eq!(a.index.as_ref().unwrap().len(), 36);
+        assert_eq!(a.groups.len(), 6);
+        assert_eq!(a.groups[5].start, 30);
+        assert_eq!(a.groups[5].material_index, 5);
+        assert_eq!(a.compute_bounding_box(),
+                   Box3::new(&Vector3::ONE.multiply_scalar(-0.5),
+                             &Vector3::ONE.multiply_scalar(0.5)));
+
+        // first vertex of the +x face
+        let position = a.attribute(&AttributeName::Position).unwrap();
+        let normal = a.attribute(&AttributeName::Normal).unwrap();
+        let uv = a.attribute(&AttributeName::Uv).unwrap();
+        assert_eq!(position.vector3(0), Vector3::new(0.5, 0.5, 0.5));
+        assert_eq!(normal.vector3(0), Vector3::X);
+        assert_eq!(uv.vector2(0).y, 1.0);
+
+        let b = BoxGeometry {
+            width_segments: 2,
+            height_segments: 3,
+            depth_segments: 4,
+            ..BoxGeometry::DEFAULT
+        };
+        let b = b.build();
+        // (ds+1)(hs+1) twice, (ws+1)(ds+1) twice, (ws+1)(hs+1) twice
+        assert_eq!(b.vertex_count(), 2 * (5 * 4) + 2 * (3 * 5) + 2 * (3 * 4));
+        assert_eq!(b.index.as_ref().unwrap().len(), 6 * 2 * (4 * 3 + 2 * 4 + 2 * 3));
+    }
+}
@@ -0,0 +1,73 @@
+use std::f32::consts::PI;
+use core::BufferGeometry;
+use geometries::LatheGeometry;
+use math::Vector2;
+
+#[derive(Debug,PartialEq,Copy,Clone)]
+pub struct CapsuleGeometry {
+    pub radius: f32,
+    pub length: f32,
+    pub cap_segments: usize,
+    pub radial_segments: usize,
+}
+
+impl CapsuleGeometry {
+    pub const DEFAULT: CapsuleGeometry = CapsuleGeometry {
+        radius: 1.0,
+        length: 1.0,
+        cap_segments: 4,
+        radial_segments: 8,
+    };
+
+    pub fn build(&self) -> BufferGeometry {
+        let cap_segments = self.cap_segments.max(1);
+        let half_length = self.length / 2.0;
+
+        // the same profile three.js gets from two quarter arcs joined by a straight line,
+        // each arc sampled with twice the cap segments
+        let divisions = cap_segments * 2;
+        let mut points = vec![];
+        for i in 0..divisions + 1 {
+            let angle = PI * 1.5 + i as f32 / divisions as f32 * PI * 0.5;
+            points.push(Vector2::new(self.radius * angle.cos(),
+                                     -half_length + self.radius * angle.sin()));
+        }
+        for i in 0..divisions + 1 {
+            let angle = i as f32 / divisions as f32 * PI * 0.5;
+            points.push(Vector2::new(self.radius * angle.cos(),
+                                     half_length + self.radius * angle.sin()));
+        }
+
+        LatheGeometry::new(&points, self.radial_segments, 0.0, PI * 2.0).build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::{AttributeName, VertexAttribute};
+    use geometries::CapsuleGeometry;
+    use math::{clamp, Box3, Vector3};
+
+    #[test]
+    fn build() {
+        let a = CapsuleGeometry::DEFAULT.build();
+        // 4 * cap_segments + 2 profile points per meridian
+        assert_eq!(a.vertex_count(), 9 * 18);
+        assert_eq!(a.index.as_ref().unwrap().len(), 8 * 17 * 6);
+
+        let bounds = a.compute_bounding_box();
+        let expected = Box3::new(&Vector3::new(-1.0, -1.5, -1.0), &Vector3::new(1.0, 1.5, 1.0));
+        assert!(bounds.min.distance_to(&expected.min) < 1e-5);
+        assert!(bounds.max.distance_to(&expected.max) < 1e-5);
+
+        // every normal is unit length and points away from the capsule's axis segment
+        let position = a.attribute(&AttributeName::Position).unwrap();
+        let normal = a.attribute(&AttributeName::Normal).unwrap();
+        for i in 0..normal.count() {
+            let p = position.vector3(i);
+            let center = Vector3::new(0.0, clamp(p.y, -0.5, 0.5), 0.0);
+            assert!((normal.vector3(i).length() - 1.0).abs() < 1e-5);
+            assert!(normal.vector3(i).dot(&p.subtract(&center)) > 0.0);
+        }
+    }
+}
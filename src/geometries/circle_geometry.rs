@@ -0,0 +1,46 @@
+use std::f32::consts::PI;
+use core::BufferGeometry;
+use geometries::assemble;
+
+#[derive(Debug,PartialEq,Copy,Clone)]
+pub struct CircleGeometry {
+    pub radius: f32,
+    pub segments: usize,
+    pub theta_start: f32,
+    pub theta_length: f32,
+}
+
+impl CircleGeometry {
+    pub const DEFAULT: CircleGeometry = CircleGeometry {
+        radius: 1.0,
+        segments: 32,
+        theta_start: 0.0,
+        theta_length: PI * 2.0,
+    };
+
+    pub fn build(&self) -> BufferGeometry {
+        let segments = self.segments.max(3);
+
+        let mut indices = vec![];
+        let mut vertices = vec![0.0, 0.0, 0.0];
+        let mut normals = vec![0.0, 0.0, 1.0];
+        let mut uvs = vec![0.5, 0.5];
+
+        for s in 0..segments + 1 {
+            let segment = self.theta_start + s as f32 / segments as f32 * self.theta_length;
+            let x = self.radius * segment.cos();
+            let y = self.radius * segment.sin();
+
+            vertices.extend_from_slice(&[x, y, 0.0]);
+            normals.extend_from_slice(&[0.0, 0.0, 1.0]);
+            uvs.push((x / self.radius + 1.0) / 2.0);
+            uvs.push((y / self.radius + 1.0) / 2.0);
+        }
+
+        for i in 1..segments + 1 {
+            indices.extend_from_slice(&[i, i + 1, 0]);
+        }
+
+        assemble(Some(&indices), vertices, normals, uvs)
+    }
+}
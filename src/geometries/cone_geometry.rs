@@ -0,0 +1,40 @@
+use std::f32::consts::PI;
+use core::BufferGeometry;
+use geometries::CylinderGeometry;
+
+#[derive(Debug,PartialEq,Copy,Clone)]
+pub struct ConeGeometry {
+    pub radius: f32,
+    pub height: f32,
+    pub radial_segments: usize,
+    pub height_segments: usize,
+    pub open_ended: bool,
+    pub theta_start: f32,
+    pub theta_length: f32,
+}
+
+impl ConeGeometry {
+    pub const DEFAULT: ConeGeometry = ConeGeometry {
+        radius: 1.0,
+        height: 1.0,
+        radial_segments: 32,
+        height_segments: 1,
+        open_ended: false,
+        theta_start: 0.0,
+        theta_length: PI * 2.0,
+    };
+
+    pub fn build(&self) -> BufferGeometry {
+        let cylinder = CylinderGeometry {
+            radius_top: 0.0,
+            radius_bottom: self.radius,
+            height: self.height,
+            radial_segments: self.radial_segments,
+            height_segments: self.height_segments,
+            open_ended: self.open_ended,
+            theta_start: self.theta_start,
+            theta_length: self.theta_length,
+        };
+        cylinder.build()
+    }
+}
@@ -0,0 +1,227 @@
+use std::f32::consts::PI;
+use core::BufferGeometry;
+use geometries::{assemble, normalized_or_zero};
+use math::Vector3;
+
+#[derive(Debug,PartialEq,Copy,Clone)]
+pub struct CylinderGeometry {
+    pub radius_top: f32,
+    pub radius_bottom: f32,
+    pub height: f32,
+    pub radial_segments: usize,
+    pub height_segments: usize,
+    pub open_ended: bool,
+    pub theta_start: f32,
+    pub theta_length: f32,
+}
+
+struct Builder<'a> {
+    parameters: &'a CylinderGeometry,
+    radial_segments: usize,
+    height_segments: usize,
+    indices: Vec<usize>,
+    vertices: Vec<f32>,
+    normals: Vec<f32>,
+    uvs: Vec<f32>,
+    groups: Vec<(usize, usize, usize)>,
+}
+
+impl CylinderGeometry {
+    pub const DEFAULT: CylinderGeometry = CylinderGeometry {
+        radius_top: 1.0,
+        radius_bottom: 1.0,
+        height: 1.0,
+        radial_segments: 32,
+        height_segments: 1,
+        open_ended: false,
+        theta_start: 0.0,
+        theta_length: PI * 2.0,
+    };
+
+    // groups are 0 for the torso, 1 for the top cap and 2 for the bottom cap
+    pub fn build(&self) -> BufferGeometry {
+        let mut builder = Builder {
+            parameters: self,
+            radial_segments: self.radial_segments.max(1),
+            height_segments: self.height_segments.max(1),
+            indices: vec![],
+            vertices: vec![],
+            normals: vec![],
+            uvs: vec![],
+            groups: vec![],
+        };
+
+        builder.torso();
+        if !self.open_ended {
+            if self.radius_top > 0.0 {
+                builder.cap(true);
+            }
+            if self.radius_bottom > 0.0 {
+                builder.cap(false);
+            }
+        }
+
+        let mut geometry = assemble(Some(&builder.indices),
+                                    builder.vertices,
+                                    builder.normals,
+                                    builder.uvs);
+        for (start, count, material_index) in builder.groups {
+            geometry.add_group(start, count, material_index);
+        }
+        geometry
+    }
+}
+
+impl<'a> Builder<'a> {
+    fn torso(&mut self) {
+        let p = self.parameters;
+        let group_start = self.indices.len();
+        let half_height = p.height / 2.0;
+
+        // the normals of a cone lean along the slope
+        let slope = (p.radius_bottom - p.radius_top) / p.height;
+
+        let mut index_array = vec![];
+        let mut index = self.vertices.len() / 3;
+
+        for y in 0..self.height_segments + 1 {
+            let mut index_row = vec![];
+            let v = y as f32 / self.height_segments as f32;
+            let radius = v * (p.radius_bottom - p.radius_top) + p.radius_top;
+
+            for x in 0..self.radial_segments + 1 {
+                let u = x as f32 / self.radial_segments as f32;
+                let theta = u * p.theta_length + p.theta_start;
+                let sin_theta = theta.sin();
+                let cos_theta = theta.cos();
+
+                self.vertices.extend_from_slice(&[radius * sin_theta,
+                                                  -v * p.height + half_height,
+                                                  radius * cos_theta]);
+                let normal = Vector3::new(sin_theta, slope, cos_theta);
+                self.normals.extend(normalized_or_zero(&normal).to_vec());
+                self.uvs.push(u);
+                self.uvs.push(1.0 - v);
+
+                index_row.push(index);
+                index += 1;
+            }
+
+            index_array.push(index_row);
+        }
+
+        for x in 0..self.radial_segments {
+            for y in 0..self.height_segments {
+                let a = index_array[y][x];
+                let b = index_array[y + 1][x];
+                let c = index_array[y + 1][x + 1];
+                let d = index_array[y][x + 1];
+
+                // skip the degenerate triangles at the tip of a cone
+                if p.radius_top > 0.0 || y != 0 {
+                    self.indices.extend_from_slice(&[a, b, d]);
+                }
+                if p.radius_bottom > 0.0 || y != self.height_segments - 1 {
+                    self.indices.extend_from_slice(&[b, c, d]);
+                }
+            }
+        }
+
+        self.groups.push((group_start, self.indices.len() - group_start, 0));
+    }
+
+    fn cap(&mut self, top: bool) {
+        let p = self.parameters;
+        let group_start = self.indices.len();
+        let half_height = p.height / 2.0;
+
+        let radius = if top { p.radius_top } else { p.radius_bottom };
+        let sign = if top { 1.0 } else { -1.0 };
+
+        // one center vertex per segment so every face gets its own uv
+        let center_index_start = self.vertices.len() / 3;
+        for _ in 0..self.radial_segments {
+            self.vertices.extend_from_slice(&[0.0, half_height * sign, 0.0]);
+            self.normals.extend_from_slice(&[0.0, sign, 0.0]);
+            self.uvs.extend_from_slice(&[0.5, 0.5]);
+        }
+        let center_index_end = self.vertices.len() / 3;
+
+        for x in 0..self.radial_segments + 1 {
+            let u = x as f32 / self.radial_segments as f32;
+            let theta = u * p.theta_length + p.theta_start;
+            let cos_theta = theta.cos();
+            let sin_theta = theta.sin();
+
+            self.vertices.extend_from_slice(&[radius * sin_theta,
+                                              half_height * sign,
+                                              radius * cos_theta]);
+            self.normals.extend_from_slice(&[0.0, sign, 0.0]);
+            self.uvs.push((cos_theta * 0.5) + 0.5);
+            self.uvs.push((sin_theta * 0.5 * sign) + 0.5);
+        }
+
+        for x in 0..self.radial_segments {
+            let c = center_index_start + x;
+            let i = center_index_end + x;
+
+            if top {
+                self.indices.extend_from_slice(&[i, i + 1, c]);
+            } else {
+                self.indices.extend_from_slice(&[i + 1, i, c]);
+            }
+        }
+
+        let material_index = if top { 1 } else { 2 };
+        self.groups.push((group_start, self.indices.len() - group_start, material_index));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::{AttributeName, VertexAttribute};
+    use geometries::{ConeGeometry, CylinderGeometry};
+    use math::Vector3;
+
+    #[test]
+    fn build() {
+        let a = CylinderGeometry {
+            radial_segments: 8,
+            height_segments: 2,
+            ..CylinderGeometry::DEFAULT
+        };
+        let a = a.build();
+        // torso grid plus a center fan and a rim for each cap
+        assert_eq!(a.vertex_count(), 9 * 3 + 2 * (8 + 9));
+        assert_eq!(a.groups.len(), 3);
+        assert_eq!(a.groups[0].count, 8 * 2 * 6);
+        assert_eq!(a.groups[1].count, 8 * 3);
+        assert_eq!(a.groups[2].material_index, 2);
+
+        let normal = a.attribute(&AttributeName::Normal).unwrap();
+        assert_eq!(normal.vector3(0), Vector3::Z);
+        assert_eq!(normal.vector3(a.vertex_count() - 1), Vector3::NEG_Y);
+
+        let open = CylinderGeometry {
+            open_ended: true,
+            ..CylinderGeometry::DEFAULT
+        };
+        assert_eq!(open.build().groups.len(), 1);
+    }
+
+    #[test]
+    fn cone() {
+        let a = ConeGeometry {
+            radial_segments: 8,
+            ..ConeGeometry::DEFAULT
+        };
+        let a = a.build();
+        // no top cap and no triangles collapsed into the tip
+        assert_eq!(a.groups.len(), 2);
+        assert_eq!(a.groups[0].count, 8 * 3);
+
+        let normal = a.attribute(&AttributeName::Normal).unwrap();
+        let expected = Vector3::new(0.0, 1.0, 1.0).normalized();
+        assert!(normal.vector3(0).subtract(&expected).length() < 1e-6);
+    }
+}
@@ -0,0 +1,41 @@
+use core::BufferGeometry;
+use geometries::PolyhedronGeometry;
+
+// golden ratio and its inverse
+const T: f32 = 1.618034;
+const R: f32 = 0.618034;
+
+const VERTICES: [f32; 60] = [// (±1, ±1, ±1)
+                             -1.0, -1.0, -1.0, -1.0, -1.0, 1.0, -1.0, 1.0, -1.0, -1.0, 1.0,
+                             1.0, 1.0, -1.0, -1.0, 1.0, -1.0, 1.0, 1.0, 1.0, -1.0, 1.0, 1.0,
+                             1.0,
+                             // (0, ±1/φ, ±φ)
+                             0.0, -R, -T, 0.0, -R, T, 0.0, R, -T, 0.0, R, T,
+                             // (±1/φ, ±φ, 0)
+                             -R, -T, 0.0, -R, T, 0.0, R, -T, 0.0, R, T, 0.0,
+                             // (±φ, 0, ±1/φ)
+                             -T, 0.0, -R, T, 0.0, -R, -T, 0.0, R, T, 0.0, R];
+
+const INDICES: [usize; 108] = [3, 11, 7, 3, 7, 15, 3, 15, 13, 7, 19, 17, 7, 17, 6, 7, 6, 15,
+                               17, 4, 8, 17, 8, 10, 17, 10, 6, 8, 0, 16, 8, 16, 2, 8, 2, 10, 0,
+                               12, 1, 0, 1, 18, 0, 18, 16, 6, 10, 2, 6, 2, 13, 6, 13, 15, 2, 16,
+                               18, 2, 18, 3, 2, 3, 13, 18, 1, 9, 18, 9, 11, 18, 11, 3, 4, 14,
+                               12, 4, 12, 0, 4, 0, 8, 11, 9, 5, 11, 5, 19, 11, 19, 7, 19, 5, 14,
+                               19, 14, 4, 19, 4, 17, 1, 12, 14, 1, 14, 5, 1, 5, 9];
+
+#[derive(Debug,PartialEq,Copy,Clone)]
+pub struct DodecahedronGeometry {
+    pub radius: f32,
+    pub detail: usize,
+}
+
+impl DodecahedronGeometry {
+    pub const DEFAULT: DodecahedronGeometry = DodecahedronGeometry {
+        radius: 1.0,
+        detail: 0,
+    };
+
+    pub fn build(&self) -> BufferGeometry {
+        PolyhedronGeometry::new(&VERTICES, &INDICES, self.radius, self.detail).build()
+    }
+}
@@ -0,0 +1,373 @@
+use std::f32::EPSILON;
+use std::f32::consts::PI;
+use core::{AttributeName, BufferAttribute, BufferGeometry, NormalWeighting};
+use extras::{Curve, Shape, ShapeUtils};
+use math::{Spline, Vector2, Vector3};
+
+#[derive(Debug,PartialEq,Clone)]
+pub struct ExtrudeGeometry {
+    pub shapes: Vec<Shape>,
+    pub curve_segments: usize,
+    pub steps: usize,
+    pub depth: f32,
+    pub bevel_enabled: bool,
+    pub bevel_thickness: f32,
+    pub bevel_size: f32,
+    pub bevel_offset: f32,
+    pub bevel_segments: usize,
+    // bevels are not supported when extruding along a path
+    pub extrude_path: Option<Spline>,
+}
+
+struct Builder {
+    vertices: Vec<f32>,
+    uvs: Vec<f32>,
+    groups: Vec<(usize, usize, usize)>,
+}
+
+impl ExtrudeGeometry {
+    // same defaults as three.js
+    pub fn new(shapes: &[Shape]) -> ExtrudeGeometry {
+        ExtrudeGeometry {
+            shapes: shapes.to_vec(),
+            curve_segments: 12,
+            steps: 1,
+            depth: 1.0,
+            bevel_enabled: true,
+            bevel_thickness: 0.2,
+            bevel_size: 0.1,
+            bevel_offset: 0.0,
+            bevel_segments: 3,
+            extrude_path: None,
+        }
+    }
+
+    // non indexed, every shape adds a group 0 for the lids and a group 1 for the sides
+    pub fn build(&self) -> BufferGeometry {
+        let mut builder = Builder {
+            vertices: vec![],
+            uvs: vec![],
+            groups: vec![],
+        };
+
+        for shape in &self.shapes {
+            self.add_shape(shape, &mut builder);
+        }
+
+        let mut geometry = BufferGeometry::new();
+        geometry.set_attribute(AttributeName::Position,
+                               BufferAttribute::from_f32(builder.vertices, 3));
+        geometry.set_attribute(AttributeName::Uv, BufferAttribute::from_f32(builder.uvs, 2));
+        for (start, count, material_index) in builder.groups {
+            geometry.add_group(start, count, material_index);
+        }
+        geometry.compute_vertex_normals(NormalWeighting::Area);
+        geometry
+    }
+
+    fn add_shape(&self, shape: &Shape, builder: &mut Builder) {
+        let steps = self.steps.max(1);
+
+        let bevel_enabled = self.bevel_enabled && self.extrude_path.is_none();
+        let (bevel_segments, bevel_thickness, bevel_size, bevel_offset) = if bevel_enabled {
+            (self.bevel_segments, self.bevel_thickness, self.bevel_size, self.bevel_offset)
+        } else {
+            (0, 0.0, 0.0, 0.0)
+        };
+
+        let (mut contour, mut holes) = shape.extract_points(self.curve_segments);
+        ShapeUtils::remove_closing_point(&mut contour);
+
+        // the contour has to be clockwise and the holes counterclockwise
+        if !ShapeUtils::is_clockwise(&contour) {
+            contour.reverse();
+        }
+        for hole in &mut holes {
+            ShapeUtils::remove_closing_point(hole);
+            if ShapeUtils::is_clockwise(hole) {
+                hole.reverse();
+            }
+        }
+
+        let faces = ShapeUtils::triangulate(&contour, &holes);
+
+        // contour followed by the holes, with the direction every point moves in when
+        // bevelled
+        let mut vertices = vec![];
+        let mut movements = vec![];
+        for ring in Some(&contour).into_iter().chain(holes.iter()) {
+            vertices.extend_from_slice(ring);
+            movements.extend(bevel_vectors(ring));
+        }
+        let vlen = vertices.len();
+
+        let bevel_layer = |b: usize, z_offset: f32, z_sign: f32, placeholder: &mut Vec<Vector3>| {
+            let t = b as f32 / bevel_segments as f32;
+            let z = bevel_thickness * (t * PI / 2.0).cos();
+            let bs = bevel_size * (t * PI / 2.0).sin() + bevel_offset;
+
+            for (point, movement) in vertices.iter().zip(&movements) {
+                let vert = point.add(&movement.multiply_scalar(bs));
+                placeholder.push(Vector3::new(vert.x, vert.y, z_offset + z_sign * z));
+            }
+        };
+
+        let mut placeholder = vec![];
+
+        // back bevel, starting with the outermost layer
+        for b in 0..bevel_segments {
+            bevel_layer(b, 0.0, -1.0, &mut placeholder);
+        }
+
+        // the shape itself at every step, including the back and front layers
+        let path = self.extrude_path.as_ref().map(|path| {
+            (path.spaced_points(steps), path.compute_frenet_frames(steps, false))
+        });
+        let bs = bevel_size + bevel_offset;
+        for s in 0..steps + 1 {
+            for (point, movement) in vertices.iter().zip(&movements) {
+                let vert = if bevel_enabled {
+                    point.add(&movement.multiply_scalar(bs))
+                } else {
+                    *point
+                };
+
+                match path {
+                    None => {
+                        let z = self.depth / steps as f32 * s as f32;
+                        placeholder.push(Vector3::new(vert.x, vert.y, z))
+                    }
+                    Some((ref points, ref frames)) => {
+                        placeholder.push(points[s]
+                            .add(&frames.normals[s].multiply_scalar(vert.x))
+                            .add(&frames.binormals[s].multiply_scalar(vert.y)))
+                    }
+                }
+            }
+        }
+
+        // front bevel, ending with the outermost layer
+        for b in (0..bevel_segments).rev() {
+            bevel_layer(b, self.depth, 1.0, &mut placeholder);
+        }
+
+        // lids
+        let start = builder.vertices.len() / 3;
+        let top = vlen * (steps + bevel_segments * 2);
+        for face in &faces {
+            builder.triangle(&placeholder, face[2], face[1], face[0]);
+        }
+        for face in &faces {
+            builder.triangle(&placeholder, face[0] + top, face[1] + top, face[2] + top);
+        }
+        builder.groups.push((start, builder.vertices.len() / 3 - start, 0));
+
+        // sides
+        let start = builder.vertices.len() / 3;
+        let mut layer_offset = 0;
+        for ring in Some(&contour).into_iter().chain(holes.iter()) {
+            for j in (0..ring.len()).rev() {
+                let k = if j == 0 { ring.len() - 1 } else { j - 1 };
+
+                for s in 0..steps + bevel_segments * 2 {
+                    let slen1 = vlen * s;
+                    let slen2 = vlen * (s + 1);
+
+                    builder.quad(&placeholder,
+                                 layer_offset + j + slen1,
+                                 layer_offset + k + slen1,
+                                 layer_offset + k + slen2,
+                                 layer_offset + j + slen2);
+                }
+            }
+
+            layer_offset += ring.len();
+        }
+        builder.groups.push((start, builder.vertices.len() / 3 - start, 1));
+    }
+}
+
+impl Builder {
+    // lid uvs are the plain x and y coordinates
+    fn triangle(&mut self, placeholder: &[Vector3], a: usize, b: usize, c: usize) {
+        for &i in &[a, b, c] {
+            let p = &placeholder[i];
+            self.vertices.extend_from_slice(&[p.x, p.y, p.z]);
+            self.uvs.extend_from_slice(&[p.x, p.y]);
+        }
+    }
+
+    // side uvs use the depth and whichever of x or y changes more along the wall
+    fn quad(&mut self, placeholder: &[Vector3], a: usize, b: usize, c: usize, d: usize) {
+        let (pa, pb) = (&placeholder[a], &placeholder[b]);
+        let use_x = (pa.y - pb.y).abs() < (pa.x - pb.x).abs();
+
+        for &i in &[a, b, d, b, c, d] {
+            let p = &placeholder[i];
+            self.vertices.extend_from_slice(&[p.x, p.y, p.z]);
+            self.uvs.extend_from_slice(&[if use_x { p.x } else { p.y }, 1.0 - p.z]);
+        }
+    }
+}
+
+// for every point, the offset to the matching point of a contour shifted by one unit to the
+// left. walking the contour clockwise that contour lies outside of this one
+fn bevel_vectors(ring: &[Vector2]) -> Vec<Vector2> {
+    let len = ring.len();
+    (0..len)
+        .map(|i| {
+            let prev = &ring[(i + len - 1) % len];
+            let next = &ring[(i + 1) % len];
+            bevel_vector(&ring[i], prev, next)
+        })
+        .collect()
+}
+
+fn bevel_vector(point: &Vector2, prev: &Vector2, next: &Vector2) -> Vector2 {
+    let v_prev = point.subtract(prev);
+    let v_next = next.subtract(point);
+    let v_prev_length_squared = v_prev.length_squared();
+
+    let collinear = v_prev.x * v_next.y - v_prev.y * v_next.x;
+
+    let (v_trans, shrink_by) = if collinear.abs() > EPSILON {
+        // shift the adjacent points by unit vectors to the left and intersect the lines
+        // through them
+        let v_prev_length = v_prev_length_squared.sqrt();
+        let v_next_length = v_next.length();
+
+        let prev_shift = Vector2::new(prev.x - v_prev.y / v_prev_length,
+                                      prev.y + v_prev.x / v_prev_length);
+        let next_shift = Vector2::new(next.x - v_next.y / v_next_length,
+                                      next.y + v_next.x / v_next_length);
+
+        let sf = ((next_shift.x - prev_shift.x) * v_next.y -
+                  (next_shift.y - prev_shift.y) * v_next.x) / collinear;
+
+        let v_trans = prev_shift.add(&v_prev.multiply_scalar(sf)).subtract(point);
+
+        // not normalized on purpose, sharp corners would become ugly, but crazy spikes are
+        // prevented
+        let v_trans_length_squared = v_trans.length_squared();
+        if v_trans_length_squared <= 2.0 {
+            return v_trans;
+        }
+        (v_trans, (v_trans_length_squared / 2.0).sqrt())
+    } else {
+        let same_direction = if v_prev.x > EPSILON {
+            v_next.x > EPSILON
+        } else if v_prev.x < -EPSILON {
+            v_next.x < -EPSILON
+        } else {
+            sign(v_prev.y) == sign(v_next.y)
+        };
+
+        if same_direction {
+            // a straight sequence
+            (Vector2::new(-v_prev.y, v_prev.x), v_prev_length_squared.sqrt())
+        } else {
+            // a straight spike
+            (v_prev, (v_prev_length_squared / 2.0).sqrt())
+        }
+    };
+
+    v_trans.divide_scalar(shrink_by)
+}
+
+fn sign(value: f32) -> i32 {
+    if value > 0.0 {
+        1
+    } else if value < 0.0 {
+        -1
+    } else {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::{AttributeName, VertexAttribute};
+    use extras::{Path, Shape};
+    use geometries::ExtrudeGeometry;
+    use math::{Box3, Spline, Vector2, Vector3};
+
+    fn square() -> Shape {
+        Shape::from_points(&[Vector2::new(0.0, 0.0),
+                             Vector2::new(1.0, 0.0),
+                             Vector2::new(1.0, 1.0),
+                             Vector2::new(0.0, 1.0)])
+    }
+
+    fn assert_box(a: &Box3, b: &Box3) {
+        assert!(a.min.distance_to(&b.min) < 1e-5, "{:?} != {:?}", a, b);
+        assert!(a.max.distance_to(&b.max) < 1e-5, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn build() {
+        let mut a = ExtrudeGeometry::new(&[square()]);
+        a.bevel_enabled = false;
+        a.depth = 2.0;
+        a.steps = 2;
+        let geometry = a.build();
+        // two triangles per lid and two quads on every side
+        assert_eq!(geometry.vertex_count(), 2 * 2 * 3 + 4 * 2 * 6);
+        assert_eq!(geometry.groups.len(), 2);
+        assert_eq!(geometry.groups[0].count, 12);
+        assert_eq!(geometry.groups[1].material_index, 1);
+        assert_box(&geometry.compute_bounding_box(),
+                   &Box3::new(&Vector3::ZERO, &Vector3::new(1.0, 1.0, 2.0)));
+
+        // the back lid faces -z, the front lid +z and the sides outwards
+        let normals = geometry.compute_face_normals();
+        assert_eq!(normals[0], Vector3::NEG_Z);
+        assert_eq!(normals[2], Vector3::Z);
+        let position = geometry.attribute(&AttributeName::Position).unwrap();
+        let center = Vector3::new(0.5, 0.5, 1.0);
+        for (i, normal) in normals.iter().enumerate().skip(4) {
+            let p = position.vector3(i * 3);
+            assert!(normal.dot(&p.subtract(&center)) > 0.0);
+        }
+    }
+
+    #[test]
+    fn bevel() {
+        let mut a = ExtrudeGeometry::new(&[square()]);
+        a.bevel_segments = 2;
+        let geometry = a.build();
+        assert_eq!(geometry.vertex_count(), 2 * 2 * 3 + 4 * (1 + 2 * 2) * 6);
+        assert_box(&geometry.compute_bounding_box(),
+                   &Box3::new(&Vector3::new(-0.1, -0.1, -0.2),
+                              &Vector3::new(1.1, 1.1, 1.2)));
+    }
+
+    #[test]
+    fn holes() {
+        let mut shape = square();
+        shape.add_hole(Path::from_points(&[Vector2::new(0.25, 0.25),
+                                           Vector2::new(0.75, 0.25),
+                                           Vector2::new(0.75, 0.75),
+                                           Vector2::new(0.25, 0.75)]));
+        let mut a = ExtrudeGeometry::new(&[shape]);
+        a.bevel_enabled = false;
+        let geometry = a.build();
+        assert_eq!(geometry.vertex_count(), 2 * 8 * 3 + 8 * 6);
+    }
+
+    #[test]
+    fn extrude_path() {
+        let path = Spline::new(&[Vector3::ZERO,
+                                 Vector3::new(1.0, 0.0, 0.0),
+                                 Vector3::new(2.0, 0.0, 0.0),
+                                 Vector3::new(3.0, 0.0, 0.0)]);
+        let mut a = ExtrudeGeometry::new(&[square()]);
+        a.steps = 3;
+        a.extrude_path = Some(path);
+        let geometry = a.build();
+
+        // the shape's x maps to the path normal (-z here) and y to the binormal (+y)
+        assert_eq!(geometry.vertex_count(), 2 * 2 * 3 + 4 * 3 * 6);
+        assert_box(&geometry.compute_bounding_box(),
+                   &Box3::new(&Vector3::new(0.0, 0.0, -1.0), &Vector3::new(3.0, 1.0, 0.0)));
+    }
+}
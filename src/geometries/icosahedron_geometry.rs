@@ -0,0 +1,30 @@
+use core::BufferGeometry;
+use geometries::PolyhedronGeometry;
+
+// golden ratio
+const T: f32 = 1.618034;
+
+const VERTICES: [f32; 36] = [-1.0, T, 0.0, 1.0, T, 0.0, -1.0, -T, 0.0, 1.0, -T, 0.0, 0.0, -1.0,
+                             T, 0.0, 1.0, T, 0.0, -1.0, -T, 0.0, 1.0, -T, T, 0.0, -1.0, T, 0.0,
+                             1.0, -T, 0.0, -1.0, -T, 0.0, 1.0];
+
+const INDICES: [usize; 60] = [0, 11, 5, 0, 5, 1, 0, 1, 7, 0, 7, 10, 0, 10, 11, 1, 5, 9, 5, 11,
+                              4, 11, 10, 2, 10, 7, 6, 7, 1, 8, 3, 9, 4, 3, 4, 2, 3, 2, 6, 3, 6,
+                              8, 3, 8, 9, 4, 9, 5, 2, 4, 11, 6, 2, 10, 8, 6, 7, 9, 8, 1];
+
+#[derive(Debug,PartialEq,Copy,Clone)]
+pub struct IcosahedronGeometry {
+    pub radius: f32,
+    pub detail: usize,
+}
+
+impl IcosahedronGeometry {
+    pub const DEFAULT: IcosahedronGeometry = IcosahedronGeometry {
+        radius: 1.0,
+        detail: 0,
+    };
+
+    pub fn build(&self) -> BufferGeometry {
+        PolyhedronGeometry::new(&VERTICES, &INDICES, self.radius, self.detail).build()
+    }
+}
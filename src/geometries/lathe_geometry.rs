@@ -0,0 +1,122 @@
+use std::f32::consts::PI;
+use core::BufferGeometry;
+use geometries::{assemble, normalized_or_zero};
+use math::{clamp, Vector2, Vector3};
+
+#[derive(Debug,PartialEq,Clone)]
+pub struct LatheGeometry {
+    pub points: Vec<Vector2>,
+    pub segments: usize,
+    pub phi_start: f32,
+    pub phi_length: f32,
+}
+
+impl LatheGeometry {
+    pub fn new(points: &[Vector2],
+               segments: usize,
+               phi_start: f32,
+               phi_length: f32)
+               -> LatheGeometry {
+        LatheGeometry {
+            points: points.to_vec(),
+            segments: segments,
+            phi_start: phi_start,
+            phi_length: phi_length,
+        }
+    }
+
+    // revolves the profile around the y axis, x is the distance from the axis
+    pub fn build(&self) -> BufferGeometry {
+        let points = &self.points;
+        if points.len() < 2 {
+            return BufferGeometry::new();
+        }
+
+        let segments = self.segments.max(1);
+        let phi_length = clamp(self.phi_length, 0.0, PI * 2.0);
+        let last = points.len() - 1;
+
+        // normals of the initial meridian, averaged over the two adjacent profile edges
+        let mut init_normals = vec![];
+        let mut prev_normal = Vector3::ZERO;
+        for j in 0..points.len() {
+            if j == last {
+                init_normals.push(normalized_or_zero(&prev_normal));
+                continue;
+            }
+
+            let d = points[j + 1].subtract(&points[j]);
+            let normal = Vector3::new(d.y, -d.x, 0.0);
+            if j == 0 {
+                init_normals.push(normalized_or_zero(&normal));
+            } else {
+                init_normals.push(normalized_or_zero(&normal.add(&prev_normal)));
+            }
+            prev_normal = normal;
+        }
+
+        let mut indices = vec![];
+        let mut vertices = vec![];
+        let mut normals = vec![];
+        let mut uvs = vec![];
+
+        for i in 0..segments + 1 {
+            let phi = self.phi_start + i as f32 / segments as f32 * phi_length;
+            let sin = phi.sin();
+            let cos = phi.cos();
+
+            for j in 0..points.len() {
+                vertices.extend_from_slice(&[points[j].x * sin, points[j].y, points[j].x * cos]);
+                uvs.push(i as f32 / segments as f32);
+                uvs.push(j as f32 / last as f32);
+
+                let n = &init_normals[j];
+                normals.extend_from_slice(&[n.x * sin, n.y, n.x * cos]);
+            }
+        }
+
+        for i in 0..segments {
+            for j in 0..last {
+                let base = j + i * points.len();
+
+                let a = base;
+                let b = base + points.len();
+                let c = base + points.len() + 1;
+                let d = base + 1;
+
+                indices.extend_from_slice(&[a, b, d, c, d, b]);
+            }
+        }
+
+        assemble(Some(&indices), vertices, normals, uvs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+    use core::{AttributeName, VertexAttribute};
+    use geometries::LatheGeometry;
+    use math::{Vector2, Vector3};
+
+    #[test]
+    fn build() {
+        let profile = [Vector2::new(1.0, 0.0), Vector2::new(1.0, 1.0), Vector2::new(0.5, 2.0)];
+        let a = LatheGeometry::new(&profile, 4, 0.0, PI * 2.0).build();
+        assert_eq!(a.vertex_count(), 5 * 3);
+        assert_eq!(a.index.as_ref().unwrap().len(), 4 * 2 * 6);
+
+        // first meridian lies on +z, a quarter turn later on +x
+        let position = a.attribute(&AttributeName::Position).unwrap();
+        let normal = a.attribute(&AttributeName::Normal).unwrap();
+        let uv = a.attribute(&AttributeName::Uv).unwrap();
+        assert_eq!(position.vector3(1), Vector3::new(0.0, 1.0, 1.0));
+        assert!(position.vector3(4).subtract(&Vector3::new(1.0, 1.0, 0.0)).length() < 1e-6);
+        assert_eq!(normal.vector3(0), Vector3::new(0.0, 0.0, 1.0));
+        assert_eq!(uv.vector2(5), Vector2::new(0.25, 1.0));
+
+        // the last profile point keeps the normal of the last edge
+        let expected = Vector3::new(0.0, 0.5, 1.0).normalized();
+        assert!(normal.vector3(2).subtract(&expected).length() < 1e-6);
+    }
+}
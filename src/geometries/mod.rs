@@ -0,0 +1,67 @@
+mod box_geometry;
+mod capsule_geometry;
+mod circle_geometry;
+mod cone_geometry;
+mod cylinder_geometry;
+mod dodecahedron_geometry;
+mod extrude_geometry;
+mod icosahedron_geometry;
+mod lathe_geometry;
+mod nurbs_surface_geometry;
+mod octahedron_geometry;
+mod plane_geometry;
+mod polyhedron_geometry;
+mod ring_geometry;
+mod shape_geometry;
+mod sphere_geometry;
+mod tetrahedron_geometry;
+mod torus_geometry;
+mod torus_knot_geometry;
+mod tube_geometry;
+
+pub use self::box_geometry::BoxGeometry;
+pub use self::capsule_geometry::CapsuleGeometry;
+pub use self::circle_geometry::CircleGeometry;
+pub use self::cone_geometry::ConeGeometry;
+pub use self::cylinder_geometry::CylinderGeometry;
+pub use self::dodecahedron_geometry::DodecahedronGeometry;
+pub use self::extrude_geometry::ExtrudeGeometry;
+pub use self::icosahedron_geometry::IcosahedronGeometry;
+pub use self::lathe_geometry::LatheGeometry;
+pub use self::nurbs_surface_geometry::NurbsSurfaceGeometry;
+pub use self::octahedron_geometry::OctahedronGeometry;
+pub use self::plane_geometry::PlaneGeometry;
+pub use self::polyhedron_geometry::PolyhedronGeometry;
+pub use self::ring_geometry::RingGeometry;
+pub use self::shape_geometry::ShapeGeometry;
+pub use self::sphere_geometry::SphereGeometry;
+pub use self::tetrahedron_geometry::TetrahedronGeometry;
+pub use self::torus_geometry::TorusGeometry;
+pub use self::torus_knot_geometry::TorusKnotGeometry;
+pub use self::tube_geometry::TubeGeometry;
+
+use core::{AttributeName, BufferAttribute, BufferGeometry, Index};
+use math::Vector3;
+
+// shared by the generators, they all build flat position/normal/uv arrays like three.js does
+fn assemble(indices: Option<&[usize]>,
+            vertices: Vec<f32>,
+            normals: Vec<f32>,
+            uvs: Vec<f32>)
+            -> BufferGeometry {
+    let mut geometry = BufferGeometry::new();
+    geometry.set_index(indices.map(Index::from_vec));
+    geometry.set_attribute(AttributeName::Position, BufferAttribute::from_f32(vertices, 3));
+    geometry.set_attribute(AttributeName::Normal, BufferAttribute::from_f32(normals, 3));
+    geometry.set_attribute(AttributeName::Uv, BufferAttribute::from_f32(uvs, 2));
+    geometry
+}
+
+// zero length vectors stay zero instead of turning into NaNs
+fn normalized_or_zero(v: &Vector3) -> Vector3 {
+    if v.length_squared() > 0.0 {
+        v.normalized()
+    } else {
+        Vector3::ZERO
+    }
+}
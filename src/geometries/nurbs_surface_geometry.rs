@@ -0,0 +1,92 @@
+use core::BufferGeometry;
+use extras::NurbsSurface;
+use geometries::assemble;
+
+// samples the surface on a (slices + 1) x (stacks + 1) grid, slices along u and stacks
+// along v, laid out like three.js' ParametricGeometry. normals come from the surface
+// derivatives rather than the triangles
+#[derive(Debug,PartialEq,Clone)]
+pub struct NurbsSurfaceGeometry {
+    pub surface: NurbsSurface,
+    pub slices: usize,
+    pub stacks: usize,
+}
+
+impl NurbsSurfaceGeometry {
+    pub fn new(surface: &NurbsSurface, slices: usize, stacks: usize) -> NurbsSurfaceGeometry {
+        NurbsSurfaceGeometry {
+            surface: surface.clone(),
+            slices: slices,
+            stacks: stacks,
+        }
+    }
+
+    pub fn build(&self) -> BufferGeometry {
+        let slices = self.slices.max(1);
+        let stacks = self.stacks.max(1);
+        let slice_count = slices + 1;
+
+        let mut indices = vec![];
+        let mut vertices = vec![];
+        let mut normals = vec![];
+        let mut uvs = vec![];
+
+        for i in 0..stacks + 1 {
+            let v = i as f32 / stacks as f32;
+
+            for j in 0..slices + 1 {
+                let u = j as f32 / slices as f32;
+
+                vertices.extend(self.surface.point_at(u, v).to_vec());
+                normals.extend(self.surface.normal_at(u, v).to_vec());
+                uvs.push(u);
+                uvs.push(v);
+            }
+        }
+
+        for i in 0..stacks {
+            for j in 0..slices {
+                let a = i * slice_count + j;
+                let b = i * slice_count + j + 1;
+                let c = (i + 1) * slice_count + j + 1;
+                let d = (i + 1) * slice_count + j;
+
+                indices.extend_from_slice(&[a, b, d, b, c, d]);
+            }
+        }
+
+        assemble(Some(&indices), vertices, normals, uvs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::{AttributeName, VertexAttribute};
+    use extras::NurbsSurface;
+    use geometries::NurbsSurfaceGeometry;
+    use math::Vector4;
+
+    #[test]
+    fn build() {
+        let row = |x: f32| {
+            vec![Vector4::new(x, 0.0, 0.0, 1.0),
+                 Vector4::new(x, 1.0, 1.0, 1.0),
+                 Vector4::new(x, 0.0, 2.0, 1.0)]
+        };
+        let surface = NurbsSurface::new(1,
+                                        2,
+                                        &[0.0, 0.0, 1.0, 1.0],
+                                        &[0.0, 0.0, 0.0, 1.0, 1.0, 1.0],
+                                        &[row(0.0), row(1.0)]);
+        let a = NurbsSurfaceGeometry::new(&surface, 4, 8).build();
+        assert_eq!(a.vertex_count(), 5 * 9);
+        assert_eq!(a.index.as_ref().unwrap().len(), 4 * 8 * 6);
+
+        // the normals agree with the winding of the triangles
+        let normal = a.attribute(&AttributeName::Normal).unwrap();
+        let faces = a.compute_face_normals();
+        for (face, triangle) in faces.iter().zip(a.triangle_indices()) {
+            assert!(face.dot(&normal.vector3(triangle[0])) > 0.0);
+        }
+    }
+}
@@ -0,0 +1,25 @@
+use core::BufferGeometry;
+use geometries::PolyhedronGeometry;
+
+const VERTICES: [f32; 18] = [1.0, 0.0, 0.0, -1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, -1.0, 0.0, 0.0,
+                             0.0, 1.0, 0.0, 0.0, -1.0];
+
+const INDICES: [usize; 24] = [0, 2, 4, 0, 4, 3, 0, 3, 5, 0, 5, 2, 1, 2, 5, 1, 5, 3, 1, 3, 4, 1,
+                              4, 2];
+
+#[derive(Debug,PartialEq,Copy,Clone)]
+pub struct OctahedronGeometry {
+    pub radius: f32,
+    pub detail: usize,
+}
+
+impl OctahedronGeometry {
+    pub const DEFAULT: OctahedronGeometry = OctahedronGeometry {
+        radius: 1.0,
+        detail: 0,
+    };
+
+    pub fn build(&self) -> BufferGeometry {
+        PolyhedronGeometry::new(&VERTICES, &INDICES, self.radius, self.detail).build()
+    }
+}
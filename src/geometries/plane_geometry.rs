@@ -0,0 +1,63 @@
+use core::BufferGeometry;
+use geometries::assemble;
+
+#[derive(Debug,PartialEq,Copy,Clone)]
+pub struct PlaneGeometry {
+    pub width: f32,
+    pub height: f32,
+    pub width_segments: usize,
+    pub height_segments: usize,
+}
+
+impl PlaneGeometry {
+    pub const DEFAULT: PlaneGeometry = PlaneGeometry {
+        width: 1.0,
+        height: 1.0,
+        width_segments: 1,
+        height_segments: 1,
+    };
+
+    pub fn build(&self) -> BufferGeometry {
+        let width_half = self.width / 2.0;
+        let height_half = self.height / 2.0;
+
+        let grid_x = self.width_segments.max(1);
+        let grid_y = self.height_segments.max(1);
+        let grid_x1 = grid_x + 1;
+        let grid_y1 = grid_y + 1;
+
+        let segment_width = self.width / grid_x as f32;
+        let segment_height = self.height / grid_y as f32;
+
+        let mut indices = vec![];
+        let mut vertices = vec![];
+        let mut normals = vec![];
+        let mut uvs = vec![];
+
+        for iy in 0..grid_y1 {
+            let y = iy as f32 * segment_height - height_half;
+
+            for ix in 0..grid_x1 {
+                let x = ix as f32 * segment_width - width_half;
+
+                vertices.extend_from_slice(&[x, -y, 0.0]);
+                normals.extend_from_slice(&[0.0, 0.0, 1.0]);
+                uvs.push(ix as f32 / grid_x as f32);
+                uvs.push(1.0 - (iy as f32 / grid_y as f32));
+            }
+        }
+
+        for iy in 0..grid_y {
+            for ix in 0..grid_x {
+                let a = ix + grid_x1 * iy;
+                let b = ix + grid_x1 * (iy + 1);
+                let c = (ix + 1) + grid_x1 * (iy + 1);
+                let d = (ix + 1) + grid_x1 * iy;
+
+                indices.extend_from_slice(&[a, b, d, b, c, d]);
+            }
+        }
+
+        assemble(Some(&indices), vertices, normals, uvs)
+    }
+}
@@ -0,0 +1,205 @@
+use std::f32::consts::PI;
+use core::{BufferGeometry, NormalWeighting};
+use geometries::{assemble, normalized_or_zero};
+use math::Vector3;
+
+#[derive(Debug,PartialEq,Clone)]
+pub struct PolyhedronGeometry {
+    pub vertices: Vec<f32>,
+    pub indices: Vec<usize>,
+    pub radius: f32,
+    pub detail: usize,
+}
+
+impl PolyhedronGeometry {
+    pub fn new(vertices: &[f32],
+               indices: &[usize],
+               radius: f32,
+               detail: usize)
+               -> PolyhedronGeometry {
+        PolyhedronGeometry {
+            vertices: vertices.to_vec(),
+            indices: indices.to_vec(),
+            radius: radius,
+            detail: detail,
+        }
+    }
+
+    // non indexed like in three.js, every face keeps its own vertices so the uv seam can
+    // be fixed per face
+    pub fn build(&self) -> BufferGeometry {
+        let mut points = vec![];
+        for face in self.indices.chunks(3) {
+            let a = Vector3::from_vec(&self.vertices, face[0] * 3);
+            let b = Vector3::from_vec(&self.vertices, face[1] * 3);
+            let c = Vector3::from_vec(&self.vertices, face[2] * 3);
+            subdivide_face(&a, &b, &c, self.detail, &mut points);
+        }
+
+        let points: Vec<Vector3> = points.iter()
+            .map(|p| normalized_or_zero(p).multiply_scalar(self.radius))
+            .collect();
+        let uvs = generate_uvs(&points);
+
+        let vertices = points.iter().flat_map(|p| p.to_vec()).collect();
+        let normals = points.iter().flat_map(|p| normalized_or_zero(p).to_vec()).collect();
+        let mut geometry = assemble(None, vertices, normals, uvs);
+
+        // flat shading without subdivision, the smooth sphere normals otherwise
+        if self.detail == 0 {
+            geometry.compute_vertex_normals(NormalWeighting::Area);
+        }
+        geometry
+    }
+}
+
+fn subdivide_face(a: &Vector3,
+                  b: &Vector3,
+                  c: &Vector3,
+                  detail: usize,
+                  points: &mut Vec<Vector3>) {
+    let cols = detail + 1;
+
+    // a grid of points lerped between the face corners, row i lies between a-c and b-c
+    let mut v: Vec<Vec<Vector3>> = vec![];
+    for i in 0..cols + 1 {
+        let t = i as f32 / cols as f32;
+        let aj = a.lerp(c, t);
+        let bj = b.lerp(c, t);
+        let rows = cols - i;
+
+        let mut row = vec![];
+        for j in 0..rows + 1 {
+            if j == 0 && i == cols {
+                row.push(aj);
+            } else {
+                row.push(aj.lerp(&bj, j as f32 / rows as f32));
+            }
+        }
+        v.push(row);
+    }
+
+    for i in 0..cols {
+        for j in 0..2 * (cols - i) - 1 {
+            let k = j / 2;
+
+            if j % 2 == 0 {
+                points.extend_from_slice(&[v[i][k + 1], v[i + 1][k], v[i][k]]);
+            } else {
+                points.extend_from_slice(&[v[i][k + 1], v[i + 1][k + 1], v[i + 1][k]]);
+            }
+        }
+    }
+}
+
+fn generate_uvs(points: &[Vector3]) -> Vec<f32> {
+    let mut uvs = vec![];
+    for p in points {
+        let u = azimuth(p) / 2.0 / PI + 0.5;
+        let v = inclination(p) / PI + 0.5;
+        uvs.push(u);
+        uvs.push(1.0 - v);
+    }
+
+    correct_uvs(points, &mut uvs);
+    correct_seam(&mut uvs);
+    uvs
+}
+
+fn correct_uvs(points: &[Vector3], uvs: &mut [f32]) {
+    for (face, corners) in points.chunks(3).enumerate() {
+        let centroid = corners[0].add(&corners[1]).add(&corners[2]).divide_scalar(3.0);
+        let azi = azimuth(&centroid);
+
+        for (k, vector) in corners.iter().enumerate() {
+            let stride = face * 6 + k * 2;
+
+            if azi < 0.0 && uvs[stride] == 1.0 {
+                uvs[stride] -= 1.0;
+            }
+            // vertices on the poles take the azimuth of their face
+            if vector.x == 0.0 && vector.z == 0.0 {
+                uvs[stride] = azi / 2.0 / PI + 0.5;
+            }
+        }
+    }
+}
+
+// faces wrapping around the back of the sphere get their small u values pushed past 1
+fn correct_seam(uvs: &mut [f32]) {
+    for face in uvs.chunks_mut(6) {
+        let x0 = face[0];
+        let x1 = face[2];
+        let x2 = face[4];
+
+        let max = x0.max(x1).max(x2);
+        let min = x0.min(x1).min(x2);
+
+        if max > 0.9 && min < 0.1 {
+            for i in 0..3 {
+                if face[i * 2] < 0.2 {
+                    face[i * 2] += 1.0;
+                }
+            }
+        }
+    }
+}
+
+// angle around the y axis, counterclockwise from -x
+fn azimuth(vector: &Vector3) -> f32 {
+    vector.z.atan2(-vector.x)
+}
+
+// angle above the xz plane
+fn inclination(vector: &Vector3) -> f32 {
+    (-vector.y).atan2((vector.x * vector.x + vector.z * vector.z).sqrt())
+}
+
+#[cfg(test)]
+mod tests {
+    use core::{AttributeName, VertexAttribute};
+    use geometries::{DodecahedronGeometry, IcosahedronGeometry, OctahedronGeometry,
+                     TetrahedronGeometry};
+
+    #[test]
+    fn build() {
+        let a = TetrahedronGeometry::DEFAULT.build();
+        assert_eq!(a.vertex_count(), 4 * 3);
+        assert!(a.index.is_none());
+
+        let b = OctahedronGeometry::DEFAULT.build();
+        assert_eq!(b.vertex_count(), 8 * 3);
+
+        let c = DodecahedronGeometry::DEFAULT.build();
+        assert_eq!(c.vertex_count(), 36 * 3);
+
+        // every subdivision level splits each face into (detail + 1)^2 faces
+        let d = IcosahedronGeometry {
+            radius: 2.0,
+            detail: 2,
+        };
+        let d = d.build();
+        assert_eq!(d.vertex_count(), 20 * 9 * 3);
+
+        let position = d.attribute(&AttributeName::Position).unwrap();
+        let normal = d.attribute(&AttributeName::Normal).unwrap();
+        let uv = d.attribute(&AttributeName::Uv).unwrap();
+        for i in 0..position.count() {
+            assert!((position.vector3(i).length() - 2.0).abs() < 1e-5);
+            let expected = position.vector3(i).multiply_scalar(0.5);
+            assert!(normal.vector3(i).subtract(&expected).length() < 1e-5);
+            let u = uv.vector2(i);
+            assert!(u.x >= 0.0 && u.x <= 1.2 && u.y >= 0.0 && u.y <= 1.0);
+        }
+    }
+
+    #[test]
+    fn flat_normals() {
+        let a = OctahedronGeometry::DEFAULT.build();
+        let normal = a.attribute(&AttributeName::Normal).unwrap();
+        let expected = normal.vector3(0);
+        assert!((expected.length() - 1.0).abs() < 1e-6);
+        assert_eq!(normal.vector3(1), expected);
+        assert_eq!(normal.vector3(2), expected);
+    }
+}
@@ -0,0 +1,70 @@
+use std::f32::consts::PI;
+use core::BufferGeometry;
+use geometries::assemble;
+
+#[derive(Debug,PartialEq,Copy,Clone)]
+pub struct RingGeometry {
+    pub inner_radius: f32,
+    pub outer_radius: f32,
+    pub theta_segments: usize,
+    pub phi_segments: usize,
+    pub theta_start: f32,
+    pub theta_length: f32,
+}
+
+impl RingGeometry {
+    pub const DEFAULT: RingGeometry = RingGeometry {
+        inner_radius: 0.5,
+        outer_radius: 1.0,
+        theta_segments: 32,
+        phi_segments: 1,
+        theta_start: 0.0,
+        theta_length: PI * 2.0,
+    };
+
+    pub fn build(&self) -> BufferGeometry {
+        let theta_segments = self.theta_segments.max(3);
+        let phi_segments = self.phi_segments.max(1);
+
+        let mut indices = vec![];
+        let mut vertices = vec![];
+        let mut normals = vec![];
+        let mut uvs = vec![];
+
+        let mut radius = self.inner_radius;
+        let radius_step = (self.outer_radius - self.inner_radius) / phi_segments as f32;
+
+        for _ in 0..phi_segments + 1 {
+            for i in 0..theta_segments + 1 {
+                let segment = self.theta_start +
+                              i as f32 / theta_segments as f32 * self.theta_length;
+                let x = radius * segment.cos();
+                let y = radius * segment.sin();
+
+                vertices.extend_from_slice(&[x, y, 0.0]);
+                normals.extend_from_slice(&[0.0, 0.0, 1.0]);
+                uvs.push((x / self.outer_radius + 1.0) / 2.0);
+                uvs.push((y / self.outer_radius + 1.0) / 2.0);
+            }
+
+            radius += radius_step;
+        }
+
+        for j in 0..phi_segments {
+            let theta_segment_level = j * (theta_segments + 1);
+
+            for i in 0..theta_segments {
+                let segment = i + theta_segment_level;
+
+                let a = segment;
+                let b = segment + theta_segments + 1;
+                let c = segment + theta_segments + 2;
+                let d = segment + 1;
+
+                indices.extend_from_slice(&[a, b, d, b, c, d]);
+            }
+        }
+
+        assemble(Some(&indices), vertices, normals, uvs)
+    }
+}
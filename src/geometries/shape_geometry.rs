@@ -0,0 +1,104 @@
+use core::BufferGeometry;
+use extras::{Shape, ShapeUtils};
+use geometries::assemble;
+
+#[derive(Debug,PartialEq,Clone)]
+pub struct ShapeGeometry {
+    pub shapes: Vec<Shape>,
+    pub curve_segments: usize,
+}
+
+impl ShapeGeometry {
+    pub fn new(shapes: &[Shape], curve_segments: usize) -> ShapeGeometry {
+        ShapeGeometry {
+            shapes: shapes.to_vec(),
+            curve_segments: curve_segments,
+        }
+    }
+
+    // flat in the xy plane facing +z, one group per shape. uvs are the plain x and y
+    // coordinates like in three.js
+    pub fn build(&self) -> BufferGeometry {
+        let mut indices = vec![];
+        let mut vertices = vec![];
+        let mut normals = vec![];
+        let mut uvs = vec![];
+        let mut groups = vec![];
+
+        for (material_index, shape) in self.shapes.iter().enumerate() {
+            let group_start = indices.len();
+            let index_offset = vertices.len() / 3;
+
+            let (mut contour, mut holes) = shape.extract_points(self.curve_segments);
+            ShapeUtils::remove_closing_point(&mut contour);
+
+            // the contour has to be clockwise and the holes counterclockwise for the faces to
+            // point along +z
+            if !ShapeUtils::is_clockwise(&contour) {
+                contour.reverse();
+            }
+            for hole in &mut holes {
+                ShapeUtils::remove_closing_point(hole);
+                if ShapeUtils::is_clockwise(hole) {
+                    hole.reverse();
+                }
+            }
+
+            let faces = ShapeUtils::triangulate(&contour, &holes);
+
+            for point in contour.iter().chain(holes.iter().flat_map(|hole| hole.iter())) {
+                vertices.extend_from_slice(&[point.x, point.y, 0.0]);
+                normals.extend_from_slice(&[0.0, 0.0, 1.0]);
+                uvs.extend_from_slice(&[point.x, point.y]);
+            }
+
+            for face in faces {
+                indices.extend(face.iter().map(|i| i + index_offset));
+            }
+
+            groups.push((group_start, indices.len() - group_start, material_index));
+        }
+
+        let mut geometry = assemble(Some(&indices), vertices, normals, uvs);
+        for (start, count, material_index) in groups {
+            geometry.add_group(start, count, material_index);
+        }
+        geometry
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use extras::{Path, Shape};
+    use geometries::ShapeGeometry;
+    use math::{Vector2, Vector3};
+
+    #[test]
+    fn build() {
+        let mut a = Shape::from_points(&[Vector2::new(0.0, 0.0),
+                                         Vector2::new(4.0, 0.0),
+                                         Vector2::new(4.0, 4.0),
+                                         Vector2::new(0.0, 4.0),
+                                         Vector2::new(0.0, 0.0)]);
+        a.add_hole(Path::from_points(&[Vector2::new(2.0, 1.0),
+                                       Vector2::new(1.0, 2.0),
+                                       Vector2::new(2.0, 3.0),
+                                       Vector2::new(3.0, 2.0),
+                                       Vector2::new(2.0, 1.0)]));
+
+        let b = Shape::from_points(&[Vector2::new(5.0, 0.0),
+                                     Vector2::new(6.0, 0.0),
+                                     Vector2::new(6.0, 1.0)]);
+
+        let geometry = ShapeGeometry::new(&[a, b], 4).build();
+        // closing points are dropped from the square and the hole
+        assert_eq!(geometry.vertex_count(), 4 + 4 + 3);
+        assert_eq!(geometry.groups.len(), 2);
+        assert_eq!(geometry.groups[0].count, 3 * 8);
+        assert_eq!(geometry.groups[1].count, 3);
+
+        for normal in geometry.compute_face_normals() {
+            assert!(normal.subtract(&Vector3::Z).length() < 1e-6);
+        }
+    }
+}
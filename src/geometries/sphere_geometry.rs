@@ -0,0 +1,122 @@
+use std::f32::consts::PI;
+use core::BufferGeometry;
+use geometries::{assemble, normalized_or_zero};
+use math::Vector3;
+
+#[derive(Debug,PartialEq,Copy,Clone)]
+pub struct SphereGeometry {
+    pub radius: f32,
+    pub width_segments: usize,
+    pub height_segments: usize,
+    pub phi_start: f32,
+    pub phi_length: f32,
+    pub theta_start: f32,
+    pub theta_length: f32,
+}
+
+impl SphereGeometry {
+    pub const DEFAULT: SphereGeometry = SphereGeometry {
+        radius: 1.0,
+        width_segments: 32,
+        height_segments: 16,
+        phi_start: 0.0,
+        phi_length: PI * 2.0,
+        theta_start: 0.0,
+        theta_length: PI,
+    };
+
+    pub fn build(&self) -> BufferGeometry {
+        let width_segments = self.width_segments.max(3);
+        let height_segments = self.height_segments.max(2);
+        let theta_end = (self.theta_start + self.theta_length).min(PI);
+
+        let mut indices = vec![];
+        let mut vertices = vec![];
+        let mut normals = vec![];
+        let mut uvs = vec![];
+        let mut grid = vec![];
+        let mut index = 0;
+
+        for iy in 0..height_segments + 1 {
+            let mut vertices_row = vec![];
+            let v = iy as f32 / height_segments as f32;
+
+            // special case for the poles
+            let u_offset = if iy == 0 && self.theta_start == 0.0 {
+                0.5 / width_segments as f32
+            } else if iy == height_segments && theta_end == PI {
+                -0.5 / width_segments as f32
+            } else {
+                0.0
+            };
+
+            for ix in 0..width_segments + 1 {
+                let u = ix as f32 / width_segments as f32;
+                let phi = self.phi_start + u * self.phi_length;
+                let theta = self.theta_start + v * self.theta_length;
+
+                let vertex = Vector3::new(-self.radius * phi.cos() * theta.sin(),
+                                          self.radius * theta.cos(),
+                                          self.radius * phi.sin() * theta.sin());
+                vertices.extend(vertex.to_vec());
+                normals.extend(normalized_or_zero(&vertex).to_vec());
+                uvs.push(u + u_offset);
+                uvs.push(1.0 - v);
+
+                vertices_row.push(index);
+                index += 1;
+            }
+
+            grid.push(vertices_row);
+        }
+
+        for iy in 0..height_segments {
+            for ix in 0..width_segments {
+                let a = grid[iy][ix + 1];
+                let b = grid[iy][ix];
+                let c = grid[iy + 1][ix];
+                let d = grid[iy + 1][ix + 1];
+
+                if iy != 0 || self.theta_start > 0.0 {
+                    indices.extend_from_slice(&[a, b, d]);
+                }
+                if iy != height_segments - 1 || theta_end < PI {
+                    indices.extend_from_slice(&[b, c, d]);
+                }
+            }
+        }
+
+        assemble(Some(&indices), vertices, normals, uvs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::{AttributeName, VertexAttribute};
+    use geometries::SphereGeometry;
+    use math::Vector3;
+
+    #[test]
+    fn build() {
+        let a = SphereGeometry {
+            width_segments: 8,
+            height_segments: 6,
+            ..SphereGeometry::DEFAULT
+        };
+        let a = a.build();
+        assert_eq!(a.vertex_count(), 9 * 7);
+        // the pole rows only get a single triangle per segment
+        assert_eq!(a.index.as_ref().unwrap().len(), 3 * (2 * 8 * 6 - 2 * 8));
+
+        let position = a.attribute(&AttributeName::Position).unwrap();
+        let normal = a.attribute(&AttributeName::Normal).unwrap();
+        let uv = a.attribute(&AttributeName::Uv).unwrap();
+        for i in 0..position.count() {
+            assert!((position.vector3(i).length() - 1.0).abs() < 1e-6);
+            assert!((normal.vector3(i).subtract(&position.vector3(i))).length() < 1e-6);
+        }
+        assert_eq!(position.vector3(0), Vector3::Y);
+        assert_eq!(uv.vector2(0).x, 0.5 / 8.0);
+        assert_eq!(uv.vector2(0).y, 1.0);
+    }
+}
@@ -0,0 +1,23 @@
+use core::BufferGeometry;
+use geometries::PolyhedronGeometry;
+
+const VERTICES: [f32; 12] = [1.0, 1.0, 1.0, -1.0, -1.0, 1.0, -1.0, 1.0, -1.0, 1.0, -1.0, -1.0];
+
+const INDICES: [usize; 12] = [2, 1, 0, 0, 3, 2, 1, 3, 0, 2, 3, 1];
+
+#[derive(Debug,PartialEq,Copy,Clone)]
+pub struct TetrahedronGeometry {
+    pub radius: f32,
+    pub detail: usize,
+}
+
+impl TetrahedronGeometry {
+    pub const DEFAULT: TetrahedronGeometry = TetrahedronGeometry {
+        radius: 1.0,
+        detail: 0,
+    };
+
+    pub fn build(&self) -> BufferGeometry {
+        PolyhedronGeometry::new(&VERTICES, &INDICES, self.radius, self.detail).build()
+    }
+}
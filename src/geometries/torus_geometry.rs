@@ -0,0 +1,63 @@
+use std::f32::consts::PI;
+use core::BufferGeometry;
+use geometries::{assemble, normalized_or_zero};
+use math::Vector3;
+
+#[derive(Debug,PartialEq,Copy,Clone)]
+pub struct TorusGeometry {
+    pub radius: f32,
+    pub tube: f32,
+    pub radial_segments: usize,
+    pub tubular_segments: usize,
+    pub arc: f32,
+}
+
+impl TorusGeometry {
+    pub const DEFAULT: TorusGeometry = TorusGeometry {
+        radius: 1.0,
+        tube: 0.4,
+        radial_segments: 12,
+        tubular_segments: 48,
+        arc: PI * 2.0,
+    };
+
+    pub fn build(&self) -> BufferGeometry {
+        let radial_segments = self.radial_segments.max(1);
+        let tubular_segments = self.tubular_segments.max(1);
+
+        let mut indices = vec![];
+        let mut vertices = vec![];
+        let mut normals = vec![];
+        let mut uvs = vec![];
+
+        for j in 0..radial_segments + 1 {
+            for i in 0..tubular_segments + 1 {
+                let u = i as f32 / tubular_segments as f32 * self.arc;
+                let v = j as f32 / radial_segments as f32 * PI * 2.0;
+
+                let vertex = Vector3::new((self.radius + self.tube * v.cos()) * u.cos(),
+                                          (self.radius + self.tube * v.cos()) * u.sin(),
+                                          self.tube * v.sin());
+                let center = Vector3::new(self.radius * u.cos(), self.radius * u.sin(), 0.0);
+
+                vertices.extend(vertex.to_vec());
+                normals.extend(normalized_or_zero(&vertex.subtract(&center)).to_vec());
+                uvs.push(i as f32 / tubular_segments as f32);
+                uvs.push(j as f32 / radial_segments as f32);
+            }
+        }
+
+        for j in 1..radial_segments + 1 {
+            for i in 1..tubular_segments + 1 {
+                let a = (tubular_segments + 1) * j + i - 1;
+                let b = (tubular_segments + 1) * (j - 1) + i - 1;
+                let c = (tubular_segments + 1) * (j - 1) + i;
+                let d = (tubular_segments + 1) * j + i;
+
+                indices.extend_from_slice(&[a, b, d, b, c, d]);
+            }
+        }
+
+        assemble(Some(&indices), vertices, normals, uvs)
+    }
+}
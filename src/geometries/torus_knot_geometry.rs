@@ -0,0 +1,86 @@
+use std::f32::consts::PI;
+use core::BufferGeometry;
+use geometries::{assemble, normalized_or_zero};
+use math::Vector3;
+
+#[derive(Debug,PartialEq,Copy,Clone)]
+pub struct TorusKnotGeometry {
+    pub radius: f32,
+    pub tube: f32,
+    pub tubular_segments: usize,
+    pub radial_segments: usize,
+    pub p: f32,
+    pub q: f32,
+}
+
+impl TorusKnotGeometry {
+    pub const DEFAULT: TorusKnotGeometry = TorusKnotGeometry {
+        radius: 1.0,
+        tube: 0.4,
+        tubular_segments: 64,
+        radial_segments: 8,
+        p: 2.0,
+        q: 3.0,
+    };
+
+    pub fn build(&self) -> BufferGeometry {
+        let tubular_segments = self.tubular_segments.max(1);
+        let radial_segments = self.radial_segments.max(1);
+
+        let mut indices = vec![];
+        let mut vertices = vec![];
+        let mut normals = vec![];
+        let mut uvs = vec![];
+
+        for i in 0..tubular_segments + 1 {
+            let u = i as f32 / tubular_segments as f32 * self.p * PI * 2.0;
+
+            // the point slightly ahead approximates the tangent, the sum of both points
+            // gives a vector pointing away from the knot's center to build the frame with
+            let p1 = self.position_on_curve(u);
+            let p2 = self.position_on_curve(u + 0.01);
+            let t = p2.subtract(&p1);
+            let n = p2.add(&p1);
+            let b = t.cross(&n);
+            let n = b.cross(&t);
+            let b = normalized_or_zero(&b);
+            let n = normalized_or_zero(&n);
+
+            for j in 0..radial_segments + 1 {
+                let v = j as f32 / radial_segments as f32 * PI * 2.0;
+                let cx = -self.tube * v.cos();
+                let cy = self.tube * v.sin();
+
+                let vertex = p1.add(&n.multiply_scalar(cx)).add(&b.multiply_scalar(cy));
+                vertices.extend(vertex.to_vec());
+                normals.extend(normalized_or_zero(&vertex.subtract(&p1)).to_vec());
+                uvs.push(i as f32 / tubular_segments as f32);
+                uvs.push(j as f32 / radial_segments as f32);
+            }
+        }
+
+        for j in 1..tubular_segments + 1 {
+            for i in 1..radial_segments + 1 {
+                let a = (radial_segments + 1) * (j - 1) + (i - 1);
+                let b = (radial_segments + 1) * j + (i - 1);
+                let c = (radial_segments + 1) * j + i;
+                let d = (radial_segments + 1) * (j - 1) + i;
+
+                indices.extend_from_slice(&[a, b, d, b, c, d]);
+            }
+        }
+
+        assemble(Some(&indices), vertices, normals, uvs)
+    }
+
+    fn position_on_curve(&self, u: f32) -> Vector3 {
+        let cu = u.cos();
+        let su = u.sin();
+        let qu_over_p = self.q / self.p * u;
+        let cs = qu_over_p.cos();
+
+        Vector3::new(self.radius * (2.0 + cs) * 0.5 * cu,
+                     self.radius * (2.0 + cs) * su * 0.5,
+                     self.radius * qu_over_p.sin() * 0.5)
+    }
+}
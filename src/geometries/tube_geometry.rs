@@ -0,0 +1,146 @@
+use std::f32::consts::PI;
+use core::BufferGeometry;
+use geometries::assemble;
+use extras::{Curve, FrenetFrames};
+use math::{Spline, Vector3};
+
+// rings are spaced evenly along the length of the path
+#[derive(Debug,PartialEq,Clone)]
+pub struct TubeGeometry<C: Curve<Vector = Vector3> = Spline> {
+    pub path: C,
+    pub tubular_segments: usize,
+    pub radius: f32,
+    pub radial_segments: usize,
+    // joins the last ring back to the first one, for paths that loop
+    pub closed: bool,
+}
+
+impl<C: Curve<Vector = Vector3>> TubeGeometry<C> {
+    pub fn new(path: C,
+               tubular_segments: usize,
+               radius: f32,
+               radial_segments: usize,
+               closed: bool)
+               -> TubeGeometry<C> {
+        TubeGeometry {
+            path: path,
+            tubular_segments: tubular_segments,
+            radius: radius,
+            radial_segments: radial_segments,
+            closed: closed,
+        }
+    }
+
+    // open tubes have no caps, the ends are left as they are like in three.js
+    pub fn build(&self) -> BufferGeometry {
+        let tubular_segments = self.tubular_segments.max(1);
+        let radial_segments = self.radial_segments.max(1);
+        let frames = FrenetFrames::from_curve(&self.path, tubular_segments, self.closed);
+
+        let mut indices = vec![];
+        let mut vertices = vec![];
+        let mut normals = vec![];
+        let mut uvs = vec![];
+
+        // a closed tube repeats its first ring at the end, only the uvs differ
+        let last = if self.closed { 0 } else { tubular_segments };
+        let rings = (0..tubular_segments).chain(Some(last));
+
+        for (i, ring) in rings.enumerate() {
+            let point = self.path.spaced_point_at(ring as f32 / tubular_segments as f32);
+            let n = &frames.normals[ring];
+            let b = &frames.binormals[ring];
+
+            for j in 0..radial_segments + 1 {
+                let v = j as f32 / radial_segments as f32 * PI * 2.0;
+                let sin = v.sin();
+                let cos = -v.cos();
+
+                let normal = n.multiply_scalar(cos).add(&b.multiply_scalar(sin)).normalized();
+                vertices.extend(point.add(&normal.multiply_scalar(self.radius)).to_vec());
+                normals.extend(normal.to_vec());
+                uvs.push(i as f32 / tubular_segments as f32);
+                uvs.push(j as f32 / radial_segments as f32);
+            }
+        }
+
+        for j in 1..tubular_segments + 1 {
+            for i in 1..radial_segments + 1 {
+                let a = (radial_segments + 1) * (j - 1) + (i - 1);
+                let b = (radial_segments + 1) * j + (i - 1);
+                let c = (radial_segments + 1) * j + i;
+                let d = (radial_segments + 1) * (j - 1) + i;
+
+                indices.extend_from_slice(&[a, b, d, b, c, d]);
+            }
+        }
+
+        assemble(Some(&indices), vertices, normals, uvs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::{AttributeName, VertexAttribute};
+    use extras::{CatmullRomCurve3, CatmullRomType, Curve};
+    use geometries::TubeGeometry;
+    use math::{Spline, Vector3};
+
+    fn path() -> Spline {
+        Spline::new(&[Vector3::ZERO,
+                      Vector3::new(1.0, 1.0, 0.0),
+                      Vector3::new(2.0, 0.0, 1.0),
+                      Vector3::new(3.0, 1.0, 1.0)])
+    }
+
+    #[test]
+    fn build() {
+        let path = path();
+        let a = TubeGeometry::new(path.clone(), 16, 0.25, 6, false).build();
+        assert_eq!(a.vertex_count(), 17 * 7);
+        assert_eq!(a.index.as_ref().unwrap().len(), 16 * 6 * 6);
+
+        // every ring lies around its path point at the tube radius
+        let position = a.attribute(&AttributeName::Position).unwrap();
+        let normal = a.attribute(&AttributeName::Normal).unwrap();
+        for i in 0..17 {
+            let center = path.spaced_point_at(i as f32 / 16.0);
+            for j in 0..7 {
+                let index = i * 7 + j;
+                let offset = position.vector3(index).subtract(&center);
+                assert!((offset.length() - 0.25).abs() < 1e-5);
+                assert!(offset.normalized().subtract(&normal.vector3(index)).length() < 1e-4);
+            }
+        }
+    }
+
+    #[test]
+    fn closed() {
+        let a = TubeGeometry::new(path(), 16, 0.25, 6, true).build();
+        assert_eq!(a.vertex_count(), 17 * 7);
+
+        let position = a.attribute(&AttributeName::Position).unwrap();
+        let uv = a.attribute(&AttributeName::Uv).unwrap();
+        for j in 0..7 {
+            assert_eq!(position.vector3(16 * 7 + j), position.vector3(j));
+        }
+        assert_eq!(uv.x(16 * 7), 1.0);
+    }
+
+    #[test]
+    fn curve() {
+        let points = [Vector3::ZERO,
+                      Vector3::new(1.0, 0.0, 0.0),
+                      Vector3::new(1.0, 0.0, 1.0),
+                      Vector3::new(0.0, 0.0, 1.0)];
+        let path = CatmullRomCurve3::new(&points, true, CatmullRomType::Centripetal);
+        let a = TubeGeometry::new(path, 32, 0.1, 8, true).build();
+        assert_eq!(a.vertex_count(), 33 * 9);
+
+        // the loop has no seam
+        let normal = a.attribute(&AttributeName::Normal).unwrap();
+        for j in 0..9 {
+            assert!(normal.vector3(32 * 9 + j).subtract(&normal.vector3(j)).length() < 1e-5);
+        }
+    }
+}
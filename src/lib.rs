@@ -4,6 +4,8 @@
 
 mod cameras;
 mod core;
+mod extras;
+mod geometries;
 mod math;
 mod objects;
 
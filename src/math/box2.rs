@@ -1,7 +1,7 @@
 use std::f32::{INFINITY, NEG_INFINITY};
 
 use math::*;
-// use core::Object3D;
+use core::{NodeId, SceneGraph};
 
 #[derive(Debug,PartialEq,Copy,Clone)]
 pub struct Box2 {
@@ -37,78 +37,17 @@ impl Box2 {
         Box2::new(&center.subtract(&half_size), &center.add(&half_size))
     }
 
-    // pub fn from_object(object:&Object3D) {
-    // 		// Computes the world-axis-aligned bounding box of an object (including its children),
-    // 		// accounting for both the object's, and children's, world transforms
-
-    // 		let updated_object_matrix = object.updateMatrixWorld( true );
-
-
-    // 			self.makeEmpty();
-
-    // 			object.traverse( function ( node ) {
-
-    // 				let geometry = node.geometry;
-
-    // 				if ( geometry !== undefined ) {
-
-    // 					if ( (geometry && geometry.isGeometry) ) {
-
-    // 						let vertices = geometry.vertices;
-
-    // 						for ( let i = 0, il = vertices.length; i < il; i ++ ) {
-
-    // 							v1.copy( vertices[ i ] );
-    // 							v1.applyMatrix4( node.matrixWorld );
-
-    // 							scope.expandByPoint( v1 );
-
-    // 						}
-
-    // 					} else if ( (geometry && geometry.isBufferGeometry) ) {
-
-    // 						let attribute = geometry.attributes.position;
-
-    // 						if ( attribute !== undefined ) {
-
-    // 							let array, offset, stride;
-
-    // 							if ( (attribute && attribute.isInterleavedBufferAttribute) ) {
-
-    // 								array = attribute.data.array;
-    // 								offset = attribute.offset;
-    // 								stride = attribute.data.stride;
-
-    // 							} else {
-
-    // 								array = attribute.array;
-    // 								offset = 0;
-    // 								stride = 3;
-
-    // 							}
-
-    // 							for ( let i = offset, il = array.length; i < il; i += stride ) {
-
-    // 								v1.fromArray( array, i );
-    // 								v1.applyMatrix4( node.matrixWorld );
-
-    // 								scope.expandByPoint( v1 );
-
-    // 							}
-
-    // 						}
-
-    // 					}
-
-    // 				}
-
-    // 			} );
-
-    // 			return this;
-
-    // 		};
-
-    // 	}(),
+    // the world x and y extent of the node and all its descendants, for laying out things that
+    // are looked at from the front. see Box3::from_object
+    pub fn from_object(graph: &SceneGraph, id: NodeId, precise: bool) -> Box2 {
+        let box3 = Box3::from_object(graph, id, precise);
+        if box3.is_empty() {
+            Box2::EMPTY
+        } else {
+            Box2::new(&Vector2::new(box3.min.x, box3.min.y),
+                      &Vector2::new(box3.max.x, box3.max.y))
+        }
+    }
 
     pub fn is_empty(&self) -> bool {
         // this is a more robust check for empty than ( volume <= 0 ) because volume can get positive with two negative axes
@@ -1,7 +1,7 @@
 use std::f32::{INFINITY, NEG_INFINITY};
 
 use math::{Vector3, Sphere, Plane, Matrix4};
-// use core::Object3D;
+use core::{AttributeName, NodeId, SceneGraph, VertexAttribute};
 
 #[derive(Debug,PartialEq,Copy,Clone)]
 pub struct Box3 {
@@ -34,83 +34,48 @@ impl Box3 {
         points.into_iter().fold(Box3::EMPTY, |acc, p| acc.expand_by_point(p))
     }
 
+    pub fn from_attribute<T: VertexAttribute + ?Sized>(attribute: &T) -> Box3 {
+        (0..attribute.count())
+            .fold(Box3::EMPTY, |acc, i| acc.expand_by_point(&attribute.vector3(i)))
+    }
+
     pub fn from_center_and_size(center: &Vector3, size: &Vector3) -> Box3 {
         let half_size = size.multiply_scalar(0.5);
         Box3::new(&center.subtract(&half_size), &center.add(&half_size))
     }
 
-    // pub fn from_object(object:&Object3D) {
-    // 		// Computes the world-axis-aligned bounding box of an object (including its children),
-    // 		// accounting for both the object's, and children's, world transforms
-
-    // 		let updated_object_matrix = object.updateMatrixWorld( true );
-
-
-    // 			self.makeEmpty();
-
-    // 			object.traverse( function ( node ) {
-
-    // 				let geometry = node.geometry;
-
-    // 				if ( geometry !== undefined ) {
-
-    // 					if ( (geometry && geometry.isGeometry) ) {
-
-    // 						let vertices = geometry.vertices;
-
-    // 						for ( let i = 0, il = vertices.length; i < il; i ++ ) {
-
-    // 							v1.copy( vertices[ i ] );
-    // 							v1.applyMatrix4( node.matrixWorld );
-
-    // 							scope.expandByPoint( v1 );
-
-    // 						}
-
-    // 					} else if ( (geometry && geometry.isBufferGeometry) ) {
-
-    // 						let attribute = geometry.attributes.position;
-
-    // 						if ( attribute !== undefined ) {
-
-    // 							let array, offset, stride;
-
-    // 							if ( (attribute && attribute.isInterleavedBufferAttribute) ) {
-
-    // 								array = attribute.data.array;
-    // 								offset = attribute.offset;
-    // 								stride = attribute.data.stride;
-
-    // 							} else {
-
-    // 								array = attribute.array;
-    // 								offset = 0;
-    // 								stride = 3;
-
-    // 							}
-
-    // 							for ( let i = offset, il = array.length; i < il; i += stride ) {
-
-    // 								v1.fromArray( array, i );
-    // 								v1.applyMatrix4( node.matrixWorld );
-
-    // 								scope.expandByPoint( v1 );
-
-    // 							}
-
-    // 						}
-
-    // 					}
-
-    // 				}
-
-    // 			} );
-
-    // 			return this;
-
-    // 		};
+    // world aligned box around the geometry of the node and all its descendants, the world
+    // matrices have to be up to date. precise transforms every vertex, otherwise the corners of
+    // each geometry's local box are transformed, which is faster but can be a lot bigger for
+    // rotated nodes
+    pub fn from_object(graph: &SceneGraph, id: NodeId, precise: bool) -> Box3 {
+        let mut box3 = Box3::EMPTY;
+
+        for node in Some(id).into_iter().chain(graph.descendants(id)) {
+            let object = &graph[node];
+            let geometry = match object.geometry {
+                Some(ref geometry) => geometry,
+                None => continue,
+            };
+
+            if precise {
+                if let Some(position) = geometry.attribute(&AttributeName::Position) {
+                    for i in 0..position.count() {
+                        let point = position.vector3(i).apply_matrix4(&object.matrix_world);
+                        box3 = box3.expand_by_point(&point);
+                    }
+                }
+            } else {
+                let local = match geometry.bounding_box {
+                    Some(local) => local,
+                    None => geometry.compute_bounding_box(),
+                };
+                box3 = box3.union(&local.apply_matrix4(&object.matrix_world));
+            }
+        }
 
-    // 	}(),
+        box3
+    }
 
     pub fn is_empty(&self) -> bool {
         // this is a more robust check for empty than ( volume <= 0 ) because volume can get positive with two negative axes
@@ -253,6 +218,9 @@ impl Box3 {
 
 #[cfg(test)]
 mod tests {
+    use std::f32::consts::FRAC_PI_4;
+    use core::{Object3D, SceneGraph};
+    use geometries::{BoxGeometry, SphereGeometry};
     use math::*;
 
     const V3_TWO: Vector3 = Vector3 {
@@ -547,4 +515,48 @@ mod tests {
         assert_eq!(d.translate(&Vector3::ONE), b);
         assert_eq!(b.translate(&Vector3::ONE.negate()), d);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn from_object() {
+        let mut graph = SceneGraph::new();
+        let mut parent = Object3D::next();
+        parent.position = Vector3::new(10.0, 0.0, 0.0);
+        let parent = graph.insert(parent);
+
+        let mut child = Object3D::next();
+        child.geometry = Some(BoxGeometry::DEFAULT.build());
+        child.position = Vector3::new(0.0, 5.0, 0.0);
+        child.rotate_z(FRAC_PI_4);
+        graph.insert_child(parent, child);
+        graph.update_matrix_world();
+
+        // the unit cube turned 45 degrees reaches half a diagonal out in x and y
+        let half = 0.5f32.sqrt();
+        let precise = Box3::from_object(&graph, parent, true);
+        let expected = Box3::new(&Vector3::new(10.0 - half, 5.0 - half, -0.5),
+                                 &Vector3::new(10.0 + half, 5.0 + half, 0.5));
+        assert!(precise.min.distance_to(&expected.min) < 1e-5);
+        assert!(precise.max.distance_to(&expected.max) < 1e-5);
+
+        // the corners of the cube are its vertices, so both ways agree here
+        let fast = Box3::from_object(&graph, parent, false);
+        assert!(fast.min.distance_to(&expected.min) < 1e-5);
+        assert!(fast.max.distance_to(&expected.max) < 1e-5);
+
+        let box2 = Box2::from_object(&graph, parent, true);
+        assert_eq!(box2.min, Vector2::new(precise.min.x, precise.min.y));
+        assert_eq!(box2.max, Vector2::new(precise.max.x, precise.max.y));
+
+        // but the turned box around a sphere sticks out further than the sphere
+        let mut ball = Object3D::next();
+        ball.geometry = Some(SphereGeometry::DEFAULT.build());
+        ball.rotate_z(FRAC_PI_4);
+        let ball = graph.insert(ball);
+        let empty = graph.insert(Object3D::next());
+        graph.update_matrix_world();
+        assert!(Box3::from_object(&graph, ball, true).max.x <= 1.0 + 1e-5);
+        assert!((Box3::from_object(&graph, ball, false).max.x - 2.0f32.sqrt()).abs() < 1e-5);
+        assert!(Box3::from_object(&graph, empty, true).is_empty());
+        assert_eq!(Box2::from_object(&graph, empty, false), Box2::EMPTY);
+    }
+}
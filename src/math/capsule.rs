@@ -0,0 +1,347 @@
+use math::*;
+
+// a swept sphere, every point within radius of the segment
+#[derive(Debug,PartialEq,Clone)]
+pub struct Capsule {
+    pub segment: Line3,
+    pub radius: f32,
+}
+
+// how far the capsule has to move along normal to stop touching, normal points toward the capsule
+#[derive(Debug,PartialEq,Copy,Clone)]
+pub struct Penetration {
+    pub normal: Vector3,
+    pub depth: f32,
+}
+
+impl Capsule {
+    pub const DEFAULT: Capsule = Capsule {
+        segment: Line3 {
+            start: Vector3::ZERO,
+            end: Vector3::Y,
+        },
+        radius: 1.0,
+    };
+
+    pub fn new(start: &Vector3, end: &Vector3, radius: f32) -> Capsule {
+        Capsule {
+            segment: Line3::new(start, end),
+            radius: radius,
+        }
+    }
+
+    pub fn from_line(line: &Line3, radius: f32) -> Capsule {
+        Capsule {
+            segment: line.clone(),
+            radius: radius,
+        }
+    }
+
+    pub fn center(&self) -> Vector3 {
+        self.segment.center()
+    }
+
+    pub fn translate(&self, offset: &Vector3) -> Capsule {
+        Capsule::new(&self.segment.start.add(offset),
+                     &self.segment.end.add(offset),
+                     self.radius)
+    }
+
+    pub fn bounding_box(&self) -> Box3 {
+        Box3::new(&self.segment.start.min(&self.segment.end),
+                  &self.segment.start.max(&self.segment.end))
+            .expand_by_scalar(self.radius)
+    }
+
+    pub fn distance_to_point(&self, point: &Vector3) -> f32 {
+        self.segment.closest_point_to_point(point, true).distance_to(point) - self.radius
+    }
+
+    pub fn contains_point(&self, point: &Vector3) -> bool {
+        self.distance_to_point(point) <= 0.0
+    }
+
+    pub fn intersect_sphere(&self, sphere: &Sphere) -> Option<Penetration> {
+        let closest = self.segment.closest_point_to_point(&sphere.center, true);
+        penetration(&closest, &sphere.center, self.radius + sphere.radius)
+    }
+
+    pub fn intersect_capsule(&self, capsule: &Capsule) -> Option<Penetration> {
+        let (a, b) = self.segment.closest_points_to_line(&capsule.segment);
+        penetration(&a, &b, self.radius + capsule.radius)
+    }
+
+    // the plane is the surface of a solid half space, anything behind it is inside
+    pub fn intersect_plane(&self, plane: &Plane) -> Option<Penetration> {
+        let deepest = plane.distance_to_point(&self.segment.start)
+            .min(plane.distance_to_point(&self.segment.end));
+
+        if deepest < self.radius {
+            Some(Penetration {
+                normal: plane.normal,
+                depth: self.radius - deepest,
+            })
+        } else {
+            None
+        }
+    }
+
+    pub fn intersect_box(&self, box3: &Box3) -> Option<Penetration> {
+        if box3.is_empty() {
+            return None;
+        }
+
+        // the distance to the box is convex along the segment, so a ternary search finds its
+        // minimum
+        let distance = |t: f32| box3.distance_to_point(&self.segment.at(t));
+        let (mut low, mut high) = (0.0f32, 1.0f32);
+        for _ in 0..48 {
+            let third = (high - low) / 3.0;
+            if distance(low + third) <= distance(high - third) {
+                high -= third;
+            } else {
+                low += third;
+            }
+        }
+
+        let point = self.segment.at((low + high) * 0.5);
+        let closest = box3.clamp_point(&point);
+        if point.distance_to_squared(&closest) > 0.0 {
+            return penetration(&point, &closest, self.radius);
+        }
+
+        // the segment runs through the box, push it out through the cheapest face
+        let (start, end) = (&self.segment.start, &self.segment.end);
+        let mut best: Option<Penetration> = None;
+        for axis in 0..3 {
+            let low_end = start.component(axis).min(*end.component(axis));
+            let high_end = start.component(axis).max(*end.component(axis));
+            let normal = match axis {
+                0 => Vector3::X,
+                1 => Vector3::Y,
+                _ => Vector3::Z,
+            };
+
+            let faces = [(normal, box3.max.component(axis) - low_end),
+                         (normal.negate(), high_end - box3.min.component(axis))];
+            for &(normal, push) in &faces {
+                let depth = push + self.radius;
+                let better = match best {
+                    Some(ref b) => depth < b.depth,
+                    None => true,
+                };
+                if better {
+                    best = Some(Penetration {
+                        normal: normal,
+                        depth: depth,
+                    });
+                }
+            }
+        }
+        best
+    }
+
+    // triangles are one sided like level geometry, a capsule entirely behind one doesn't touch
+    // it and one that passes through it is pushed out of the front face
+    pub fn intersect_triangle(&self, triangle: &Triangle) -> Option<Penetration> {
+        let plane = triangle.plane();
+        let d0 = plane.distance_to_point(&self.segment.start);
+        let d1 = plane.distance_to_point(&self.segment.end);
+        if d0.min(d1) > self.radius || d0.max(d1) < 0.0 {
+            return None;
+        }
+
+        // the closest pair is either an end projected into the face, or on one of the edges
+        let mut pairs = Vec::with_capacity(6);
+        for &(end, d) in &[(self.segment.start, d0), (self.segment.end, d1)] {
+            let projected = plane.project_point(&end);
+            if d >= 0.0 && triangle.contains_point(&projected) {
+                pairs.push((end, projected));
+            }
+        }
+        for edge in &[Line3::new(&triangle.a, &triangle.b),
+                      Line3::new(&triangle.b, &triangle.c),
+                      Line3::new(&triangle.c, &triangle.a)] {
+            pairs.push(self.segment.closest_points_to_line(edge));
+        }
+
+        // the segment crossing the face inside the triangle
+        if d0 * d1 < 0.0 {
+            let crossing = self.segment.at(d0 / (d0 - d1));
+            if triangle.contains_point(&crossing) {
+                pairs.push((crossing, crossing));
+            }
+        }
+
+        let mut closest = pairs[0];
+        for pair in &pairs[1..] {
+            if pair.0.distance_to_squared(&pair.1) < closest.0.distance_to_squared(&closest.1) {
+                closest = *pair;
+            }
+        }
+
+        let distance = closest.0.distance_to(&closest.1);
+        if distance >= self.radius {
+            None
+        } else if distance > 1e-6 {
+            penetration(&closest.0, &closest.1, self.radius)
+        } else {
+            Some(Penetration {
+                normal: plane.normal,
+                depth: self.radius - d0.min(d1),
+            })
+        }
+    }
+}
+
+// pushes point a out to distance from b
+fn penetration(a: &Vector3, b: &Vector3, distance: f32) -> Option<Penetration> {
+    let d = a.subtract(b);
+    let length = d.length();
+    if length >= distance {
+        None
+    } else {
+        Some(Penetration {
+            // centers on top of each other, any direction will do
+            normal: if length > 0.0 {
+                d.divide_scalar(length)
+            } else {
+                Vector3::Y
+            },
+            depth: distance - length,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use math::*;
+
+    fn close(a: &Vector3, b: &Vector3) -> bool {
+        a.distance_to(b) < 0.0001
+    }
+
+    // a standing character, feet at the origin
+    fn player() -> Capsule {
+        Capsule::new(&Vector3::new(0.0, 0.35, 0.0), &Vector3::new(0.0, 1.0, 0.0), 0.35)
+    }
+
+    #[test]
+    fn constructor() {
+        let a = player();
+        assert_eq!(a.center(), Vector3::new(0.0, 0.675, 0.0));
+        assert_eq!(a.bounding_box(),
+                   Box3::new(&Vector3::new(-0.35, 0.0, -0.35), &Vector3::new(0.35, 1.35, 0.35)));
+        assert!(a.contains_point(&Vector3::new(0.3, 0.7, 0.0)));
+        assert!(!a.contains_point(&Vector3::new(0.3, 0.05, 0.3)));
+        assert!((a.distance_to_point(&Vector3::new(0.0, 2.0, 0.0)) - 0.65).abs() < 0.0001);
+        assert_eq!(Capsule::from_line(&Line3::new(&Vector3::ZERO, &Vector3::Y), 1.0),
+                   Capsule::DEFAULT);
+        assert_eq!(a.translate(&Vector3::X).segment.end, Vector3::new(1.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn intersect_sphere_capsule() {
+        let a = player();
+        let hit = a.intersect_sphere(&Sphere::new(&Vector3::new(0.5, 0.7, 0.0), 0.25)).unwrap();
+        assert!(close(&hit.normal, &Vector3::NEG_X));
+        assert!((hit.depth - 0.1).abs() < 0.0001);
+        assert!(a.intersect_sphere(&Sphere::new(&Vector3::new(0.7, 0.7, 0.0), 0.25)).is_none());
+
+        // crossed segments, closest between the middles
+        let b = Capsule::new(&Vector3::new(-1.0, 0.7, 0.5), &Vector3::new(1.0, 0.7, 0.5), 0.25);
+        let hit = a.intersect_capsule(&b).unwrap();
+        assert!(close(&hit.normal, &Vector3::NEG_Z));
+        assert!((hit.depth - 0.1).abs() < 0.0001);
+        assert!(a.intersect_capsule(&b.translate(&Vector3::new(0.0, 0.0, 0.2))).is_none());
+
+        // end to end along the axis
+        let above = Capsule::new(&Vector3::new(0.0, 1.5, 0.0), &Vector3::new(0.0, 3.0, 0.0), 0.2);
+        let hit = a.intersect_capsule(&above).unwrap();
+        assert!(close(&hit.normal, &Vector3::NEG_Y));
+        assert!((hit.depth - 0.05).abs() < 0.0001);
+    }
+
+    #[test]
+    fn intersect_plane() {
+        let a = player();
+        let floor = Plane::new(&Vector3::Y, -0.1);
+        let hit = a.intersect_plane(&floor).unwrap();
+        assert_eq!(hit.normal, Vector3::Y);
+        assert!((hit.depth - 0.1).abs() < 0.0001);
+        assert!(a.intersect_plane(&Plane::new(&Vector3::Y, 0.1)).is_none());
+
+        // lying across a wall, the deep end decides
+        let lying = Capsule::new(&Vector3::new(-0.5, 1.0, 0.0), &Vector3::new(1.0, 1.0, 0.0), 0.25);
+        let hit = lying.intersect_plane(&Plane::new(&Vector3::NEG_X, 0.0)).unwrap();
+        assert!((hit.depth - 1.25).abs() < 0.0001);
+    }
+
+    #[test]
+    fn intersect_box() {
+        let a = player();
+        let crate_box = Box3::new(&Vector3::new(0.2, 0.0, -1.0), &Vector3::new(2.0, 0.5, 1.0));
+        let hit = a.intersect_box(&crate_box).unwrap();
+        assert!(close(&hit.normal, &Vector3::NEG_X));
+        assert!((hit.depth - 0.15).abs() < 0.001);
+
+        // stepping onto the corner from above
+        let corner = Box3::new(&Vector3::new(0.1, -1.0, 0.1), &Vector3::new(1.0, 0.5, 1.0));
+        let hit = a.intersect_box(&corner).unwrap();
+        assert!(close(&hit.normal, &Vector3::new(-0.5f32.sqrt(), 0.0, -0.5f32.sqrt())));
+
+        assert!(a.intersect_box(&crate_box.translate(&Vector3::new(0.2, 0.0, 0.0))).is_none());
+        assert!(a.intersect_box(&Box3::EMPTY).is_none());
+
+        // sunk into the floor, the top face is the way out
+        let floor = Box3::new(&Vector3::new(-5.0, -1.0, -5.0), &Vector3::new(5.0, 0.5, 5.0));
+        let hit = a.intersect_box(&floor).unwrap();
+        assert_eq!(hit.normal, Vector3::Y);
+        assert!((hit.depth - 0.5).abs() < 0.0001);
+    }
+
+    #[test]
+    fn intersect_triangle() {
+        let a = player();
+        let floor = Triangle::new(&Vector3::new(-5.0, 0.1, 5.0),
+                                  &Vector3::new(5.0, 0.1, 5.0),
+                                  &Vector3::new(0.0, 0.1, -5.0));
+        let hit = a.intersect_triangle(&floor).unwrap();
+        assert!(close(&hit.normal, &Vector3::Y));
+        assert!((hit.depth - 0.1).abs() < 0.0001);
+
+        // the floor from below doesn't count
+        let flipped = Triangle::new(&floor.a, &floor.c, &floor.b);
+        assert!(a.intersect_triangle(&flipped).is_none());
+
+        // standing next to a wall facing +x
+        let wall = Triangle::new(&Vector3::new(-0.3, 0.0, 0.2),
+                                 &Vector3::new(-0.3, 0.0, -5.0),
+                                 &Vector3::new(-0.3, 3.0, 0.2));
+        let hit = a.intersect_triangle(&wall).unwrap();
+        assert!(close(&hit.normal, &Vector3::X));
+        assert!((hit.depth - 0.05).abs() < 0.0001);
+        let further = Triangle::new(&Vector3::new(-0.4, 0.0, 0.2),
+                                    &Vector3::new(-0.4, 0.0, -5.0),
+                                    &Vector3::new(-0.4, 3.0, 0.2));
+        assert!(a.intersect_triangle(&further).is_none());
+
+        // the edge of a ramp catching the capsule's side
+        let ramp = Triangle::new(&Vector3::new(0.15, 0.7, -1.0),
+                                 &Vector3::new(0.15, 0.7, 1.0),
+                                 &Vector3::new(2.0, 0.0, 0.0));
+        let hit = a.intersect_triangle(&ramp).unwrap();
+        assert!(close(&hit.normal, &Vector3::NEG_X));
+        assert!((hit.depth - 0.2).abs() < 0.0001);
+        let far = Triangle::new(&Vector3::new(0.15, 0.7, 2.0),
+                                &Vector3::new(0.15, 0.7, 1.0),
+                                &Vector3::new(2.0, 0.0, 0.0));
+        assert!(a.intersect_triangle(&far).is_none());
+
+        // speared through the middle
+        let spear = Capsule::new(&Vector3::new(0.0, -0.5, 0.0), &Vector3::new(0.0, 1.0, 0.0), 0.2);
+        let hit = spear.intersect_triangle(&floor).unwrap();
+        assert!(close(&hit.normal, &Vector3::Y));
+        assert!((hit.depth - 0.8).abs() < 0.0001);
+    }
+}
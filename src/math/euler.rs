@@ -10,7 +10,7 @@ pub enum EulerOrder {
     ZYX,
 }
 
-#[derive(Debug,PartialEq)]
+#[derive(Debug,PartialEq,Clone)]
 pub struct Euler {
     pub x: f32,
     pub y: f32,
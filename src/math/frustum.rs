@@ -50,10 +50,10 @@ impl Frustum {
             Some(ref geometry) => {
                 let bounding_sphere = match geometry.bounding_sphere {
                     None => geometry.compute_bounding_sphere(),
-                    Some(ref bs) => bs.apply_matrix4(&scene_object.matrix_world), 
+                    Some(bs) => bs,
                 };
 
-                self.intersects_sphere(&bounding_sphere)
+                self.intersects_sphere(&bounding_sphere.apply_matrix4(&scene_object.matrix_world))
             }
         }
     }
@@ -58,6 +58,59 @@ impl Line3 {
         self.delta().multiply_scalar(t).add(&self.start)
     }
 
+    // parameters of the closest points on the two segments, see Ericson's Real-Time Collision
+    // Detection 5.1.9
+    pub fn closest_points_to_line_parameters(&self, line: &Line3) -> (f32, f32) {
+        let d1 = self.delta();
+        let d2 = line.delta();
+        let r = self.start.subtract(&line.start);
+        let aa = d1.dot(&d1);
+        let ee = d2.dot(&d2);
+        let f = d2.dot(&r);
+
+        if aa <= 1e-12 && ee <= 1e-12 {
+            return (0.0, 0.0);
+        }
+        if aa <= 1e-12 {
+            return (0.0, clamp(f / ee, 0.0, 1.0));
+        }
+
+        let c = d1.dot(&r);
+        if ee <= 1e-12 {
+            return (clamp(-c / aa, 0.0, 1.0), 0.0);
+        }
+
+        let bb = d1.dot(&d2);
+        let denominator = aa * ee - bb * bb;
+
+        // parallel segments have no unique pair, any s will do
+        let s = if denominator > 0.0 {
+            clamp((bb * f - c * ee) / denominator, 0.0, 1.0)
+        } else {
+            0.0
+        };
+
+        let t = (bb * s + f) / ee;
+        if t < 0.0 {
+            (clamp(-c / aa, 0.0, 1.0), 0.0)
+        } else if t > 1.0 {
+            (clamp((bb - c) / aa, 0.0, 1.0), 1.0)
+        } else {
+            (s, t)
+        }
+    }
+
+    // (PointOnSelf,PointOnLine)
+    pub fn closest_points_to_line(&self, line: &Line3) -> (Vector3, Vector3) {
+        let (s, t) = self.closest_points_to_line_parameters(line);
+        (self.at(s), line.at(t))
+    }
+
+    pub fn distance_to_line(&self, line: &Line3) -> f32 {
+        let (a, b) = self.closest_points_to_line(line);
+        a.distance_to(&b)
+    }
+
     pub fn apply_matrix4(&self, matrix: &Matrix4) -> Line3 {
         Line3::new(&self.start.apply_matrix4(matrix),
                    &self.end.apply_matrix4(matrix))
@@ -92,6 +145,34 @@ mod tests {
         assert!(a.at(2.0).distance_to(&Vector3::new(1.0, 1.0, 3.0)) < threshold);
     }
 
+    #[test]
+    fn closest_points_to_line() {
+        let a = Line3::new(&Vector3::ZERO, &Vector3::new(2.0, 0.0, 0.0));
+
+        // crossing above the middle
+        let b = Line3::new(&Vector3::new(1.0, 1.0, -1.0), &Vector3::new(1.0, 1.0, 1.0));
+        assert_eq!(a.closest_points_to_line_parameters(&b), (0.5, 0.5));
+        assert_eq!(a.closest_points_to_line(&b),
+                   (Vector3::new(1.0, 0.0, 0.0), Vector3::new(1.0, 1.0, 0.0)));
+        assert_eq!(a.distance_to_line(&b), 1.0);
+
+        // both clamped to an end
+        let c = Line3::new(&Vector3::new(3.0, 1.0, 0.0), &Vector3::new(5.0, 3.0, 0.0));
+        let (p, q) = a.closest_points_to_line(&c);
+        assert_eq!(p, Vector3::new(2.0, 0.0, 0.0));
+        assert_eq!(q, Vector3::new(3.0, 1.0, 0.0));
+
+        // parallel, overlapping
+        let d = Line3::new(&Vector3::new(1.0, 2.0, 0.0), &Vector3::new(4.0, 2.0, 0.0));
+        assert!((a.distance_to_line(&d) - 2.0).abs() < 1e-6);
+        assert!((d.distance_to_line(&a) - 2.0).abs() < 1e-6);
+
+        // a point against a segment
+        let e = Line3::new(&Vector3::new(1.5, -1.0, 0.0), &Vector3::new(1.5, -1.0, 0.0));
+        assert_eq!(a.closest_points_to_line(&e).0, Vector3::new(1.5, 0.0, 0.0));
+        assert_eq!(e.closest_points_to_line(&a).1, Vector3::new(1.5, 0.0, 0.0));
+    }
+
     #[test]
     fn closest_point_to_point_closest_point_to_point_parameter() {
         let a = Line3::new(&Vector3::ONE, &Vector3::new(1.0, 1.0, 2.0));
@@ -1,6 +1,6 @@
 use math::Matrix4;
 
-#[derive(Debug,PartialEq)]
+#[derive(Debug,PartialEq,Copy,Clone)]
 pub struct Matrix3 {
     pub elements: [f32; 9],
 }
@@ -40,9 +40,22 @@ impl Matrix3 {
 
     pub fn from_matrix4(m: &Matrix4) -> Matrix3 {
         let me = m.elements;
-        Matrix3 { elements: [me[0], me[4], me[8], me[1], me[5], me[9], me[2], me[6], me[10]] }
+        Matrix3 { elements: [me[0], me[1], me[2], me[4], me[5], me[6], me[8], me[9], me[10]] }
     }
 
+    pub fn multiply(&self, b: &Matrix3) -> Matrix3 {
+        let ae = self.elements;
+        let be = b.elements;
+        let mut elements = [0.0; 9];
+        for column in 0..3 {
+            for row in 0..3 {
+                elements[column * 3 + row] = ae[row] * be[column * 3] +
+                                             ae[3 + row] * be[column * 3 + 1] +
+                                             ae[6 + row] * be[column * 3 + 2];
+            }
+        }
+        Matrix3 { elements: elements }
+    }
 
     pub fn multiply_scalar(&self, s: f32) -> Matrix3 {
         Matrix3 {
@@ -475,33 +475,33 @@ impl Matrix4 {
     }
 
     pub fn from_translation(v: &Vector3) -> Matrix4 {
-        Matrix4::from_rows((1.0, 0.0, 0.0, 0.0),
-                           (0.0, 1.0, 0.0, 0.0),
-                           (0.0, 0.0, 1.0, 0.0),
-                           (v.x, v.y, v.z, 1.0))
+        Matrix4::from_rows((1.0, 0.0, 0.0, v.x),
+                           (0.0, 1.0, 0.0, v.y),
+                           (0.0, 0.0, 1.0, v.z),
+                           (0.0, 0.0, 0.0, 1.0))
     }
 
     pub fn from_rotation_x(theta: f32) -> Matrix4 {
         let (c, s) = (theta.cos(), theta.sin());
         Matrix4::from_rows((1.0, 0.0, 0.0, 0.0),
-                           (0.0, c, s, 0.0),
-                           (0.0, -s, c, 0.0),
+                           (0.0, c, -s, 0.0),
+                           (0.0, s, c, 0.0),
                            (0.0, 0.0, 0.0, 1.0))
     }
 
     pub fn from_rotation_y(theta: f32) -> Matrix4 {
         let (c, s) = (theta.cos(), theta.sin());
-        Matrix4::from_rows((c, 0.0, -s, 0.0),
+        Matrix4::from_rows((c, 0.0, s, 0.0),
                            (0.0, 1.0, 0.0, 0.0),
-                           (s, 0.0, c, 0.0),
+                           (-s, 0.0, c, 0.0),
                            (0.0, 0.0, 0.0, 1.0))
     }
 
 
     pub fn from_rotation_z(theta: f32) -> Matrix4 {
         let (c, s) = (theta.cos(), theta.sin());
-        Matrix4::from_rows((c, s, 0.0, 0.0),
-                           (-s, c, 0.0, 0.0),
+        Matrix4::from_rows((c, -s, 0.0, 0.0),
+                           (s, c, 0.0, 0.0),
                            (0.0, 0.0, 1.0, 0.0),
                            (0.0, 0.0, 0.0, 1.0))
     }
@@ -513,10 +513,10 @@ impl Matrix4 {
         let &Vector3 { x, y, z } = axis;
         let (tx, ty) = (t * x, t * y);
 
-        Matrix4::from_columns((tx * x + c, tx * y - s * z, tx * z + s * y, 0.0),
-                              (tx * y + s * z, ty * y + c, ty * z - s * x, 0.0),
-                              (tx * z - s * y, ty * z + s * x, t * z * z + c, 0.0),
-                              (0.0, 0.0, 0.0, 1.0))
+        Matrix4::from_rows((tx * x + c, tx * y - s * z, tx * z + s * y, 0.0),
+                           (tx * y + s * z, ty * y + c, ty * z - s * x, 0.0),
+                           (tx * z - s * y, ty * z + s * x, t * z * z + c, 0.0),
+                           (0.0, 0.0, 0.0, 1.0))
     }
 
     pub fn from_scale(v: &Vector3) -> Matrix4 {
@@ -590,8 +590,9 @@ impl Matrix4 {
                               (0.0, 0.0, d, 0.0))
     }
 
+    // fov is the vertical field of view in degrees
     pub fn from_perspective(fov: f32, aspect: f32, near: f32, far: f32) -> Matrix4 {
-        let ymax = near * (fov.to_degrees() * 0.5).tan();
+        let ymax = near * (fov.to_radians() * 0.5).tan();
         let ymin = -ymax;
         let xmin = ymin * aspect;
         let xmax = ymax * aspect;
@@ -615,7 +616,7 @@ impl Matrix4 {
         let z = (far + near) * p;
 
         Matrix4::from_columns((2.0 * w, 0.0, 0.0, 0.0),
-                              (0.0, h, 0.0, 0.0),
+                              (0.0, 2.0 * h, 0.0, 0.0),
                               (0.0, 0.0, -2.0 * p, 0.0),
                               (-x, -y, -z, 1.0))
     }
@@ -1,11 +1,13 @@
 mod box2;
 mod box3;
+mod capsule;
 mod color;
 mod euler;
 mod frustum;
 mod line3;
 mod matrix3;
 mod matrix4;
+mod obb;
 mod plane;
 mod quaternion;
 mod ray;
@@ -15,16 +17,19 @@ mod spline;
 mod triangle;
 mod vector2;
 mod vector3;
+mod vector4;
 mod utils;
 
 pub use self::box2::*;
 pub use self::box3::*;
+pub use self::capsule::*;
 pub use self::color::*;
 pub use self::euler::*;
 pub use self::frustum::*;
 pub use self::line3::*;
 pub use self::matrix3::*;
 pub use self::matrix4::*;
+pub use self::obb::*;
 pub use self::plane::*;
 pub use self::quaternion::*;
 pub use self::ray::*;
@@ -34,4 +39,5 @@ pub use self::spline::Spline;
 pub use self::triangle::Triangle;
 pub use self::vector2::*;
 pub use self::vector3::*;
+pub use self::vector4::*;
 pub use self::utils::*;
\ No newline at end of file
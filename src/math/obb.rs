@@ -0,0 +1,448 @@
+use std::f32::EPSILON;
+use math::*;
+
+// oriented bounding box, the columns of rotation are the box axes in world space
+#[derive(Debug,PartialEq,Copy,Clone)]
+pub struct OBB {
+    pub center: Vector3,
+    pub half_size: Vector3,
+    pub rotation: Matrix3,
+}
+
+impl OBB {
+    pub const DEFAULT: OBB = OBB {
+        center: Vector3::ZERO,
+        half_size: Vector3::ZERO,
+        rotation: Matrix3::IDENTITY,
+    };
+
+    pub fn new(center: &Vector3, half_size: &Vector3, rotation: &Matrix3) -> OBB {
+        OBB {
+            center: *center,
+            half_size: *half_size,
+            rotation: *rotation,
+        }
+    }
+
+    pub fn from_box3(box3: &Box3) -> OBB {
+        if box3.is_empty() {
+            return OBB::DEFAULT;
+        }
+
+        OBB::new(&box3.center(),
+                 &box3.size().multiply_scalar(0.5),
+                 &Matrix3::IDENTITY)
+    }
+
+    pub fn from_box3_and_matrix4(box3: &Box3, matrix: &Matrix4) -> OBB {
+        OBB::from_box3(box3).apply_matrix4(matrix)
+    }
+
+    // principal component analysis, the axes are the eigenvectors of the covariance of the points
+    pub fn from_points(points: &[&Vector3]) -> OBB {
+        if points.is_empty() {
+            return OBB::DEFAULT;
+        }
+
+        let count = points.len() as f32;
+        let mean = points.iter().fold(Vector3::ZERO, |sum, p| sum.add(p)).divide_scalar(count);
+
+        let mut covariance = [[0.0f32; 3]; 3];
+        for point in points {
+            let d = point.subtract(&mean);
+            let d = [d.x, d.y, d.z];
+            for row in 0..3 {
+                for column in 0..3 {
+                    covariance[row][column] += d[row] * d[column] / count;
+                }
+            }
+        }
+
+        let v = eigenvectors(covariance);
+        let mut axes = [Vector3::new(v[0][0], v[1][0], v[2][0]).normalized(),
+                        Vector3::new(v[0][1], v[1][1], v[2][1]).normalized(),
+                        Vector3::ZERO];
+        axes[2] = axes[0].cross(&axes[1]);
+
+        let mut min = Vector3::INFINITY;
+        let mut max = Vector3::NEG_INFINITY;
+        for point in points {
+            let d = point.subtract(&mean);
+            let local = Vector3::new(d.dot(&axes[0]), d.dot(&axes[1]), d.dot(&axes[2]));
+            min = min.min(&local);
+            max = max.max(&local);
+        }
+
+        let middle = min.add(&max).multiply_scalar(0.5);
+        let center = mean.add(&axes[0].multiply_scalar(middle.x))
+            .add(&axes[1].multiply_scalar(middle.y))
+            .add(&axes[2].multiply_scalar(middle.z));
+
+        OBB::new(&center,
+                 &max.subtract(&min).multiply_scalar(0.5),
+                 &Matrix3::from_columns((axes[0].x, axes[0].y, axes[0].z),
+                                        (axes[1].x, axes[1].y, axes[1].z),
+                                        (axes[2].x, axes[2].y, axes[2].z)))
+    }
+
+    pub fn axes(&self) -> [Vector3; 3] {
+        let e = self.rotation.elements;
+        [Vector3::new(e[0], e[1], e[2]),
+         Vector3::new(e[3], e[4], e[5]),
+         Vector3::new(e[6], e[7], e[8])]
+    }
+
+    pub fn size(&self) -> Vector3 {
+        self.half_size.multiply_scalar(2.0)
+    }
+
+    // point in the box's frame, relative to its center
+    pub fn to_local(&self, point: &Vector3) -> Vector3 {
+        let d = point.subtract(&self.center);
+        let axes = self.axes();
+        Vector3::new(d.dot(&axes[0]), d.dot(&axes[1]), d.dot(&axes[2]))
+    }
+
+    pub fn to_world(&self, local: &Vector3) -> Vector3 {
+        let axes = self.axes();
+        self.center
+            .add(&axes[0].multiply_scalar(local.x))
+            .add(&axes[1].multiply_scalar(local.y))
+            .add(&axes[2].multiply_scalar(local.z))
+    }
+
+    pub fn contains_point(&self, point: &Vector3) -> bool {
+        let local = self.to_local(point);
+        local.x.abs() <= self.half_size.x && local.y.abs() <= self.half_size.y &&
+        local.z.abs() <= self.half_size.z
+    }
+
+    // closest point on or in the box
+    pub fn clamp_point(&self, point: &Vector3) -> Vector3 {
+        let local = self.to_local(point).clamp(&self.half_size.negate(), &self.half_size);
+        self.to_world(&local)
+    }
+
+    pub fn distance_to_point(&self, point: &Vector3) -> f32 {
+        self.clamp_point(point).distance_to(point)
+    }
+
+    // separating axis test over the 15 candidate axes, see Ericson's Real-Time Collision Detection
+    pub fn intersects_obb(&self, obb: &OBB) -> bool {
+        let a = self.axes();
+        let b = obb.axes();
+        let ah = [self.half_size.x, self.half_size.y, self.half_size.z];
+        let bh = [obb.half_size.x, obb.half_size.y, obb.half_size.z];
+
+        // b expressed in a's frame, with an epsilon so parallel edges don't give a zero cross
+        let mut r = [[0.0f32; 3]; 3];
+        let mut abs_r = [[0.0f32; 3]; 3];
+        for i in 0..3 {
+            for j in 0..3 {
+                r[i][j] = a[i].dot(&b[j]);
+                abs_r[i][j] = r[i][j].abs() + EPSILON;
+            }
+        }
+
+        let d = obb.center.subtract(&self.center);
+        let t = [d.dot(&a[0]), d.dot(&a[1]), d.dot(&a[2])];
+
+        for i in 0..3 {
+            let rb = bh[0] * abs_r[i][0] + bh[1] * abs_r[i][1] + bh[2] * abs_r[i][2];
+            if t[i].abs() > ah[i] + rb {
+                return false;
+            }
+        }
+
+        for j in 0..3 {
+            let ra = ah[0] * abs_r[0][j] + ah[1] * abs_r[1][j] + ah[2] * abs_r[2][j];
+            let tb = t[0] * r[0][j] + t[1] * r[1][j] + t[2] * r[2][j];
+            if tb.abs() > ra + bh[j] {
+                return false;
+            }
+        }
+
+        // a[i] x b[j]
+        for i in 0..3 {
+            let (i1, i2) = ((i + 1) % 3, (i + 2) % 3);
+            for j in 0..3 {
+                let (j1, j2) = ((j + 1) % 3, (j + 2) % 3);
+                let ra = ah[i1] * abs_r[i2][j] + ah[i2] * abs_r[i1][j];
+                let rb = bh[j1] * abs_r[i][j2] + bh[j2] * abs_r[i][j1];
+                if (t[i2] * r[i1][j] - t[i1] * r[i2][j]).abs() > ra + rb {
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+
+    pub fn intersects_box(&self, box3: &Box3) -> bool {
+        !box3.is_empty() && self.intersects_obb(&OBB::from_box3(box3))
+    }
+
+    pub fn intersects_sphere(&self, sphere: &Sphere) -> bool {
+        self.clamp_point(&sphere.center).distance_to_squared(&sphere.center) <=
+        sphere.radius * sphere.radius
+    }
+
+    pub fn intersects_plane(&self, plane: &Plane) -> bool {
+        plane.distance_to_point(&self.center).abs() <= self.projected_radius(&plane.normal)
+    }
+
+    // the box is axis aligned in its own frame
+    pub fn intersects_triangle(&self, triangle: &Triangle) -> bool {
+        Triangle::new(&self.to_local(&triangle.a),
+                      &self.to_local(&triangle.b),
+                      &self.to_local(&triangle.c))
+            .intersects_box(&Box3::new(&self.half_size.negate(), &self.half_size))
+    }
+
+    // first point where the ray enters the box, the origin when it starts inside
+    pub fn intersect_ray(&self, ray: &Ray) -> Option<Vector3> {
+        let axes = self.axes();
+        let local = Ray {
+            origin: self.to_local(&ray.origin),
+            direction: Vector3::new(ray.direction.dot(&axes[0]),
+                                    ray.direction.dot(&axes[1]),
+                                    ray.direction.dot(&axes[2])),
+        };
+
+        local.distance_to_box(&Box3::new(&self.half_size.negate(), &self.half_size))
+            .map(|t| ray.at(t))
+    }
+
+    pub fn intersects_ray(&self, ray: &Ray) -> bool {
+        self.intersect_ray(ray).is_some()
+    }
+
+    pub fn bounding_box(&self) -> Box3 {
+        let a = self.axes();
+        let h = self.half_size;
+        let extents = Vector3::new(h.x * a[0].x.abs() + h.y * a[1].x.abs() + h.z * a[2].x.abs(),
+                                   h.x * a[0].y.abs() + h.y * a[1].y.abs() + h.z * a[2].y.abs(),
+                                   h.x * a[0].z.abs() + h.y * a[1].z.abs() + h.z * a[2].z.abs());
+        Box3::new(&self.center.subtract(&extents), &self.center.add(&extents))
+    }
+
+    // scale is pulled out of the matrix into the half size, so the axes stay orthonormal
+    pub fn apply_matrix4(&self, matrix: &Matrix4) -> OBB {
+        let scale = Vector3::from_matrix_scale(matrix);
+        let column = |index: usize, s: f32| {
+            let c = Vector3::from_matrix_column(matrix, index);
+            if s > 0.0 { c.divide_scalar(s) } else { c }
+        };
+        let x = column(0, scale.x);
+        let y = column(1, scale.y);
+        let z = column(2, scale.z);
+        let rotation = Matrix3::from_columns((x.x, x.y, x.z), (y.x, y.y, y.z), (z.x, z.y, z.z));
+
+        OBB {
+            center: self.center.apply_matrix4(matrix),
+            half_size: self.half_size.multiply(&scale),
+            rotation: rotation.multiply(&self.rotation),
+        }
+    }
+
+    pub fn translate(&self, offset: &Vector3) -> OBB {
+        OBB { center: self.center.add(offset), ..*self }
+    }
+
+    // half the length of the box projected onto axis
+    fn projected_radius(&self, axis: &Vector3) -> f32 {
+        let a = self.axes();
+        self.half_size.x * a[0].dot(axis).abs() + self.half_size.y * a[1].dot(axis).abs() +
+        self.half_size.z * a[2].dot(axis).abs()
+    }
+}
+
+// cyclic jacobi rotations on a symmetric matrix, the columns of the result are the eigenvectors
+fn eigenvectors(matrix: [[f32; 3]; 3]) -> [[f32; 3]; 3] {
+    let mut a = matrix;
+    let mut v = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+
+    for _ in 0..32 {
+        // largest off diagonal element
+        let (p, q) = if a[0][1].abs() >= a[0][2].abs() && a[0][1].abs() >= a[1][2].abs() {
+            (0, 1)
+        } else if a[0][2].abs() >= a[1][2].abs() {
+            (0, 2)
+        } else {
+            (1, 2)
+        };
+
+        let scale = a[p][p].abs() + a[q][q].abs();
+        if a[p][q].abs() <= 1e-9 || a[p][q].abs() <= scale * 1e-7 {
+            break;
+        }
+
+        let theta = (a[q][q] - a[p][p]) / (2.0 * a[p][q]);
+        let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+        let c = 1.0 / (t * t + 1.0).sqrt();
+        let s = t * c;
+
+        for k in 0..3 {
+            let (kp, kq) = (a[k][p], a[k][q]);
+            a[k][p] = c * kp - s * kq;
+            a[k][q] = s * kp + c * kq;
+        }
+        for k in 0..3 {
+            let (pk, qk) = (a[p][k], a[q][k]);
+            a[p][k] = c * pk - s * qk;
+            a[q][k] = s * pk + c * qk;
+        }
+        for row in &mut v {
+            let (kp, kq) = (row[p], row[q]);
+            row[p] = c * kp - s * kq;
+            row[q] = s * kp + c * kq;
+        }
+    }
+
+    v
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+    use math::*;
+
+    fn close(a: &Vector3, b: &Vector3) -> bool {
+        a.distance_to(b) < 0.0001
+    }
+
+    // unit cube at 2,0,0 turned 45 degrees around z
+    fn diamond() -> OBB {
+        let box3 = Box3::new(&Vector3::new(-1.0, -1.0, -1.0), &Vector3::ONE);
+        let matrix = Matrix4::from_translation(&Vector3::new(2.0, 0.0, 0.0))
+            .multiply(&Matrix4::from_rotation_z(PI / 4.0));
+        OBB::from_box3_and_matrix4(&box3, &matrix)
+    }
+
+    #[test]
+    fn from_box3_and_matrix4() {
+        let obb = diamond();
+        assert!(close(&obb.center, &Vector3::new(2.0, 0.0, 0.0)));
+        assert!(close(&obb.half_size, &Vector3::ONE));
+
+        let scaled = OBB::from_box3_and_matrix4(&Box3::new(&Vector3::ZERO, &Vector3::ONE),
+                                                &Matrix4::from_scale(&Vector3::new(2.0, 4.0,
+                                                                                   6.0)));
+        assert!(close(&scaled.center, &Vector3::new(1.0, 2.0, 3.0)));
+        assert!(close(&scaled.half_size, &Vector3::new(1.0, 2.0, 3.0)));
+        assert_eq!(scaled.rotation, Matrix3::IDENTITY);
+
+        let corner = 2.0f32.sqrt();
+        let bounds = obb.bounding_box();
+        assert!(close(&bounds.min, &Vector3::new(2.0 - corner, -corner, -1.0)));
+        assert!(close(&bounds.max, &Vector3::new(2.0 + corner, corner, 1.0)));
+    }
+
+    #[test]
+    fn contains_point() {
+        let obb = diamond();
+        assert!(obb.contains_point(&Vector3::new(2.0, 1.3, 0.0)));
+        assert!(!obb.contains_point(&Vector3::new(2.9, 0.9, 0.0)));
+        assert!(!obb.contains_point(&Vector3::new(2.0, 0.0, 1.1)));
+
+        // straight out along the diagonal lands on the face
+        let p = obb.clamp_point(&Vector3::new(4.0, 2.0, 0.0));
+        let face = 0.5f32.sqrt();
+        assert!(close(&p, &Vector3::new(2.0 + face, face, 0.0)));
+        assert_eq!(obb.clamp_point(&obb.center), obb.center);
+        assert!((obb.distance_to_point(&Vector3::new(2.0, 0.0, 3.0)) - 2.0).abs() < 0.0001);
+    }
+
+    #[test]
+    fn from_points() {
+        // a thin stick along x = y, which an axis aligned box fits badly
+        let rotation = Matrix4::from_rotation_z(PI / 4.0);
+        let points: Vec<Vector3> = [(-4.0, -0.5, -0.25), (4.0, 0.5, 0.25), (-4.0, 0.5, 0.25),
+                                    (4.0, -0.5, -0.25), (-4.0, -0.5, 0.25), (4.0, 0.5, -0.25),
+                                    (-4.0, 0.5, -0.25), (4.0, -0.5, 0.25)]
+            .iter()
+            .map(|&(x, y, z)| Vector3::new(x, y, z).apply_matrix4(&rotation))
+            .collect();
+        let refs: Vec<&Vector3> = points.iter().collect();
+        let obb = OBB::from_points(&refs);
+
+        assert!(close(&obb.center, &Vector3::ZERO));
+        let mut half: Vec<f32> = vec![obb.half_size.x, obb.half_size.y, obb.half_size.z];
+        half.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert!((half[0] - 0.25).abs() < 0.001);
+        assert!((half[1] - 0.5).abs() < 0.001);
+        assert!((half[2] - 4.0).abs() < 0.001);
+        assert!((obb.rotation.determinant() - 1.0).abs() < 0.0001);
+
+        for p in &points {
+            assert!(obb.distance_to_point(p) < 0.001);
+        }
+        assert!(!obb.contains_point(&Vector3::new(2.0, -2.0, 0.0)));
+
+        assert_eq!(OBB::from_points(&[]), OBB::DEFAULT);
+        let single = OBB::from_points(&[&Vector3::ONE]);
+        assert_eq!(single.center, Vector3::ONE);
+        assert_eq!(single.half_size, Vector3::ZERO);
+    }
+
+    #[test]
+    fn intersects() {
+        let obb = diamond();
+
+        // the box's corner reaches x = 2 + sqrt(2), its face only 2 + 1 along the axes
+        let near = Box3::new(&Vector3::new(3.3, -0.1, -0.1), &Vector3::new(4.0, 0.1, 0.1));
+        let far = Box3::new(&Vector3::new(3.5, -0.1, -0.1), &Vector3::new(4.0, 0.1, 0.1));
+        let off_corner = Box3::new(&Vector3::new(3.0, 0.9, -0.1), &Vector3::new(3.5, 1.5, 0.1));
+        assert!(obb.intersects_box(&near));
+        assert!(!obb.intersects_box(&far));
+        assert!(!obb.intersects_box(&off_corner));
+        assert!(!obb.intersects_box(&Box3::EMPTY));
+        assert!(obb.intersects_obb(&obb.translate(&Vector3::new(0.0, 2.5, 0.0))));
+        assert!(!obb.intersects_obb(&obb.translate(&Vector3::new(0.0, 3.0, 0.0))));
+
+        // edge to edge, only the cross product of the two edges separates these
+        let cube = Box3::new(&Vector3::new(-1.0, -1.0, -1.0), &Vector3::ONE);
+        let offset = Vector3::new(2.0 + 2.0 * 2.0f32.sqrt() + 0.1, 0.0, 0.0);
+        let edge = OBB::from_box3_and_matrix4(&cube,
+                                              &Matrix4::from_translation(&offset)
+                                                  .multiply(&Matrix4::from_rotation_y(PI / 4.0)));
+        assert!(!obb.intersects_obb(&edge));
+        assert!(obb.intersects_obb(&edge.translate(&Vector3::new(-0.2, 0.0, 0.0))));
+
+        assert!(obb.intersects_sphere(&Sphere::new(&Vector3::new(4.0, 0.0, 0.0), 0.6)));
+        assert!(!obb.intersects_sphere(&Sphere::new(&Vector3::new(4.0, 2.0, 0.0), 1.0)));
+
+        assert!(obb.intersects_plane(&Plane::new(&Vector3::X, -3.4)));
+        assert!(!obb.intersects_plane(&Plane::new(&Vector3::X, -3.5)));
+        assert!(obb.intersects_plane(&Plane::new(&Vector3::Z, 1.0)));
+
+        let hit = Triangle::new(&Vector3::new(3.3, 0.0, -5.0),
+                                &Vector3::new(3.3, 0.0, 5.0),
+                                &Vector3::new(5.0, 0.0, 0.0));
+        let miss = Triangle::new(&Vector3::new(3.5, 0.0, -5.0),
+                                 &Vector3::new(3.5, 0.0, 5.0),
+                                 &Vector3::new(5.0, 0.0, 0.0));
+        assert!(obb.intersects_triangle(&hit));
+        assert!(!obb.intersects_triangle(&miss));
+    }
+
+    #[test]
+    fn intersect_ray() {
+        let obb = diamond();
+        let ray = Ray {
+            origin: Vector3::new(6.0, 0.0, 0.0),
+            direction: Vector3::NEG_X,
+        };
+        let corner = 2.0 + 2.0f32.sqrt();
+        assert!(close(&obb.intersect_ray(&ray).unwrap(), &Vector3::new(corner, 0.0, 0.0)));
+
+        let inside = Ray { origin: Vector3::new(2.0, 0.0, 0.0), ..ray };
+        assert_eq!(obb.intersect_ray(&inside), Some(inside.origin));
+
+        let away = Ray { direction: Vector3::X, ..ray };
+        assert!(!obb.intersects_ray(&away));
+
+        let past = Ray { origin: Vector3::new(6.0, 1.5, 0.0), ..ray };
+        assert!(!obb.intersects_ray(&past));
+    }
+}
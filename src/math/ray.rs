@@ -1,6 +1,7 @@
+use std::f32::INFINITY;
 use math::*;
 
-#[derive(Debug,PartialEq)]
+#[derive(Debug,PartialEq,Copy,Clone)]
 pub struct Ray {
     pub origin: Vector3,
     pub direction: Vector3,
@@ -85,7 +86,7 @@ impl Ray {
                         let inv_det = 1.0 / det;
                         s0 *= inv_det;
                         s1 *= inv_det;
-                        0.0 * (s0 + a01 * s1 + 2.0 * b0) + s1 * (a01 * s0 + s1 + 2.0 * b1) + c
+                        s0 * (s0 + a01 * s1 + 2.0 * b0) + s1 * (a01 * s0 + s1 + 2.0 * b1) + c
                     } else {
                         // region 1
                         s1 = seg_extent;
@@ -212,6 +213,41 @@ impl Ray {
         }
     }
 
+    // how far along the ray it enters the box, 0 when it starts inside. unlike intersect_box
+    // this never picks the exit point
+    pub fn distance_to_box(&self, box3: &Box3) -> Option<f32> {
+        if box3.is_empty() {
+            return None;
+        }
+
+        let mut near = 0.0f32;
+        let mut far = INFINITY;
+
+        for axis in 0..3 {
+            let origin = *self.origin.component(axis);
+            let direction = *self.direction.component(axis);
+            let min = *box3.min.component(axis);
+            let max = *box3.max.component(axis);
+
+            if direction == 0.0 {
+                if origin < min || origin > max {
+                    return None;
+                }
+                continue;
+            }
+
+            let t1 = (min - origin) / direction;
+            let t2 = (max - origin) / direction;
+            near = near.max(t1.min(t2));
+            far = far.min(t1.max(t2));
+            if near > far {
+                return None;
+            }
+        }
+
+        Some(near)
+    }
+
     pub fn intersect_box(&self, box3: &Box3) -> Option<Vector3> {
         let invdirx = 1.0 / self.direction.x;
         let invdiry = 1.0 / self.direction.y;
@@ -308,7 +344,7 @@ impl Ray {
             None
         } else {
             let diff = self.origin.subtract(a);
-            let dd_qx_e2 = sign * self.direction.dot(&edge2.cross(&diff));
+            let dd_qx_e2 = sign * self.direction.dot(&diff.cross(&edge2));
 
             // b1 < 0, no intersection
             if dd_qx_e2 < 0.0 {
@@ -351,5 +387,77 @@ impl Ray {
 #[cfg(test)]
 mod tests {
     // use std::f32::consts::*;
-    // use math::*;
-}
\ No newline at end of file
+    use math::*;
+
+    #[test]
+    fn distance_squared_to_segment() {
+        let a = Ray {
+            origin: Vector3::new(0.0, 0.0, 5.0),
+            direction: Vector3::NEG_Z,
+        };
+
+        // passes between the ends
+        let (distance_squared, on_ray, on_segment) =
+            a.distance_squared_to_segment(&Vector3::new(-1.0, 1.0, 1.0),
+                                          &Vector3::new(1.0, 1.0, 1.0));
+        assert!((distance_squared - 1.0).abs() < 1e-6);
+        assert!(on_ray.distance_to(&Vector3::new(0.0, 0.0, 1.0)) < 1e-6);
+        assert!(on_segment.distance_to(&Vector3::new(0.0, 1.0, 1.0)) < 1e-6);
+
+        // past the end of the segment
+        let (distance_squared, _, on_segment) =
+            a.distance_squared_to_segment(&Vector3::new(1.0, 0.0, 0.0),
+                                          &Vector3::new(2.0, 0.0, 0.0));
+        assert!((distance_squared - 1.0).abs() < 1e-6);
+        assert_eq!(on_segment, Vector3::new(1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn intersect_triangle() {
+        let a = Ray {
+            origin: Vector3::new(0.2, 0.3, 5.0),
+            direction: Vector3::NEG_Z,
+        };
+        let (v0, v1, v2) = (Vector3::ZERO, Vector3::X, Vector3::Y);
+        assert_eq!(a.intersect_triangle(&v0, &v1, &v2, false),
+                   Some(Vector3::new(0.2, 0.3, 0.0)));
+        // seen from the back
+        assert_eq!(a.intersect_triangle(&v0, &v2, &v1, true), None);
+        assert_eq!(a.intersect_triangle(&v0, &v2, &v1, false),
+                   Some(Vector3::new(0.2, 0.3, 0.0)));
+
+        let b = Ray {
+            origin: Vector3::new(-0.2, 0.3, 5.0),
+            direction: Vector3::NEG_Z,
+        };
+        assert_eq!(b.intersect_triangle(&v0, &v1, &v2, false), None);
+        assert_eq!(b.intersect_triangle(&v0, &v2, &v1, false), None);
+    }
+
+    #[test]
+    fn distance_to_box() {
+        let a = Ray {
+            origin: Vector3::new(0.5, 0.5, 5.0),
+            direction: Vector3::NEG_Z,
+        };
+        let box3 = Box3::new(&Vector3::ZERO, &Vector3::ONE);
+        assert_eq!(a.distance_to_box(&box3), Some(4.0));
+        assert_eq!(a.distance_to_box(&box3.translate(&Vector3::new(0.0, 0.0, 10.0))), None);
+        assert_eq!(a.distance_to_box(&box3.translate(&Vector3::new(1.0, 0.0, 0.0))), None);
+        assert_eq!(a.distance_to_box(&Box3::EMPTY), None);
+
+        // starting inside
+        let b = Ray {
+            origin: Vector3::new(0.5, 0.5, 0.5),
+            direction: Vector3::new(1.0, 1.0, 0.0).normalized(),
+        };
+        assert_eq!(b.distance_to_box(&box3), Some(0.0));
+
+        // every slab of an empty box gives an inverted interval, which mustn't read as a hit
+        let c = Ray {
+            origin: Vector3::new(5.0, 5.0, 5.0),
+            direction: Vector3::ONE.normalized(),
+        };
+        assert_eq!(c.distance_to_box(&Box3::EMPTY), None);
+    }
+}
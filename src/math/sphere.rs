@@ -1,6 +1,7 @@
+use std::f32::EPSILON;
 use math::{Vector3, Box3, Matrix4, Plane};
 
-#[derive(Debug,PartialEq)]
+#[derive(Debug,PartialEq,Copy,Clone)]
 pub struct Sphere {
     pub center: Vector3,
     pub radius: f32,
@@ -19,6 +20,82 @@ impl Sphere {
         }
     }
 
+    // the smallest sphere around the points (Welzl's algorithm). it runs in expected linear
+    // time on the points in random order, they are shuffled the same way every time so the
+    // result doesn't change between calls
+    pub fn from_points(points: &[&Vector3]) -> Sphere {
+        let mut points: Vec<Vector3> = points.iter().map(|&p| *p).collect();
+        shuffle(&mut points);
+
+        let mut sphere = match points.first() {
+            Some(first) => Sphere::new(first, 0.0),
+            None => return Sphere::DEFAULT,
+        };
+
+        // every point that ends up outside has to be on the surface of the sphere around the
+        // ones before it, together with the outer loop's points
+        for i in 1..points.len() {
+            if encloses(&sphere, &points[i]) {
+                continue;
+            }
+            sphere = Sphere::new(&points[i], 0.0);
+
+            for j in 0..i {
+                if encloses(&sphere, &points[j]) {
+                    continue;
+                }
+                sphere = diameter(&points[i], &points[j]);
+
+                for k in 0..j {
+                    if encloses(&sphere, &points[k]) {
+                        continue;
+                    }
+                    sphere = circumscribed(&points[i], &points[j], &points[k]);
+
+                    for l in 0..k {
+                        if !encloses(&sphere, &points[l]) {
+                            let (a, b, c, d) = (&points[i], &points[j], &points[k], &points[l]);
+                            sphere = circumscribed_4(a, b, c, d);
+                        }
+                    }
+                }
+            }
+        }
+
+        sphere
+    }
+
+    // Ritter's approximation, a couple of passes over the points that usually end up within
+    // a few percent of the smallest sphere. it is then shrunk and grown again a few times to
+    // get closer
+    pub fn from_points_fast(points: &[&Vector3]) -> Sphere {
+        let first = match points.first() {
+            Some(first) => *first,
+            None => return Sphere::DEFAULT,
+        };
+
+        let farthest = |from: &Vector3| {
+            points.iter().fold(first, |best, &p| {
+                if p.distance_to_squared(from) > best.distance_to_squared(from) { p } else { best }
+            })
+        };
+        let a = farthest(first);
+        let b = farthest(a);
+        let mut best = grow(&diameter(a, b), points.iter().cloned());
+
+        let mut shuffled: Vec<&Vector3> = points.to_vec();
+        for _ in 0..8 {
+            shuffle(&mut shuffled);
+            let smaller = Sphere::new(&best.center, best.radius * 0.95);
+            let sphere = grow(&smaller, shuffled.iter().cloned());
+            if sphere.radius < best.radius {
+                best = sphere;
+            }
+        }
+
+        best
+    }
+
     pub fn empty(&self) -> bool {
         self.radius <= 0.0
     }
@@ -79,6 +156,93 @@ impl Sphere {
     }
 }
 
+// a little slack so the points the sphere was built through count as inside
+fn encloses(sphere: &Sphere, point: &Vector3) -> bool {
+    point.distance_to(&sphere.center) <= sphere.radius * (1.0 + 1e-5) + EPSILON
+}
+
+fn diameter(a: &Vector3, b: &Vector3) -> Sphere {
+    Sphere::new(&a.add(b).multiply_scalar(0.5), a.distance_to(b) * 0.5)
+}
+
+// the smallest sphere through three points, the one around the two farthest apart when they
+// are on a line
+fn circumscribed(a: &Vector3, b: &Vector3, c: &Vector3) -> Sphere {
+    let ab = b.subtract(a);
+    let ac = c.subtract(a);
+    let normal = ab.cross(&ac);
+    let denominator = 2.0 * normal.length_squared();
+
+    if denominator <= EPSILON * ab.length_squared() * ac.length_squared() {
+        let candidates = [diameter(a, b), diameter(a, c), diameter(b, c)];
+        return candidates.iter()
+            .fold(candidates[0], |best, s| if s.radius > best.radius { *s } else { best });
+    }
+
+    let offset = normal.cross(&ab)
+        .multiply_scalar(ac.length_squared())
+        .add(&ac.cross(&normal).multiply_scalar(ab.length_squared()))
+        .multiply_scalar(1.0 / denominator);
+    Sphere::new(&a.add(&offset), offset.length())
+}
+
+// the sphere through four points, when they are on a plane the smallest one through three of
+// them that holds the fourth
+fn circumscribed_4(a: &Vector3, b: &Vector3, c: &Vector3, d: &Vector3) -> Sphere {
+    let u = b.subtract(a);
+    let v = c.subtract(a);
+    let w = d.subtract(a);
+    let denominator = 2.0 * u.dot(&v.cross(&w));
+
+    if denominator.abs() <= EPSILON * u.length() * v.length() * w.length() {
+        let candidates = [circumscribed(a, b, c),
+                          circumscribed(a, b, d),
+                          circumscribed(a, c, d),
+                          circumscribed(b, c, d)];
+        let points = [a, b, c, d];
+        return candidates.iter()
+            .filter(|s| points.iter().all(|p| encloses(s, p)))
+            .fold(None, |best: Option<Sphere>, s| match best {
+                Some(best) if best.radius <= s.radius => Some(best),
+                _ => Some(*s),
+            })
+            .unwrap_or(candidates[0]);
+    }
+
+    let offset = v.cross(&w)
+        .multiply_scalar(u.length_squared())
+        .add(&w.cross(&u).multiply_scalar(v.length_squared()))
+        .add(&u.cross(&v).multiply_scalar(w.length_squared()))
+        .multiply_scalar(1.0 / denominator);
+    Sphere::new(&a.add(&offset), offset.length())
+}
+
+// moves and widens the sphere just enough to take in every point outside of it
+fn grow<'a, I: Iterator<Item = &'a Vector3>>(sphere: &Sphere, points: I) -> Sphere {
+    points.fold(*sphere, |sphere, p| {
+        let distance = p.distance_to(&sphere.center);
+        if distance <= sphere.radius {
+            sphere
+        } else {
+            let radius = (sphere.radius + distance) * 0.5;
+            let shift = (radius - sphere.radius) / distance;
+            let center = sphere.center.add(&p.subtract(&sphere.center).multiply_scalar(shift));
+            Sphere::new(&center, radius)
+        }
+    })
+}
+
+// fisher-yates with a fixed seed
+fn shuffle<T>(items: &mut [T]) {
+    let mut state: u32 = 0x9e37_79b9;
+    for i in (1..items.len()).rev() {
+        state ^= state << 13;
+        state ^= state >> 17;
+        state ^= state << 5;
+        items.swap(i, state as usize % (i + 1));
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::Sphere;
@@ -168,4 +332,55 @@ mod tests {
         let b = a.translate(&Vector3::ONE.negate());
         assert_eq!(b.center, Vector3::ZERO);
     }
-}
\ No newline at end of file
+
+    #[test]
+    pub fn from_points() {
+        let corners = [Vector3::new(-1.0, 0.0, 0.0),
+                       Vector3::new(1.0, 0.0, 0.0),
+                       Vector3::new(0.0, 2.0, 0.0)];
+        let inside = Vector3::new(0.0, 0.5, 0.0);
+        let points = [&corners[0], &inside, &corners[1], &corners[2]];
+        let a = Sphere::from_points(&points);
+        assert!(a.center.distance_to(&Vector3::new(0.0, 0.75, 0.0)) < 1e-6);
+        assert!((a.radius - 1.25).abs() < 1e-6);
+
+        // an obtuse triangle only needs its longest edge
+        let b = Sphere::from_points(&[&Vector3::ZERO,
+                                      &Vector3::new(4.0, 0.0, 0.0),
+                                      &Vector3::new(2.0, 0.5, 0.0)]);
+        assert!(b.center.distance_to(&Vector3::new(2.0, 0.0, 0.0)) < 1e-6);
+        assert!((b.radius - 2.0).abs() < 1e-6);
+
+        assert_eq!(Sphere::from_points(&[]), Sphere::DEFAULT);
+        assert_eq!(Sphere::from_points(&[&Vector3::ONE]), Sphere::new(&Vector3::ONE, 0.0));
+    }
+
+    #[test]
+    pub fn from_points_elongated() {
+        // a long thin cloud, which the box around it is a poor fit for
+        let points: Vec<Vector3> = (0..500)
+            .map(|i| {
+                let t = i as f32 * 0.37;
+                Vector3::new(t.sin() * 10.0, (t * 1.3).cos(), (t * 0.7).sin())
+            })
+            .collect();
+        let references: Vec<&Vector3> = points.iter().collect();
+
+        let exact = Sphere::from_points(&references);
+        let fast = Sphere::from_points_fast(&references);
+        let loose = Box3::from_points(&references).bounding_sphere();
+        for p in &points {
+            assert!(p.distance_to(&exact.center) <= exact.radius * (1.0 + 1e-4));
+            assert!(p.distance_to(&fast.center) <= fast.radius * (1.0 + 1e-4));
+        }
+        assert!(exact.radius <= fast.radius + 1e-4);
+        assert!(fast.radius < exact.radius * 1.05);
+        assert!(exact.radius < loose.radius);
+
+        // no point can be dropped without the sphere getting smaller, so some are on it
+        let on_surface = points.iter()
+            .filter(|p| (p.distance_to(&exact.center) - exact.radius).abs() < 1e-3)
+            .count();
+        assert!(on_surface >= 2);
+    }
+}
@@ -1,22 +1,39 @@
+use extras::{ArcLengths, Curve, FrenetFrames};
 use math::Vector3;
 
+#[derive(Debug,PartialEq,Clone)]
 pub struct Spline {
     points: Vec<Vector3>,
+    arc_lengths: ArcLengths,
 }
 
 impl Spline {
+    pub fn new(points: &[Vector3]) -> Spline {
+        Spline {
+            points: points.to_vec(),
+            arc_lengths: ArcLengths::new(),
+        }
+    }
+
+    // k runs from 0 at the first point to 1 at the last, a spline without points is a single
+    // point at the origin
     pub fn point_at(&self, k: f32) -> Vector3 {
-        let point = (self.points.len() - 1) as f32 * k;
-        let int_point = point.floor() as usize;
+        if self.points.is_empty() {
+            return Vector3::ZERO;
+        }
+
+        // neighbours past either end repeat the end point, so short splines work too
+        let last = self.points.len() - 1;
+        let point = last as f32 * k.max(0.0).min(1.0);
+        let int_point = (point.floor() as usize).min(last);
         let weight = point - (int_point as f32);
         let c = vec![
-            if int_point == 0 { int_point}else {int_point -1 },
+            int_point.saturating_sub(1),
             int_point,
-            if int_point > self.points.len() - 2 {self.points.len() -1 }else {int_point + 1},
-            if int_point > self.points.len() - 3 {self.points.len() -1 }else {int_point + 2},
+            (int_point + 1).min(last),
+            (int_point + 2).min(last),
         ];
 
-
         let point_a = self.points[c[0]];
         let point_b = self.points[c[1]];
         let point_c = self.points[c[2]];
@@ -32,6 +49,11 @@ impl Spline {
         }
     }
 
+    // see FrenetFrames::from_curve
+    pub fn compute_frenet_frames(&self, segments: usize, closed: bool) -> FrenetFrames {
+        FrenetFrames::from_curve(self, segments, closed)
+    }
+
     pub fn control_points_array(&self) -> Vec<[f32; 3]> {
         let mut coordinates: Vec<[f32; 3]> = Vec::new();
         for point in &self.points {
@@ -94,7 +116,7 @@ impl Spline {
             }
         }
 
-        Spline { points: new_points }
+        Spline::new(&new_points)
     }
 
     // Catmull-Rom
@@ -103,4 +125,109 @@ impl Spline {
         let v1 = (p3 - p1) * 0.5;
         (2.0 * (p1 - p2) + v0 + v1) * t3 + (-3.0 * (p1 - p2) - 2.0 * v0 - v1) * t2 + v0 * t + p1
     }
-}
\ No newline at end of file
+}
+
+impl Curve for Spline {
+    type Vector = Vector3;
+
+    fn point_at(&self, t: f32) -> Vector3 {
+        Spline::point_at(self, t)
+    }
+
+    fn arc_lengths(&self) -> &ArcLengths {
+        &self.arc_lengths
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use extras::Curve;
+    use geometries::TubeGeometry;
+    use math::{Spline, Vector3};
+
+    fn helix() -> Spline {
+        let points: Vec<Vector3> = (0..12)
+            .map(|i| {
+                let angle = i as f32 * 0.5;
+                Vector3::new(angle.cos(), i as f32 * 0.2, angle.sin())
+            })
+            .collect();
+        Spline::new(&points)
+    }
+
+    #[test]
+    fn tangent_at() {
+        let a = Spline::new(&[Vector3::ZERO,
+                              Vector3::new(1.0, 0.0, 0.0),
+                              Vector3::new(2.0, 0.0, 0.0),
+                              Vector3::new(3.0, 0.0, 0.0)]);
+        assert!(a.tangent_at(0.0).subtract(&Vector3::X).length() < 1e-4);
+        assert!(a.tangent_at(0.5).subtract(&Vector3::X).length() < 1e-4);
+        assert!(a.tangent_at(1.0).subtract(&Vector3::X).length() < 1e-4);
+    }
+
+    #[test]
+    fn two_points() {
+        let a = Spline::new(&[Vector3::ZERO, Vector3::Z]);
+        assert_eq!(a.point_at(0.0), Vector3::ZERO);
+        assert!(a.point_at(0.5).distance_to(&Vector3::new(0.0, 0.0, 0.5)) < 1e-5);
+        assert_eq!(a.point_at(1.0), Vector3::Z);
+        assert!(a.tangent_at(0.5).subtract(&Vector3::Z).length() < 1e-4);
+
+        let tube = TubeGeometry::new(a, 8, 0.1, 4, false).build();
+        assert_eq!(tube.vertex_count(), 9 * 5);
+    }
+
+    #[test]
+    fn out_of_range() {
+        let a = Spline::new(&[]);
+        assert_eq!(a.point_at(0.5), Vector3::ZERO);
+
+        let b = Spline::new(&[Vector3::X]);
+        assert_eq!(b.point_at(0.5), Vector3::X);
+
+        // k is clamped to the ends
+        let c = Spline::new(&[Vector3::ZERO, Vector3::X, Vector3::new(2.0, 0.0, 0.0)]);
+        assert_eq!(c.point_at(1.0 + 1e-4), c.point_at(1.0));
+        assert_eq!(c.point_at(1.5), Vector3::new(2.0, 0.0, 0.0));
+        assert_eq!(c.point_at(-0.5), Vector3::ZERO);
+    }
+
+    #[test]
+    fn compute_frenet_frames() {
+        let a = helix();
+        let frames = a.compute_frenet_frames(40, false);
+        assert_eq!(frames.tangents.len(), 41);
+
+        for i in 0..41 {
+            let t = &frames.tangents[i];
+            let n = &frames.normals[i];
+            let b = &frames.binormals[i];
+            assert!((t.length() - 1.0).abs() < 1e-4);
+            assert!((n.length() - 1.0).abs() < 1e-4);
+            assert!((b.length() - 1.0).abs() < 1e-4);
+            assert!(t.dot(n).abs() < 1e-3);
+            assert!(t.dot(b).abs() < 1e-3);
+            assert!(n.dot(b).abs() < 1e-3);
+        }
+
+        // parallel transport keeps the normal from jumping between samples
+        for i in 1..41 {
+            assert!(frames.normals[i].dot(&frames.normals[i - 1]) > 0.9);
+        }
+    }
+
+    #[test]
+    fn closed_frames() {
+        let a = helix();
+        let frames = a.compute_frenet_frames(40, true);
+        let open = a.compute_frenet_frames(40, false);
+        assert_eq!(frames.normals[0], open.normals[0]);
+
+        // the correction spreads the remaining twist evenly along the curve
+        let twist = open.normals[0].angle_to(&open.normals[40]);
+        let corrected = open.normals[40].angle_to(&frames.normals[40]);
+        assert!((twist - corrected).abs() < 1e-2);
+        assert!(frames.normals[40].dot(&frames.tangents[40]).abs() < 1e-3);
+    }
+}
@@ -1,19 +1,28 @@
 use std::f32::INFINITY;
-use math::{Vector3, Plane, Line3};
+use math::{Vector3, Plane, Line3, Box3, Sphere};
 
+#[derive(Debug,PartialEq,Copy,Clone)]
 pub struct Triangle {
-    a: Vector3,
-    b: Vector3,
-    c: Vector3,
+    pub a: Vector3,
+    pub b: Vector3,
+    pub c: Vector3,
 }
 
 impl Triangle {
-    const DEFAULT: Triangle = Triangle {
+    pub const DEFAULT: Triangle = Triangle {
         a: Vector3::ZERO,
         b: Vector3::ZERO,
         c: Vector3::ZERO,
     };
 
+    pub fn new(a: &Vector3, b: &Vector3, c: &Vector3) -> Triangle {
+        Triangle {
+            a: *a,
+            b: *b,
+            c: *c,
+        }
+    }
+
     pub fn area(&self) -> f32 {
         let v0 = self.c.subtract(&self.b);
         let v1 = self.a.subtract(&self.b);
@@ -67,7 +76,10 @@ impl Triangle {
     }
 
     pub fn contains_point(&self, point: &Vector3) -> bool {
-        self.barycentric_coordinates_from_point(point).is_some()
+        match self.barycentric_coordinates_from_point(point) {
+            Some(weights) => weights.x >= 0.0 && weights.y >= 0.0 && weights.z >= 0.0,
+            None => false,
+        }
     }
 
     pub fn closest_point_to_point(&self, point: &Vector3) -> Vector3 {
@@ -100,4 +112,243 @@ impl Triangle {
         }
 
     }
-}
\ No newline at end of file
+
+    pub fn intersects_sphere(&self, sphere: &Sphere) -> bool {
+        self.closest_point_to_point(&sphere.center).distance_to_squared(&sphere.center) <=
+        sphere.radius * sphere.radius
+    }
+
+    // separating axis test, the box axes, the triangle normal and the cross products of their
+    // edges, see Akenine-Möller's Fast 3D Triangle-Box Overlap Testing
+    pub fn intersects_box(&self, box3: &Box3) -> bool {
+        if box3.is_empty() {
+            return false;
+        }
+
+        let center = box3.center();
+        let extents = box3.max.subtract(&center);
+        let v0 = self.a.subtract(&center);
+        let v1 = self.b.subtract(&center);
+        let v2 = self.c.subtract(&center);
+        let edges = [v1.subtract(&v0), v2.subtract(&v1), v0.subtract(&v2)];
+
+        let mut axes = vec![Vector3::X, Vector3::Y, Vector3::Z, edges[0].cross(&edges[1])];
+        for edge in &edges {
+            for axis in &[Vector3::X, Vector3::Y, Vector3::Z] {
+                axes.push(axis.cross(edge));
+            }
+        }
+
+        axes.iter().all(|axis| {
+            let r = extents.x * axis.x.abs() + extents.y * axis.y.abs() + extents.z * axis.z.abs();
+            let (p0, p1, p2) = (v0.dot(axis), v1.dot(axis), v2.dot(axis));
+            (-p0.max(p1).max(p2)).max(p0.min(p1).min(p2)) <= r
+        })
+    }
+
+    pub fn intersects_triangle(&self, triangle: &Triangle) -> bool {
+        match self.planes(triangle) {
+            Planes::Apart => false,
+            Planes::Coplanar => self.coplanar_overlap(triangle),
+            Planes::Crossing(..) => self.intersect_triangle(triangle).is_some(),
+        }
+    }
+
+    // the segment two triangles cross along, see Möller's A Fast Triangle-Triangle Intersection
+    // Test. coplanar triangles that overlap give None, use intersects_triangle for those
+    pub fn intersect_triangle(&self, triangle: &Triangle) -> Option<Line3> {
+        let (d_self, d_other) = match self.planes(triangle) {
+            Planes::Crossing(d_self, d_other) => (d_self, d_other),
+            _ => return None,
+        };
+
+        // both triangles cross the line the planes meet on, where they do is an interval each
+        let direction = self.normal().cross(&triangle.normal());
+        let a = plane_crossing(self, &d_self);
+        let b = plane_crossing(triangle, &d_other);
+        let (a_min, a_max) = interval(&a, &direction);
+        let (b_min, b_max) = interval(&b, &direction);
+
+        let start = if a_min.0 >= b_min.0 { a_min } else { b_min };
+        let end = if a_max.0 <= b_max.0 { a_max } else { b_max };
+        if start.0 > end.0 {
+            None
+        } else {
+            Some(Line3::new(&start.1, &end.1))
+        }
+    }
+
+    fn planes(&self, triangle: &Triangle) -> Planes {
+        let distances = |t: &Triangle, plane: &Plane| {
+            let snap = |d: f32| if d.abs() < 1e-6 { 0.0 } else { d };
+            [snap(plane.distance_to_point(&t.a)),
+             snap(plane.distance_to_point(&t.b)),
+             snap(plane.distance_to_point(&t.c))]
+        };
+        let one_side = |d: &[f32; 3]| {
+            (d[0] > 0.0 && d[1] > 0.0 && d[2] > 0.0) || (d[0] < 0.0 && d[1] < 0.0 && d[2] < 0.0)
+        };
+
+        let d_other = distances(triangle, &self.plane());
+        if one_side(&d_other) {
+            return Planes::Apart;
+        }
+        if d_other == [0.0, 0.0, 0.0] {
+            return Planes::Coplanar;
+        }
+
+        let d_self = distances(self, &triangle.plane());
+        if one_side(&d_self) {
+            Planes::Apart
+        } else {
+            Planes::Crossing(d_self, d_other)
+        }
+    }
+
+    fn coplanar_overlap(&self, triangle: &Triangle) -> bool {
+        let edges = |t: &Triangle| {
+            [Line3::new(&t.a, &t.b), Line3::new(&t.b, &t.c), Line3::new(&t.c, &t.a)]
+        };
+
+        self.contains_point(&triangle.a) || triangle.contains_point(&self.a) ||
+        edges(self).iter().any(|e| edges(triangle).iter().any(|f| e.distance_to_line(f) < 1e-6))
+    }
+}
+
+// how two triangles sit against each other's planes, with the signed distances of each one's
+// vertices to the other's plane when they cross
+enum Planes {
+    Apart,
+    Coplanar,
+    Crossing([f32; 3], [f32; 3]),
+}
+
+// where the edges of a triangle cross a plane, given the signed distances of its vertices
+fn plane_crossing(triangle: &Triangle, distances: &[f32; 3]) -> Vec<Vector3> {
+    let vertices = [triangle.a, triangle.b, triangle.c];
+    let mut points = Vec::with_capacity(3);
+    for i in 0..3 {
+        let j = (i + 1) % 3;
+        let (di, dj) = (distances[i], distances[j]);
+        if di == 0.0 {
+            points.push(vertices[i]);
+        } else if di * dj < 0.0 {
+            points.push(vertices[i].lerp(&vertices[j], di / (di - dj)));
+        }
+    }
+    points
+}
+
+// the extremes of points along direction, with the points themselves
+fn interval(points: &[Vector3], direction: &Vector3) -> ((f32, Vector3), (f32, Vector3)) {
+    let mut min = (INFINITY, Vector3::ZERO);
+    let mut max = (-INFINITY, Vector3::ZERO);
+    for point in points {
+        let t = point.dot(direction);
+        if t < min.0 {
+            min = (t, *point);
+        }
+        if t > max.0 {
+            max = (t, *point);
+        }
+    }
+    (min, max)
+}
+
+#[cfg(test)]
+mod tests {
+    use math::{Box3, Sphere, Triangle, Vector3};
+
+    #[test]
+    fn closest_point_to_point() {
+        let a = Triangle::new(&Vector3::ZERO, &Vector3::X, &Vector3::Y);
+        assert!(a.contains_point(&Vector3::new(0.25, 0.25, 0.0)));
+        assert!(!a.contains_point(&Vector3::new(0.75, 0.75, 0.0)));
+        assert!(!a.contains_point(&Vector3::new(-0.1, 0.5, 0.0)));
+
+        // inside, above the face
+        let closest = a.closest_point_to_point(&Vector3::new(0.25, 0.25, 2.0));
+        assert!(closest.distance_to(&Vector3::new(0.25, 0.25, 0.0)) < 1e-6);
+        // past an edge and past a corner
+        let closest = a.closest_point_to_point(&Vector3::new(1.0, 1.0, 1.0));
+        assert!(closest.distance_to(&Vector3::new(0.5, 0.5, 0.0)) < 1e-6);
+        let closest = a.closest_point_to_point(&Vector3::new(2.0, -1.0, 0.0));
+        assert!(closest.distance_to(&Vector3::X) < 1e-6);
+    }
+
+    #[test]
+    fn intersects_box_sphere() {
+        let a = Triangle::new(&Vector3::ZERO,
+                              &Vector3::new(2.0, 0.0, 0.0),
+                              &Vector3::new(0.0, 2.0, 0.0));
+
+        // the box sits over the hypotenuse, inside the triangle's bounds but past its face
+        let past = Box3::new(&Vector3::new(1.2, 1.2, -0.1), &Vector3::new(1.5, 1.5, 0.1));
+        let on = Box3::new(&Vector3::new(0.8, 0.8, -0.1), &Vector3::new(1.5, 1.5, 0.1));
+        let above = Box3::new(&Vector3::new(0.1, 0.1, 0.2), &Vector3::new(0.5, 0.5, 0.4));
+        assert!(!a.intersects_box(&past));
+        assert!(a.intersects_box(&on));
+        assert!(!a.intersects_box(&above));
+        assert!(a.intersects_box(&above.translate(&Vector3::new(0.0, 0.0, -0.3))));
+        assert!(!a.intersects_box(&Box3::EMPTY));
+
+        assert!(a.intersects_sphere(&Sphere::new(&Vector3::new(0.5, 0.5, 0.5), 0.5)));
+        assert!(!a.intersects_sphere(&Sphere::new(&Vector3::new(0.5, 0.5, 0.5), 0.45)));
+        assert!(!a.intersects_sphere(&Sphere::new(&Vector3::new(1.5, 1.5, 0.0), 0.7)));
+        assert!(a.intersects_sphere(&Sphere::new(&Vector3::new(1.5, 1.5, 0.0), 0.75)));
+    }
+
+    #[test]
+    fn intersect_triangle() {
+        let a = Triangle::new(&Vector3::ZERO,
+                              &Vector3::new(2.0, 0.0, 0.0),
+                              &Vector3::new(0.0, 2.0, 0.0));
+
+        // standing up through a, crossing it along y = 0.5
+        let b = Triangle::new(&Vector3::new(-1.0, 0.5, -1.0),
+                              &Vector3::new(3.0, 0.5, -1.0),
+                              &Vector3::new(1.0, 0.5, 1.0));
+        let segment = a.intersect_triangle(&b).unwrap();
+        let (min, max) = if segment.start.x < segment.end.x {
+            (segment.start, segment.end)
+        } else {
+            (segment.end, segment.start)
+        };
+        assert!(min.distance_to(&Vector3::new(0.0, 0.5, 0.0)) < 1e-6);
+        assert!(max.distance_to(&Vector3::new(1.5, 0.5, 0.0)) < 1e-6);
+        assert!(a.intersects_triangle(&b));
+        assert!(b.intersects_triangle(&a));
+
+        // crosses a's plane, but outside a
+        let c = Triangle::new(&Vector3::new(2.0, 2.0, -1.0),
+                              &Vector3::new(3.0, 2.0, -1.0),
+                              &Vector3::new(2.5, 2.0, 1.0));
+        assert!(a.intersect_triangle(&c).is_none());
+        assert!(!a.intersects_triangle(&c));
+
+        // entirely above
+        let d = Triangle::new(&Vector3::new(0.0, 0.0, 1.0),
+                              &Vector3::new(1.0, 0.0, 2.0),
+                              &Vector3::new(0.0, 1.0, 1.0));
+        assert!(!a.intersects_triangle(&d));
+
+        // touching at a corner
+        let e = Triangle::new(&Vector3::new(0.5, 0.5, 0.0),
+                              &Vector3::new(0.5, 0.5, 1.0),
+                              &Vector3::new(-0.5, 0.5, 1.0));
+        let touch = a.intersect_triangle(&e).unwrap();
+        assert!(touch.start.distance_to(&Vector3::new(0.5, 0.5, 0.0)) < 1e-6);
+        assert!(touch.end.distance_to(&touch.start) < 1e-6);
+
+        // coplanar, overlapping and apart
+        let f = Triangle::new(&Vector3::new(1.0, 1.0, 0.0),
+                              &Vector3::new(3.0, 1.0, 0.0),
+                              &Vector3::new(1.0, 3.0, 0.0));
+        let g = Triangle::new(&Vector3::new(2.0, 2.0, 0.0),
+                              &Vector3::new(4.0, 2.0, 0.0),
+                              &Vector3::new(2.0, 4.0, 0.0));
+        assert!(a.intersects_triangle(&f));
+        assert!(a.intersect_triangle(&f).is_none());
+        assert!(!a.intersects_triangle(&g));
+    }
+}
@@ -0,0 +1,100 @@
+use math::Vector3;
+
+#[derive(Debug,PartialEq,Copy,Clone)]
+pub struct Vector4 {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub w: f32,
+}
+
+impl Vector4 {
+    pub const ZERO: Vector4 = Vector4 {
+        x: 0.0,
+        y: 0.0,
+        z: 0.0,
+        w: 0.0,
+    };
+
+    pub fn new(x: f32, y: f32, z: f32, w: f32) -> Vector4 {
+        Vector4 {
+            x: x,
+            y: y,
+            z: z,
+            w: w,
+        }
+    }
+
+    pub fn from_vector3(v: &Vector3, w: f32) -> Vector4 {
+        Vector4::new(v.x, v.y, v.z, w)
+    }
+
+    pub fn add(&self, v: &Vector4) -> Vector4 {
+        Vector4 {
+            x: self.x + v.x,
+            y: self.y + v.y,
+            z: self.z + v.z,
+            w: self.w + v.w,
+        }
+    }
+
+    pub fn subtract(&self, v: &Vector4) -> Vector4 {
+        Vector4 {
+            x: self.x - v.x,
+            y: self.y - v.y,
+            z: self.z - v.z,
+            w: self.w - v.w,
+        }
+    }
+
+    pub fn multiply_scalar(&self, scalar: f32) -> Vector4 {
+        Vector4 {
+            x: self.x * scalar,
+            y: self.y * scalar,
+            z: self.z * scalar,
+            w: self.w * scalar,
+        }
+    }
+
+    pub fn divide_scalar(&self, scalar: f32) -> Vector4 {
+        self.multiply_scalar(1.0 / scalar)
+    }
+
+    pub fn dot(&self, v: &Vector4) -> f32 {
+        self.x * v.x + self.y * v.y + self.z * v.z + self.w * v.w
+    }
+
+    pub fn length(&self) -> f32 {
+        self.dot(self).sqrt()
+    }
+
+    pub fn lerp(&self, v: &Vector4, alpha: f32) -> Vector4 {
+        self.add(&v.subtract(self).multiply_scalar(alpha))
+    }
+
+    // drops w
+    pub fn xyz(&self) -> Vector3 {
+        Vector3::new(self.x, self.y, self.z)
+    }
+
+    pub fn to_vec(&self) -> Vec<f32> {
+        vec![self.x, self.y, self.z, self.w]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use math::{Vector3, Vector4};
+
+    #[test]
+    fn add() {
+        let a = Vector4::new(1.0, 2.0, 3.0, 4.0);
+        let b = Vector4::from_vector3(&Vector3::new(-1.0, -2.0, -3.0), 1.0);
+        assert_eq!(a.add(&b), Vector4::new(0.0, 0.0, 0.0, 5.0));
+        assert_eq!(a.subtract(&a), Vector4::ZERO);
+        assert_eq!(a.multiply_scalar(2.0).divide_scalar(4.0),
+                   Vector4::new(0.5, 1.0, 1.5, 2.0));
+        assert_eq!(a.xyz(), Vector3::new(1.0, 2.0, 3.0));
+        assert_eq!(Vector4::new(1.0, 1.0, 1.0, 1.0).length(), 2.0);
+    }
+}